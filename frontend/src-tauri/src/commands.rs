@@ -1,13 +1,33 @@
-use crate::doc_state::{AppState, VectorObject};
+use crate::context_pool;
+use crate::count_markups::{self, SearchCounts};
+use crate::disk_cache::{self, PruneReport};
+use crate::doc_state::AppState;
 use crate::geometry::{calculate_aabb, FrontendPoint as GeoPoint};
-use crate::mupdf_wrapper::MuPdfDoc;
+use crate::mupdf_wrapper::{LayerInfo, MuPdfDoc};
+use crate::navigation::{OutlineEntry, PageLink};
+use crate::page_text;
+use crate::page_info::{self, PageInfo};
+use crate::render_cache::RenderCacheStats;
+use crate::render_options::{RenderMode, RenderOptions};
+use crate::scale_bar::{self, ScaleBar};
+use crate::scale_detection::{self, PageScaleDetection};
+use crate::search::{
+    self, CancelToken, Matcher, SearchDoneEvent, SearchMatch, SearchOptions, SearchPageEvent,
+    SearchTally,
+};
+use crate::title_block::{self, SheetInfo};
 use crate::ingestion_service::IngestionService;
-use rstar::RTree;
+use crate::snap::{self, SnapCandidate};
+use crate::spatial::{self, PageIndex, VectorGeometry};
+use crate::text_index::{self, IndexHit, TextIndex};
+use crate::structured_text::StructuredPage;
+use crate::viewport_buffer;
 use serde::Deserialize;
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use tauri::State;
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(serde::Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
 pub struct Point {
     pub x: f32,
     pub y: f32,
@@ -33,13 +53,16 @@ pub struct LegendData {
     pub size: Size,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(serde::Serialize, Deserialize, Debug)]
 pub struct MarkupData {
     pub id: String,
     #[serde(rename = "type")]
     pub tool_type: String,
     pub paths: Vec<Vec<Point>>,
     pub properties: serde_json::Value,
+    /// Page the markup belongs to, as the store keeps it (`{doc_id}:{page_idx}`)
+    #[serde(rename = "pageId", default, skip_serializing_if = "Option::is_none")]
+    pub page_id: Option<String>,
 }
 
 fn parse_color(hex: &str) -> (f32, f32, f32) {
@@ -511,78 +534,368 @@ pub async fn open_file(
     })?;
     let page_count = doc.get_page_count();
 
-    // For now, reset spatial index (this needs to be per-page or per-doc eventually)
-    // But since R-Tree is global in AppState currently, we might have collisions if we don't separate it.
-    // Ideally, spatial_index should also be keyed by document ID or page ID.
-    // For this step, let's keep it global but acknowledge the limitation (filtering happens at query time maybe?).
-    // Actually, the current R-Tree implementation is just mocked vectors.
-    // Let's leave R-Tree alone for now as it's not the primary issue.
-
-    let mut tree = state
-        .spatial_index
-        .write()
-        .map_err(|_| "Failed to lock tree")?;
-    *tree = RTree::new();
-
-    // Mock vectors
-    let mut vectors = Vec::new();
-    for i in 0..1000 {
-        let x = (i as f64) * 10.0;
-        let y = (i as f64) * 10.0;
-        let rect = rstar::primitives::Rectangle::from_corners([x, y], [x + 5.0, y + 5.0]);
-        vectors.push(VectorObject { id: i, geom: rect });
-    }
-    *tree = RTree::bulk_load(vectors);
+    // Content hash keys the on-disk render cache; without it renders are just not persisted
+    let hash = disk_cache::hash_file(std::path::Path::new(&path))
+        .map_err(|e| println!("COMMAND: Failed to hash {}: {}", path, e))
+        .ok();
 
     let mut docs = state.documents.write().map_err(|_| "Failed to lock docs")?;
+    // Re-opening an id replaces the document, so indexed linework and cached
+    // renders are stale. Both are rebuilt lazily on the next request.
+    state.spatial_index.evict_document(&id);
+    state.render_cache.evict_document(&id);
+    if let Ok(mut hashes) = state.content_hashes.write() {
+        match hash {
+            Some(hash) => hashes.insert(id.clone(), hash),
+            None => hashes.remove(&id),
+        };
+    }
     docs.insert(id, doc);
 
     Ok(page_count)
 }
 
+/// Drop a document removed from `documents` and delete any temp file
+/// extracted for it.
+///
+/// `doc` is dropped before the temp file is deleted so the MuPDF file handle
+/// is closed first (Windows refuses to delete open files).
+fn release_document(state: &AppState, id: &str, doc: Option<MuPdfDoc>) {
+    drop(doc);
+    if let Ok(mut hashes) = state.content_hashes.write() {
+        hashes.remove(id);
+    }
+
+    let temp_file = state
+        .temp_files
+        .write()
+        .ok()
+        .and_then(|mut temp_files| temp_files.remove(id));
+    if let Some(path) = temp_file {
+        crate::project_io::remove_temp_file(&path);
+    }
+}
+
+/// Close a document and release its MuPDF context.
+///
+/// # Returns
+/// * `true` if the document was open
+#[tauri::command]
+pub fn close_document(id: String, state: State<'_, AppState>) -> Result<bool, String> {
+    println!("COMMAND: close_document id={}", id);
+    let doc = {
+        let mut docs = state.documents.write().map_err(|_| "Failed to lock docs")?;
+        state.spatial_index.evict_document(&id);
+        state.render_cache.evict_document(&id);
+        docs.remove(&id)
+    };
+    let was_open = doc.is_some();
+    release_document(&state, &id, doc);
+    Ok(was_open)
+}
+
+/// Close every open document, e.g. before switching projects.
+///
+/// # Returns
+/// * Number of documents that were open
+#[tauri::command]
+pub fn close_all_documents(state: State<'_, AppState>) -> Result<usize, String> {
+    let docs: Vec<(String, MuPdfDoc)> = {
+        let mut docs = state.documents.write().map_err(|_| "Failed to lock docs")?;
+        for id in docs.keys() {
+            state.spatial_index.evict_document(id);
+        }
+        state.render_cache.clear();
+        docs.drain().collect()
+    };
+    println!("COMMAND: close_all_documents ({} open)", docs.len());
+    let count = docs.len();
+    for (id, doc) in docs {
+        release_document(&state, &id, Some(doc));
+    }
+
+    // Temp files extracted for documents that were never opened
+    let orphaned: Vec<String> = state
+        .temp_files
+        .read()
+        .map(|temp_files| temp_files.keys().cloned().collect())
+        .unwrap_or_default();
+    for id in orphaned {
+        release_document(&state, &id, None);
+    }
+    Ok(count)
+}
+
 #[derive(serde::Serialize)]
 pub struct ViewportResult {
+    /// Straight segments as [x1, y1, x2, y2]
     lines: Vec<[f64; 4]>,
+    /// Cubic beziers as [x0, y0, cx1, cy1, cx2, cy2, x3, y3]
+    curves: Vec<[f64; 8]>,
+}
+
+/// Get the spatial index of a page, extracting its linework on first use.
+fn page_vector_index(state: &AppState, id: &str, page: i32) -> Result<PageIndex, String> {
+    state.spatial_index.get_or_build(id, page, || {
+        let docs = state.documents.read().map_err(|_| "Failed to lock docs")?;
+        let doc = docs.get(id).ok_or("Document not found")?;
+        let segments = doc.extract_vectors(page).map_err(|e| e.to_string())?;
+        println!("COMMAND: Indexed {} vector segments for {} page {}", segments.len(), id, page);
+        Ok(spatial::vectors_from_segments(&segments, RENDER_ZOOM as f64, 0))
+    })
 }
 
 #[tauri::command]
 pub fn get_viewport_vectors(
-    _page: i32,
+    id: String,
+    page: i32,
     min_x: f64,
     min_y: f64,
     max_x: f64,
     max_y: f64,
     state: State<'_, AppState>,
 ) -> Result<ViewportResult, String> {
-    let tree = state
-        .spatial_index
-        .read()
-        .map_err(|_| "Failed to lock tree")?;
+    let tree = page_vector_index(&state, &id, page)?;
     let aabb = rstar::AABB::from_corners([min_x, min_y], [max_x, max_y]);
     let query_iter = tree.locate_in_envelope_intersecting(&aabb);
 
     let mut lines = Vec::new();
+    let mut curves = Vec::new();
     for obj in query_iter {
-        let lower = obj.geom.lower();
-        let upper = obj.geom.upper();
-        lines.push([lower[0], lower[1], upper[0], upper[1]]);
+        match obj.geom {
+            VectorGeometry::Line(a, b) => lines.push([a[0], a[1], b[0], b[1]]),
+            VectorGeometry::Curve([p0, c1, c2, p3]) => {
+                curves.push([p0[0], p0[1], c1[0], c1[1], c2[0], c2[1], p3[0], p3[1]])
+            }
+        }
     }
-    Ok(ViewportResult { lines })
+    Ok(ViewportResult { lines, curves })
 }
 
+/// Same query as `get_viewport_vectors`, returned as a `ViewportResponse`
+/// FlatBuffer (see `schemas/schema.fbs`) instead of JSON.
+///
+/// The bytes arrive in the webview as an `ArrayBuffer` and are fed straight
+/// to the wasm `SnapIndex`.
+#[tauri::command]
+pub fn get_viewport_buffer(
+    id: String,
+    page: i32,
+    min_x: f64,
+    min_y: f64,
+    max_x: f64,
+    max_y: f64,
+    state: State<'_, AppState>,
+) -> Result<tauri::ipc::Response, String> {
+    let tree = page_vector_index(&state, &id, page)?;
+    let aabb = rstar::AABB::from_corners([min_x, min_y], [max_x, max_y]);
+    let bytes = viewport_buffer::encode_viewport(page, tree.locate_in_envelope_intersecting(&aabb));
+    Ok(tauri::ipc::Response::new(bytes))
+}
+
+/// Snap the cursor to the page's own linework.
+///
+/// Returns the best candidate within `radius` (frontend units): endpoint,
+/// intersection, midpoint, perpendicular foot from `anchor` (the previously
+/// placed vertex, if any) or nearest point on a segment.
+#[tauri::command]
+pub fn snap_to_geometry(
+    id: String,
+    page: i32,
+    x: f64,
+    y: f64,
+    radius: f64,
+    anchor: Option<Point>,
+    state: State<'_, AppState>,
+) -> Result<Option<SnapCandidate>, String> {
+    let tree = page_vector_index(&state, &id, page)?;
+    let anchor = anchor.map(|p| [p.x as f64, p.y as f64]);
+    Ok(snap::find_snap(&tree, [x, y], radius, anchor))
+}
+
+/// Render a page to PNG bytes.
+///
+/// # Arguments
+/// * `mode` - Optional colour mode: grayscale, faded, inverted or monochrome
+/// * `intensity` - Fade percentage for `faded` (default 60)
+/// * `threshold` - Luminance cut-off 0-255 for `monochrome` (default 160)
+/// * `hidden_layers` - Layer indices (from `list_layers`) to hide
 #[tauri::command]
 pub async fn get_page_image_bytes(
     id: String,
     page_number: i32,
     zoom: f32,
+    mode: Option<String>,
+    intensity: Option<u8>,
+    threshold: Option<u8>,
+    hidden_layers: Option<Vec<i32>>,
     state: State<'_, AppState>,
 ) -> Result<Vec<u8>, String> {
-    let docs = state.documents.read().map_err(|_| "Failed to lock docs")?;
+    let mut options = RenderOptions::new(zoom).with_hidden_layers(hidden_layers.unwrap_or_default());
+    if let Some(mode) = mode {
+        options.mode = RenderMode::from_query(&mode, intensity, threshold)
+            .ok_or_else(|| format!("Unknown render mode: {}", mode))?;
+    }
+    let image = state.render(&id, page_number, &options)?;
+    Ok(image.bytes.clone())
+}
+
+/// Get render cache size and hit/miss counters.
+#[tauri::command]
+pub fn get_render_cache_stats(state: State<'_, AppState>) -> RenderCacheStats {
+    state.render_cache.stats()
+}
+
+/// Set the render cache memory budget, evicting renders if it shrank.
+#[tauri::command]
+pub fn set_render_cache_budget(
+    budget_bytes: usize,
+    state: State<'_, AppState>,
+) -> RenderCacheStats {
+    println!("COMMAND: set_render_cache_budget {} bytes", budget_bytes);
+    state.render_cache.set_budget(budget_bytes);
+    state.render_cache.stats()
+}
+
+/// Drop every cached render.
+#[tauri::command]
+pub fn clear_render_cache(state: State<'_, AppState>) {
+    state.render_cache.clear();
+}
+
+/// Get the media box, crop box, rotation, UserUnit and label of every page.
+/// `width`/`height` are the displayed size in points with UserUnit applied;
+/// multiply by the render zoom for pixels.
+#[tauri::command]
+pub async fn get_page_info(
+    id: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<PageInfo>, String> {
+    let docs = state.documents.read().map_err(|e| e.to_string())?;
+    let doc = docs.get(&id).ok_or("Document not found")?;
+
+    let page_count = doc.get_page_count().max(0) as usize;
+    context_pool::parallel_map(page_count, doc.worker_capacity(), |page_idx| {
+        doc.page_info(page_idx as i32).map_err(|e| e.to_string())
+    })
+    .into_iter()
+    .collect()
+}
+
+/// Read the sheet number and title of every page from its title block,
+/// falling back to the PDF page label and outline bookmarks, so pages can be
+/// named after sheets.
+#[tauri::command]
+pub async fn extract_sheet_names(
+    id: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<SheetInfo>, String> {
+    let docs = state.documents.read().map_err(|e| e.to_string())?;
     let doc = docs.get(&id).ok_or("Document not found")?;
-    let bytes = doc
-        .render_page_to_buffer(page_number, zoom)
+
+    // A broken outline only costs the bookmark fallback
+    let bookmarks = title_block::page_bookmarks(&doc.outline().unwrap_or_default());
+
+    let page_count = doc.get_page_count().max(0) as usize;
+    let sheets = context_pool::parallel_map(page_count, doc.worker_capacity(), |page_idx| {
+        let page_idx = page_idx as i32;
+        let lines = doc.extract_lines(page_idx).map_err(|e| e.to_string())?;
+        let info = doc.page_info(page_idx).map_err(|e| e.to_string())?;
+        let sheet = title_block::analyse(page_idx, &lines, info.width, info.height, info.label.as_deref());
+        Ok(title_block::apply_bookmark(sheet, bookmarks.get(&page_idx).map(String::as_str)))
+    })
+    .into_iter()
+    .collect::<Result<Vec<_>, String>>()?;
+
+    let named = sheets.iter().filter(|s| s.name.is_some()).count();
+    println!("COMMAND: extract_sheet_names {} -> named {}/{} pages", id, named, sheets.len());
+    Ok(sheets)
+}
+
+/// List the optional content groups (PDF layers) of a document. Pass indices
+/// to `get_page_image_bytes` or as `hide=` on `protakeoff://` URLs to render
+/// without them.
+#[tauri::command]
+pub async fn list_layers(
+    id: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<LayerInfo>, String> {
+    let docs = state.documents.read().map_err(|e| e.to_string())?;
+    let doc = docs.get(&id).ok_or("Document not found")?;
+    let layers = doc.list_layers().map_err(|e| e.to_string())?;
+    println!("COMMAND: list_layers {} -> {} layers", id, layers.len());
+    Ok(layers)
+}
+
+/// The document outline (bookmarks) as a tree, with target positions in
+/// frontend coordinates.
+#[tauri::command]
+pub async fn get_outline(
+    id: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<OutlineEntry>, String> {
+    let docs = state.documents.read().map_err(|e| e.to_string())?;
+    let doc = docs.get(&id).ok_or("Document not found")?;
+    let mut outline = doc.outline().map_err(|e| e.to_string())?;
+    for entry in &mut outline {
+        entry.scale(RENDER_ZOOM);
+    }
+    println!("COMMAND: get_outline {} -> {} top-level entries", id, outline.len());
+    Ok(outline)
+}
+
+/// Link annotations of a page (internal jumps and web links), with areas and
+/// targets in frontend coordinates.
+#[tauri::command]
+pub async fn get_page_links(
+    id: String,
+    page_idx: i32,
+    state: State<'_, AppState>,
+) -> Result<Vec<PageLink>, String> {
+    let docs = state.documents.read().map_err(|e| e.to_string())?;
+    let doc = docs.get(&id).ok_or("Document not found")?;
+    let mut links = doc.page_links(page_idx).map_err(|e| e.to_string())?;
+    for link in &mut links {
+        link.scale(RENDER_ZOOM);
+    }
+    Ok(links)
+}
+
+/// Number of MuPDF workers (cloned contexts) each newly opened document gets.
+#[tauri::command]
+pub fn get_render_workers() -> usize {
+    context_pool::worker_count()
+}
+
+/// Set the number of MuPDF workers per document; `None` restores the CPU-based
+/// default. Applies to documents opened afterwards. Returns the effective count.
+#[tauri::command]
+pub fn set_render_workers(count: Option<usize>) -> usize {
+    let count = context_pool::set_worker_count(count.map(|c| c.max(1)));
+    println!("COMMAND: set_render_workers -> {}", count);
+    count
+}
+
+/// Prune the on-disk render cache.
+///
+/// # Arguments
+/// * `max_age_days` - Remove renders not used for this many days (default 30)
+/// * `max_bytes` - Then trim least recently used renders down to this size (default 1 GB)
+#[tauri::command]
+pub async fn prune_disk_cache(
+    max_age_days: Option<u64>,
+    max_bytes: Option<u64>,
+    state: State<'_, AppState>,
+) -> Result<PruneReport, String> {
+    let max_age = max_age_days
+        .map(|days| std::time::Duration::from_secs(days * 24 * 60 * 60))
+        .unwrap_or(disk_cache::DEFAULT_MAX_AGE);
+    let report = state
+        .disk_cache
+        .prune(max_age, max_bytes.unwrap_or(disk_cache::DEFAULT_MAX_BYTES))
         .map_err(|e| e.to_string())?;
-    Ok(bytes)
+    println!("COMMAND: prune_disk_cache {:?}", report);
+    Ok(report)
 }
 
 /// Generate a small thumbnail for a PDF page and return it as base64-encoded PNG.
@@ -596,20 +909,15 @@ pub async fn generate_page_thumbnail(
 ) -> Result<String, String> {
     use base64::{engine::general_purpose::STANDARD, Engine as _};
 
-    let docs = state.documents.read().map_err(|_| "Failed to lock docs")?;
-    let doc = docs.get(&id).ok_or("Document not found")?;
-
     // Fixed low zoom for thumbnails (produces ~100-150px wide images for standard PDFs)
     // Standard US Letter at 72dpi = 612x792pt -> at 0.15 zoom = ~92x119px
     let zoom = 0.15_f32;
 
-    // Render at low zoom
-    let bytes = doc
-        .render_page_to_buffer(page_number, zoom)
-        .map_err(|e| e.to_string())?;
+    // Render at low zoom (shares memory and disk cache entries with the sidebar's protocol thumbnails)
+    let image = state.render_page(&id, page_number, zoom)?;
 
-    // Encode as base64 for embedding in JSON/project file
-    let base64_str = STANDARD.encode(&bytes);
+    // Encode as base64 for embedding in JSON
+    let base64_str = STANDARD.encode(&image.bytes);
 
     Ok(base64_str)
 }
@@ -641,6 +949,22 @@ pub async fn extract_page_text(
     doc.extract_text(page_idx).map_err(|e| e.to_string())
 }
 
+/// Extract the text of a page as blocks, lines and words with bounding boxes,
+/// font name, size and rotation, in the same coordinates as search hits.
+#[tauri::command]
+pub async fn extract_structured_text(
+    id: String,
+    page_idx: i32,
+    state: State<'_, AppState>,
+) -> Result<StructuredPage, String> {
+    let docs = state.documents.read().map_err(|e| e.to_string())?;
+    let doc = docs.get(&id).ok_or("Document not found")?;
+
+    let mut page = doc.structured_text(page_idx).map_err(|e| e.to_string())?;
+    page.scale(RENDER_ZOOM);
+    Ok(page)
+}
+
 // Match the RENDER_ZOOM from frontend/src/utils/scales.ts
 const RENDER_ZOOM: f32 = 1.5;
 
@@ -655,18 +979,154 @@ fn scale_hit(hit: &mut crate::mupdf_wrapper::SearchHit) {
     hit.ll.1 *= RENDER_ZOOM;
 }
 
-/// Search for text on a specific page
+/// Corners of a search hit quad (already scaled to page pixels) as a box.
+fn hit_bounds(hit: &crate::mupdf_wrapper::SearchHit) -> [f32; 4] {
+    let xs = [hit.ul.0, hit.ur.0, hit.lr.0, hit.ll.0];
+    let ys = [hit.ul.1, hit.ur.1, hit.lr.1, hit.ll.1];
+    [
+        xs.iter().copied().fold(f32::INFINITY, f32::min),
+        ys.iter().copied().fold(f32::INFINITY, f32::min),
+        xs.iter().copied().fold(f32::NEG_INFINITY, f32::max),
+        ys.iter().copied().fold(f32::NEG_INFINITY, f32::max),
+    ]
+}
+
+fn detect_scales_on_page(
+    doc: &MuPdfDoc,
+    page_idx: i32,
+    region: Option<[f32; 4]>,
+) -> Result<PageScaleDetection, String> {
+    let text = doc.extract_text(page_idx).map_err(|e| e.to_string())?;
+    // Renders are 72 points per paper inch even on UserUnit pages
+    let pixels_per_inch = (page_info::POINTS_PER_INCH * RENDER_ZOOM) as f64;
+    let mut notations = scale_detection::find_notations(&text, pixels_per_inch);
+
+    // Locate each notation; repeats of the same text take successive hits
+    let mut located: HashMap<String, Vec<[f32; 4]>> = HashMap::new();
+    for notation in &mut notations {
+        let hits = match located.entry(notation.text.clone()) {
+            std::collections::hash_map::Entry::Occupied(entry) => entry.into_mut(),
+            std::collections::hash_map::Entry::Vacant(entry) => {
+                let mut hits = doc
+                    .search_page(page_idx, &notation.text)
+                    .map_err(|e| format!("Failed to locate scales on page {}: {}", page_idx, e))?;
+                hits.iter_mut().for_each(scale_hit);
+                entry.insert(hits.iter().rev().map(hit_bounds).collect())
+            }
+        };
+        notation.bounds = hits.pop();
+    }
+
+    Ok(PageScaleDetection {
+        page_idx,
+        not_to_scale: scale_detection::is_not_to_scale(&text),
+        proposed: scale_detection::propose(&notations, region),
+        notations,
+    })
+}
+
+/// Detect drawing scale notations (`1/4" = 1'-0"`, `1" = 20'`, `1:100`) and
+/// propose a pixels-per-foot value per page.
+///
+/// # Arguments
+/// * `page_idx` - Analyse a single page instead of the whole document
+/// * `region` - Viewport `[x0, y0, x1, y1]` in page pixels; notations inside
+///   it are preferred when proposing (only meaningful with `page_idx`)
+#[tauri::command]
+pub async fn detect_page_scales(
+    id: String,
+    page_idx: Option<i32>,
+    region: Option<[f32; 4]>,
+    state: State<'_, AppState>,
+) -> Result<Vec<PageScaleDetection>, String> {
+    let docs = state.documents.read().map_err(|e| e.to_string())?;
+    let doc = docs.get(&id).ok_or("Document not found")?;
+
+    let pages: Vec<i32> = match page_idx {
+        Some(page) => vec![page],
+        None => (0..doc.get_page_count()).collect(),
+    };
+    let results = context_pool::parallel_map(pages.len(), doc.worker_capacity(), |i| {
+        detect_scales_on_page(doc, pages[i], region)
+    })
+    .into_iter()
+    .collect::<Result<Vec<_>, String>>()?;
+
+    let found = results.iter().filter(|r| r.proposed.is_some()).count();
+    println!(
+        "COMMAND: detect_page_scales {} -> scale found on {}/{} pages",
+        id,
+        found,
+        results.len()
+    );
+    Ok(results)
+}
+
+/// Detect graphic scale bars on a page from its linework and the numbers
+/// printed next to their ticks. Each bar carries a `PageCalibration`-shaped
+/// calibration; the best bar comes first.
+#[tauri::command]
+pub async fn detect_scale_bars(
+    id: String,
+    page_idx: i32,
+    state: State<'_, AppState>,
+) -> Result<Vec<ScaleBar>, String> {
+    let tree = page_vector_index(&state, &id, page_idx)?;
+    let lines: Vec<([f64; 2], [f64; 2])> = tree
+        .iter()
+        .filter_map(|obj| match obj.geom {
+            VectorGeometry::Line(a, b) => Some((a, b)),
+            VectorGeometry::Curve(_) => None,
+        })
+        .collect();
+
+    let labels = {
+        let docs = state.documents.read().map_err(|e| e.to_string())?;
+        let doc = docs.get(&id).ok_or("Document not found")?;
+        let text = doc.extract_text(page_idx).map_err(|e| e.to_string())?;
+        let tokens = scale_bar::label_tokens(&text);
+        let hits = context_pool::parallel_map(tokens.len(), doc.worker_capacity(), |i| {
+            let mut hits = doc.search_page(page_idx, &tokens[i].text).unwrap_or_default();
+            hits.iter_mut().for_each(scale_hit);
+            hits.iter()
+                .map(|hit| hit_bounds(hit).map(f64::from))
+                .collect::<Vec<_>>()
+        });
+        scale_bar::resolve_labels(&tokens.into_iter().zip(hits).collect::<Vec<_>>())
+    };
+
+    let bars = scale_bar::detect(&lines, &labels, RENDER_ZOOM as f64);
+    println!(
+        "COMMAND: detect_scale_bars {} page {} -> {} bars ({} lines, {} labels)",
+        id,
+        page_idx,
+        bars.len(),
+        lines.len(),
+        labels.len()
+    );
+    Ok(bars)
+}
+
+/// Search for text on a specific page. `options` enable case-sensitive,
+/// whole-word and regex matching.
 #[tauri::command]
 pub async fn search_page(
     id: String,
     page_idx: i32,
     query: String,
+    options: Option<SearchOptions>,
     state: State<'_, AppState>,
 ) -> Result<Vec<crate::mupdf_wrapper::SearchHit>, String> {
     let docs = state.documents.read().map_err(|e| e.to_string())?;
     let doc = docs.get(&id).ok_or("Document not found")?;
 
-    let mut hits = doc.search_page(page_idx, &query).map_err(|e| e.to_string())?;
+    let matcher = Matcher::new(&query, &options.unwrap_or_default())?;
+    let mut hits: Vec<_> = doc
+        .search_matches(page_idx, &matcher)
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .flat_map(|found| found.quads)
+        .collect();
     
     // Scale hits to match frontend rendering
     for hit in &mut hits {
@@ -676,33 +1136,375 @@ pub async fn search_page(
     Ok(hits)
 }
 
-/// Search for text across all pages in a document
+/// Search for text across all pages in a document, with the same `options` as
+/// `search_page`. Every hit is returned; with a `search_id`, each page's hits
+/// are also streamed as `search-page` events while the search runs, followed
+/// by `search-done`, and the search can be stopped with `cancel_search`
+/// (returning the pages found so far). With `tally`, hit counts per page and
+/// per distinct matched string are returned instead of the hits.
 #[tauri::command]
 pub async fn search_document(
+    app: tauri::AppHandle,
     id: String,
     query: String,
+    options: Option<SearchOptions>,
+    search_id: Option<String>,
+    tally: Option<bool>,
     state: State<'_, AppState>,
-) -> Result<Vec<PageSearchResult>, String> {
+) -> Result<DocumentSearch, String> {
+    use tauri::Emitter;
+
     let docs = state.documents.read().map_err(|e| e.to_string())?;
     let doc = docs.get(&id).ok_or("Document not found")?;
+    let options = options.unwrap_or_default();
+    let matcher = Matcher::new(&query, &options)?;
 
-    let page_count = doc.get_page_count();
-    let mut results = Vec::new();
+    let token = match &search_id {
+        Some(search_id) => state.searches.start(search_id),
+        None => CancelToken::default(),
+    };
+    let stream = search_id.as_deref().map(|search_id| PageStream {
+        app: &app,
+        search_id,
+        searched: AtomicUsize::new(0),
+        page_count: doc.get_page_count().max(0) as usize,
+    });
+    let matches = search_pages(doc, &id, &matcher, &token, stream.as_ref());
+
+    if let Some(search_id) = &search_id {
+        state.searches.finish(search_id, &token);
+        let _ = app.emit(
+            search::DONE_EVENT,
+            SearchDoneEvent {
+                search_id: search_id.clone(),
+                doc_id: Some(id.clone()),
+                total_hits: matches.total_hits(),
+                cancelled: token.is_cancelled(),
+            },
+        );
+    }
+
+    if !tally.unwrap_or(false) {
+        return Ok(DocumentSearch::Pages(matches.into_page_results()));
+    }
+    let failed_pages = matches.failed_pages;
+    let texts = matches
+        .pages
+        .into_iter()
+        .map(|(page_idx, found)| (page_idx, found.into_iter().map(|m| m.text).collect()))
+        .collect();
+    let mut tally = search::tally(texts, options.case_sensitive);
+    tally.failed_pages = failed_pages;
+    println!(
+        "COMMAND: search_document tally {} '{}' -> {} hits, {} distinct",
+        id,
+        query,
+        tally.total,
+        tally.terms.len()
+    );
+    Ok(DocumentSearch::Tally(tally))
+}
+
+/// Result of `search_document`: the pages with hits, or their tally
+#[derive(serde::Serialize)]
+#[serde(untagged)]
+pub enum DocumentSearch {
+    Pages(Vec<PageSearchResult>),
+    Tally(SearchTally),
+}
+
+/// Hits of one document search, in frontend coordinates
+struct DocumentMatches {
+    /// Pages with hits, in page order
+    pages: Vec<(i32, Vec<SearchMatch>)>,
+    /// Pages that could not be searched
+    failed_pages: Vec<i32>,
+}
+
+impl DocumentMatches {
+    fn total_hits(&self) -> usize {
+        self.pages.iter().map(|(_, found)| found.len()).sum()
+    }
+
+    /// Hit quads per page, as the search commands return them.
+    fn into_page_results(self) -> Vec<PageSearchResult> {
+        self.pages
+            .into_iter()
+            .map(|(page_idx, found)| PageSearchResult {
+                page_idx,
+                hits: found.into_iter().flat_map(|m| m.quads).collect(),
+            })
+            .collect()
+    }
+}
+
+/// Where a search streams its `search-page` events. Progress counts pages
+/// over the whole search, which may span several documents.
+struct PageStream<'a> {
+    app: &'a tauri::AppHandle,
+    search_id: &'a str,
+    /// Pages searched so far, in every document
+    searched: AtomicUsize,
+    /// Pages to search, in every document
+    page_count: usize,
+}
+
+/// Search every page of a document. Pages not yet started when `token` is
+/// cancelled are skipped; with `stream`, each searched page is emitted as a
+/// `search-page` event.
+fn search_pages(
+    doc: &MuPdfDoc,
+    doc_id: &str,
+    matcher: &Matcher,
+    token: &CancelToken,
+    stream: Option<&PageStream>,
+) -> DocumentMatches {
+    use tauri::Emitter;
+
+    let page_count = doc.get_page_count().max(0) as usize;
+
+    let pages = context_pool::parallel_map(page_count, doc.worker_capacity(), |page_idx| {
+        if token.is_cancelled() {
+            return None;
+        }
+        let page_idx = page_idx as i32;
+        let mut found = match doc.search_matches(page_idx, matcher) {
+            Ok(found) => found,
+            Err(e) => {
+                println!("COMMAND: search skipped page {} of {}: {}", page_idx, doc_id, e);
+                return Some(Err(()));
+            }
+        };
+        // Scale hits
+        for hit in found.iter_mut().flat_map(|m| m.quads.iter_mut()) {
+            scale_hit(hit);
+        }
+
+        if let Some(stream) = stream {
+            let _ = stream.app.emit(
+                search::PAGE_EVENT,
+                SearchPageEvent {
+                    search_id: stream.search_id.to_string(),
+                    doc_id: doc_id.to_string(),
+                    page_idx,
+                    hits: found.iter().flat_map(|m| m.quads.iter().cloned()).collect(),
+                    pages_searched: stream.searched.fetch_add(1, Ordering::Relaxed) + 1,
+                    page_count: stream.page_count,
+                },
+            );
+        }
+        Some(Ok(found))
+    });
+
+    let mut matches = DocumentMatches {
+        pages: Vec::new(),
+        failed_pages: Vec::new(),
+    };
+    for (page_idx, result) in pages.into_iter().enumerate() {
+        match result {
+            Some(Ok(found)) if !found.is_empty() => matches.pages.push((page_idx as i32, found)),
+            Some(Err(())) => matches.failed_pages.push(page_idx as i32),
+            _ => {}
+        }
+    }
+    matches
+}
+
+/// Hits of one document in a project-wide search
+#[derive(serde::Serialize)]
+pub struct DocumentSearchResult {
+    pub doc_id: String,
+    pub total_hits: usize,
+    /// Hit quads per page (text search)
+    pub pages: Vec<PageSearchResult>,
+    /// Ranked pages with snippets (index search)
+    pub index_hits: Vec<IndexHit>,
+}
+
+/// Search every open document at once (or those in `doc_ids`, in that order),
+/// e.g. specifications and drawings together. Text search returns hit quads
+/// per page and streams `search-page` events like `search_document` when
+/// given a `search_id`, with progress counted over all the documents; with
+/// `use_index` the documents' full-text indices are searched instead,
+/// returning the `limit` best ranked pages of the project with snippets.
+/// Documents without hits, or whose index cannot be read, are left out.
+#[tauri::command]
+pub async fn search_project(
+    app: tauri::AppHandle,
+    query: String,
+    options: Option<SearchOptions>,
+    doc_ids: Option<Vec<String>>,
+    use_index: Option<bool>,
+    limit: Option<usize>,
+    search_id: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<Vec<DocumentSearchResult>, String> {
+    use tauri::Emitter;
+
+    let doc_ids = match doc_ids {
+        Some(ids) => ids,
+        None => {
+            let docs = state.documents.read().map_err(|e| e.to_string())?;
+            let mut ids: Vec<String> = docs.keys().cloned().collect();
+            ids.sort();
+            ids
+        }
+    };
 
-    for page_idx in 0..page_count {
-        let mut hits = doc.search_page(page_idx, &query).map_err(|e| e.to_string())?;
-        if !hits.is_empty() {
-            // Scale hits
-            for hit in &mut hits {
-                scale_hit(hit);
+    let mut results = Vec::new();
+    if use_index.unwrap_or(false) {
+        // Indices are read from disk, without holding the documents lock
+        let limit = limit.unwrap_or(50);
+        let mut ranked = Vec::new();
+        for doc_id in doc_ids {
+            let index = match load_text_index(&app, &doc_id) {
+                Ok(Some(index)) => index,
+                Ok(None) => continue,
+                Err(e) => {
+                    println!("COMMAND: search_project skipped unreadable index of {}: {}", doc_id, e);
+                    continue;
+                }
+            };
+            ranked.push((doc_id, index.search(&query, limit)));
+        }
+        for (doc_id, index_hits) in text_index::top_hits(ranked, limit) {
+            results.push(DocumentSearchResult {
+                doc_id,
+                total_hits: index_hits.len(),
+                pages: Vec::new(),
+                index_hits,
+            });
+        }
+    } else {
+        let docs = state.documents.read().map_err(|e| e.to_string())?;
+        let matcher = Matcher::new(&query, &options.unwrap_or_default())?;
+        let token = match &search_id {
+            Some(search_id) => state.searches.start(search_id),
+            None => CancelToken::default(),
+        };
+        let targets: Vec<(String, &MuPdfDoc)> = doc_ids
+            .into_iter()
+            .filter_map(|doc_id| match docs.get(&doc_id) {
+                Some(doc) => Some((doc_id, doc)),
+                None => {
+                    println!("COMMAND: search_project skipped unknown document {}", doc_id);
+                    None
+                }
+            })
+            .collect();
+        let stream = search_id.as_deref().map(|search_id| PageStream {
+            app: &app,
+            search_id,
+            searched: AtomicUsize::new(0),
+            page_count: targets.iter().map(|(_, doc)| doc.get_page_count().max(0) as usize).sum(),
+        });
+
+        for (doc_id, doc) in targets {
+            if token.is_cancelled() {
+                break;
             }
-            results.push(PageSearchResult { page_idx, hits });
+            let matches = search_pages(doc, &doc_id, &matcher, &token, stream.as_ref());
+            let total_hits = matches.total_hits();
+            if total_hits > 0 {
+                results.push(DocumentSearchResult {
+                    doc_id,
+                    total_hits,
+                    pages: matches.into_page_results(),
+                    index_hits: Vec::new(),
+                });
+            }
+        }
+
+        if let Some(search_id) = &search_id {
+            state.searches.finish(search_id, &token);
+            let _ = app.emit(
+                search::DONE_EVENT,
+                SearchDoneEvent {
+                    search_id: search_id.clone(),
+                    doc_id: None,
+                    total_hits: results.iter().map(|doc| doc.total_hits).sum(),
+                    cancelled: token.is_cancelled(),
+                },
+            );
         }
     }
 
+    println!(
+        "COMMAND: search_project '{}' -> {} hits in {} documents",
+        query,
+        results.iter().map(|doc| doc.total_hits).sum::<usize>(),
+        results.len()
+    );
     Ok(results)
 }
 
+/// Run a document search and return count markups for its hits, one per
+/// page keyed by page id (`{id}:{page_idx}`), ready to add to the project.
+/// `properties` are the count properties to use (colour, shape, group...);
+/// with `split_terms` each distinct matched string (e.g. `WP-1`, `WP-2`) gets
+/// its own markup. Pages that could not be searched are listed in
+/// `failed_pages`.
+#[tauri::command]
+pub async fn search_to_count_markups(
+    id: String,
+    query: String,
+    options: Option<SearchOptions>,
+    properties: Option<serde_json::Value>,
+    split_terms: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<SearchCounts, String> {
+    let docs = state.documents.read().map_err(|e| e.to_string())?;
+    let doc = docs.get(&id).ok_or("Document not found")?;
+    let options = options.unwrap_or_default();
+    let matcher = Matcher::new(&query, &options)?;
+
+    // A count point per hit, at its first quad
+    let matches = search_pages(doc, &id, &matcher, &CancelToken::default(), None);
+    let pages = matches
+        .pages
+        .into_iter()
+        .map(|(page_idx, found)| {
+            let points = found
+                .into_iter()
+                .filter_map(|m| m.quads.into_iter().next().map(|quad| (quad, m.text)))
+                .collect();
+            (page_idx, points)
+        })
+        .collect();
+
+    let markups = count_markups::build(
+        &id,
+        &query,
+        pages,
+        &properties.unwrap_or_default(),
+        split_terms.unwrap_or(false),
+        options.case_sensitive,
+    );
+    println!(
+        "COMMAND: search_to_count_markups {} '{}' -> {} points on {} pages",
+        id,
+        query,
+        markups
+            .values()
+            .flatten()
+            .map(|m| m.paths.iter().map(Vec::len).sum::<usize>())
+            .sum::<usize>(),
+        markups.len()
+    );
+    Ok(SearchCounts {
+        markups,
+        failed_pages: matches.failed_pages,
+    })
+}
+
+/// Stop a running `search_document`. Returns false if no search has that id.
+#[tauri::command]
+pub fn cancel_search(search_id: String, state: State<'_, AppState>) -> bool {
+    let cancelled = state.searches.cancel(&search_id);
+    println!("COMMAND: cancel_search {} -> {}", search_id, cancelled);
+    cancelled
+}
+
 /// Check if OCR support is available
 #[tauri::command]
 pub fn has_ocr_support() -> bool {
@@ -712,18 +1514,36 @@ pub fn has_ocr_support() -> bool {
 pub async fn ingest_file(
     app: tauri::AppHandle,
     id: String,
-    path: String
+    path: String,
+    state: State<'_, AppState>,
 ) -> Result<crate::ingestion_service::IngestionResult, String> {
-    use tauri::Manager;
-    
     // 1. Run Ingestion (Heavy lifting)
-    let result = IngestionService::ingest_document(id, std::path::PathBuf::from(path))
+    let mut result = IngestionService::ingest_document(id, std::path::PathBuf::from(path))
         .await
         .map_err(|e| e.to_string())?;
 
-    // 2. Persist Index to AppData/indices/{doc_id}.json
-    let app_data_dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
-    let indices_dir = app_data_dir.join("indices");
+    // 2. Flag pages without a native text layer as OCR'd. The open document
+    // gives each page's native text; if it is not open, nothing is flagged.
+    let native = {
+        let docs = state.documents.read().map_err(|e| e.to_string())?;
+        match docs.get(&result.doc_id) {
+            Some(doc) => {
+                let page_count = doc.get_page_count().max(0) as usize;
+                context_pool::parallel_map(page_count, doc.worker_capacity(), |page_idx| {
+                    doc.extract_text(page_idx as i32).unwrap_or_default()
+                })
+            }
+            None => Vec::new(),
+        }
+    };
+    page_text::mark_ocr(&mut result.pages, &native);
+    let ocr_pages = result.pages.iter().filter(|page| page.ocr).count();
+    if ocr_pages > 0 {
+        println!("[Ingestion] {} page(s) of {} read by OCR", ocr_pages, result.doc_id);
+    }
+
+    // 3. Persist Index to AppData/indices/{doc_id}.json
+    let indices_dir = indices_dir(&app)?;
 
     if !indices_dir.exists() {
         std::fs::create_dir_all(&indices_dir).map_err(|e| e.to_string())?;
@@ -732,56 +1552,111 @@ pub async fn ingest_file(
     // Determine filename (using doc_id from result)
     let index_path = indices_dir.join(format!("{}.json", result.doc_id));
     
-    // Serialize full result (Content + Metadata)
+    // Serialize full result (Content + Pages + Metadata)
     let json = serde_json::to_string(&result).map_err(|e| e.to_string())?;
     
     std::fs::write(&index_path, json).map_err(|e| e.to_string())?;
 
     println!("[Ingestion] Saved index to {:?}", index_path);
 
+    // 4. Build the full-text index from per-page text: the ingestion pages,
+    // with native text for any page extraction left empty. Output without
+    // page boundaries is indexed as a single page.
+    let mut pages = page_text::page_texts(&result.pages, &native);
+    if pages.iter().all(|page| page.trim().is_empty()) {
+        pages = vec![result.text.clone()];
+    }
+    let text_index = TextIndex::build(&result.doc_id, pages);
+    let text_index_path = save_text_index(&indices_dir, &text_index)?;
+    println!(
+        "[Ingestion] Saved text index ({} pages) to {:?}",
+        text_index.page_count(),
+        text_index_path
+    );
+
     Ok(result)
 }
 
+/// `indices/` in the app data dir, where ingestion results and text indices live.
+fn indices_dir(app: &tauri::AppHandle) -> Result<std::path::PathBuf, String> {
+    use tauri::Manager;
+    let app_data_dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    Ok(app_data_dir.join("indices"))
+}
+
+/// Write a text index to `{doc_id}.index.json` in `dir`, returning the path.
+fn save_text_index(dir: &std::path::Path, index: &TextIndex) -> Result<std::path::PathBuf, String> {
+    let path = dir.join(format!("{}.index.json", index.doc_id));
+    let json = serde_json::to_string(index).map_err(|e| e.to_string())?;
+    std::fs::write(&path, json).map_err(|e| e.to_string())?;
+    Ok(path)
+}
+
+/// Load the text index of a document. Documents ingested before text indices
+/// existed, and indices saved by an older stemmer, are indexed again from
+/// their saved text and the new index is saved. `None` if the document was
+/// never ingested.
+fn load_text_index(app: &tauri::AppHandle, doc_id: &str) -> Result<Option<TextIndex>, String> {
+    let dir = indices_dir(app)?;
+    let index_path = dir.join(format!("{}.index.json", doc_id));
+    let index = if index_path.exists() {
+        let content = std::fs::read_to_string(&index_path).map_err(|e| e.to_string())?;
+        let index: TextIndex = serde_json::from_str(&content).map_err(|e| e.to_string())?;
+        if index.is_current() {
+            return Ok(Some(index));
+        }
+        index.current()
+    } else {
+        let legacy_path = dir.join(format!("{}.json", doc_id));
+        if !legacy_path.exists() {
+            return Ok(None);
+        }
+        let content = std::fs::read_to_string(&legacy_path).map_err(|e| e.to_string())?;
+        let result: crate::ingestion_service::IngestionResult =
+            serde_json::from_str(&content).map_err(|e| e.to_string())?;
+        let pages = if result.pages.is_empty() {
+            vec![result.text]
+        } else {
+            page_text::page_texts(&result.pages, &[])
+        };
+        TextIndex::build(doc_id, pages)
+    };
+
+    // Saved so later searches load it as is; a failed write only costs speed
+    match save_text_index(&dir, &index) {
+        Ok(path) => println!("[Ingestion] Rebuilt text index saved to {:?}", path),
+        Err(e) => println!("[Ingestion] Failed to save rebuilt text index of {}: {}", doc_id, e),
+    }
+    Ok(Some(index))
+}
+
 #[derive(serde::Serialize)]
 pub struct IndexSearchResult {
     pub doc_id: String,
+    /// Snippets of the ranked hits, best first
     pub matches: Vec<String>,
+    /// Ranked page hits with scores and page numbers
+    pub hits: Vec<IndexHit>,
 }
 
+/// Search a document's full-text index. Supports several terms, "quoted
+/// phrases" and misspellings; pages are ranked by relevance.
 #[tauri::command]
 pub async fn search_index(
     app: tauri::AppHandle,
     doc_id: String,
-    query: String
+    query: String,
+    limit: Option<usize>,
 ) -> Result<IndexSearchResult, String> {
-    use tauri::Manager;
-    
-    // Resolve index path
-    let app_data_dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
-    let index_path = app_data_dir.join("indices").join(format!("{}.json", doc_id));
-
-    if !index_path.exists() {
-        return Ok(IndexSearchResult { doc_id, matches: vec![] });
-    }
-
-    // Load and Parse
-    let content = std::fs::read_to_string(&index_path).map_err(|e| e.to_string())?;
-    let index: crate::ingestion_service::IngestionResult = serde_json::from_str(&content).map_err(|e| e.to_string())?;
-
-    // Basic Case-Insensitive Search
-    let text = index.text.to_lowercase();
-    let query_lower = query.to_lowercase();
-    let mut matches = Vec::new();
-
-    for (i, _) in text.match_indices(&query_lower) {
-        // Extract snippet context (e.g., +/- 30 chars)
-        let start = i.saturating_sub(30);
-        let end = (i + query_lower.len() + 30).min(text.len());
-        let snippet = index.text[start..end].replace('\n', " "); // Use original text for snippet
-        matches.push(snippet);
-
-        if matches.len() >= 50 { break; } // Limit results
-    }
+    let Some(index) = load_text_index(&app, &doc_id)? else {
+        return Ok(IndexSearchResult {
+            doc_id,
+            matches: vec![],
+            hits: vec![],
+        });
+    };
 
-    Ok(IndexSearchResult { doc_id, matches })
+    let hits = index.search(&query, limit.unwrap_or(50));
+    let matches = hits.iter().map(|hit| hit.snippet.clone()).collect();
+    Ok(IndexSearchResult { doc_id, matches, hits })
 }
@@ -0,0 +1,242 @@
+//! Context Pool Module
+//!
+//! Fixed set of slots, each guarded by its own mutex, used by `MuPdfDoc` to
+//! hand every concurrent caller its own MuPDF context + document pair. Slots
+//! past the first are created lazily, so a document that is only ever used
+//! from one thread never pays for the extra contexts.
+
+use std::ops::{Deref, DerefMut};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, MutexGuard};
+
+/// Upper bound for the default worker count; every slot holds its own parsed
+/// copy of the document, so more cores do not justify unbounded memory.
+const MAX_DEFAULT_WORKERS: usize = 8;
+
+/// Configured workers per document, 0 = derive from the CPU count
+static WORKER_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+/// Default workers per document: one per CPU, capped at `MAX_DEFAULT_WORKERS`.
+pub fn default_worker_count() -> usize {
+    num_cpus::get().clamp(1, MAX_DEFAULT_WORKERS)
+}
+
+/// Workers each newly opened document is given.
+pub fn worker_count() -> usize {
+    match WORKER_COUNT.load(Ordering::Relaxed) {
+        0 => default_worker_count(),
+        n => n,
+    }
+}
+
+/// Override the worker count (`None` restores the default). Only documents
+/// opened afterwards are affected. Returns the effective count.
+pub fn set_worker_count(count: Option<usize>) -> usize {
+    WORKER_COUNT.store(count.unwrap_or(0), Ordering::Relaxed);
+    worker_count()
+}
+
+/// A pool of up to `capacity` lazily created values, each used by one thread
+/// at a time.
+pub struct ContextPool<T> {
+    slots: Vec<Mutex<Option<T>>>,
+    /// Round-robin cursor for callers that have to wait for a busy slot
+    next: AtomicUsize,
+}
+
+/// Exclusive access to one pooled value.
+pub struct PoolGuard<'a, T> {
+    guard: MutexGuard<'a, Option<T>>,
+}
+
+impl<T> Deref for PoolGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.guard.as_ref().expect("pool slot is initialised before use")
+    }
+}
+
+impl<T> DerefMut for PoolGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.guard.as_mut().expect("pool slot is initialised before use")
+    }
+}
+
+impl<T> ContextPool<T> {
+    /// Create a pool around an already created first value.
+    pub fn new(first: T, capacity: usize) -> Self {
+        let mut slots = Vec::with_capacity(capacity.max(1));
+        slots.push(Mutex::new(Some(first)));
+        slots.resize_with(capacity.max(1), || Mutex::new(None));
+        Self {
+            slots,
+            next: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.slots.len()
+    }
+
+    /// Number of slots created so far.
+    pub fn created(&self) -> usize {
+        self.slots
+            .iter()
+            .filter(|slot| slot.lock().map(|v| v.is_some()).unwrap_or(true))
+            .count()
+    }
+
+    /// The first value, which is always created. Used for operations that
+    /// must run against one specific context (export, overlays).
+    pub fn primary(&self) -> Result<PoolGuard<'_, T>, String> {
+        let guard = self.slots[0].lock().map_err(|_| "Mutex poisoned")?;
+        Ok(PoolGuard { guard })
+    }
+
+    /// Take any idle value, creating a new one with `create` when every
+    /// existing value is busy and the pool has room. Blocks only when all
+    /// `capacity` slots are in use.
+    pub fn acquire<F>(&self, create: F) -> Result<PoolGuard<'_, T>, String>
+    where
+        F: FnOnce() -> Result<T, String>,
+    {
+        // Prefer an idle, already created value
+        for slot in &self.slots {
+            if let Ok(guard) = slot.try_lock() {
+                if guard.is_some() {
+                    return Ok(PoolGuard { guard });
+                }
+            }
+        }
+
+        // Then room to create one; otherwise wait for a busy slot
+        let idle = self.slots.iter().find_map(|slot| slot.try_lock().ok());
+        let mut guard = match idle {
+            Some(guard) => guard,
+            None => {
+                let i = self.next.fetch_add(1, Ordering::Relaxed) % self.slots.len();
+                self.slots[i].lock().map_err(|_| "Mutex poisoned")?
+            }
+        };
+        if guard.is_none() {
+            *guard = Some(create()?);
+        }
+        Ok(PoolGuard { guard })
+    }
+
+    /// Take every created value out of the pool, with the first one last, so
+    /// values derived from it can be released before it.
+    pub fn take_values(&mut self) -> Vec<T> {
+        let mut values: Vec<T> = self
+            .slots
+            .iter_mut()
+            .filter_map(|slot| slot.get_mut().unwrap_or_else(|e| e.into_inner()).take())
+            .collect();
+        if !values.is_empty() {
+            values.rotate_left(1);
+        }
+        values
+    }
+}
+
+/// Run `f` for every index in `0..count` on up to `workers` threads, returning
+/// the results in index order.
+pub fn parallel_map<R, F>(count: usize, workers: usize, f: F) -> Vec<R>
+where
+    R: Send,
+    F: Fn(usize) -> R + Sync,
+{
+    let workers = workers.clamp(1, count.max(1));
+    if workers == 1 {
+        return (0..count).map(f).collect();
+    }
+
+    let next = AtomicUsize::new(0);
+    let mut results: Vec<(usize, R)> = std::thread::scope(|s| {
+        let handles: Vec<_> = (0..workers)
+            .map(|_| {
+                s.spawn(|| {
+                    let mut done = Vec::new();
+                    loop {
+                        let i = next.fetch_add(1, Ordering::Relaxed);
+                        if i >= count {
+                            break done;
+                        }
+                        done.push((i, f(i)));
+                    }
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|h| h.join().expect("parallel_map worker panicked"))
+            .collect()
+    });
+    results.sort_by_key(|(i, _)| *i);
+    results.into_iter().map(|(_, r)| r).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reuses_idle_slot_and_grows_under_contention() {
+        let mut pool = ContextPool::new(0, 3);
+        assert_eq!(pool.capacity(), 3);
+
+        // Sequential use never creates extra values
+        for _ in 0..5 {
+            let v = pool.acquire(|| Err("should not create".to_string())).unwrap();
+            assert_eq!(*v, 0);
+        }
+        assert_eq!(pool.created(), 1);
+
+        // Simultaneous holders each get their own value
+        let mut next = 1;
+        let mut create = || {
+            next += 1;
+            Ok(next - 1)
+        };
+        let a = pool.acquire(&mut create).unwrap();
+        let b = pool.acquire(&mut create).unwrap();
+        let c = pool.acquire(&mut create).unwrap();
+        assert_eq!((*a, *b, *c), (0, 1, 2));
+        drop((a, b, c));
+        assert_eq!(pool.created(), 3);
+
+        let mut values = pool.take_values();
+        assert_eq!(values.pop(), Some(0));
+        values.sort();
+        assert_eq!(values, vec![1, 2]);
+        assert_eq!(pool.created(), 0);
+    }
+
+    #[test]
+    fn test_waits_when_full_and_reports_create_errors() {
+        let pool = ContextPool::new(7, 1);
+        let held = pool.primary().unwrap();
+        std::thread::scope(|s| {
+            let waiter = s.spawn(|| *pool.acquire(|| Ok(99)).unwrap());
+            std::thread::sleep(std::time::Duration::from_millis(20));
+            drop(held);
+            assert_eq!(waiter.join().unwrap(), 7);
+        });
+
+        let pool = ContextPool::new(1, 2);
+        let held = pool.primary().unwrap();
+        assert!(pool.acquire(|| Err("open failed".to_string())).is_err());
+        drop(held);
+        assert_eq!(pool.created(), 1);
+    }
+
+    #[test]
+    fn test_parallel_map_keeps_order() {
+        let squares = parallel_map(100, 4, |i| i * i);
+        assert_eq!(squares.len(), 100);
+        assert!(squares.iter().enumerate().all(|(i, &v)| v == i * i));
+        assert!(parallel_map(0, 4, |i| i).is_empty());
+        assert_eq!(parallel_map(3, 1, |i| i + 1), vec![1, 2, 3]);
+    }
+}
@@ -0,0 +1,197 @@
+//! Count Markups Module
+//!
+//! Turns search hits into count markups ready for the project store, so a
+//! search like "every FD-1 tag" becomes a count takeoff in one action. The
+//! markups are `MarkupData`, the shape the frontend `Markup` is exported in:
+//! one `count` markup per page, a point at the centre of each hit, keyed by
+//! page id `{doc_id}:{page_idx}`.
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+
+use crate::commands::{MarkupData, Point};
+use crate::mupdf_wrapper::SearchHit;
+use crate::search;
+
+/// Count markups made from a document search, keyed by page id.
+#[derive(Debug, Serialize)]
+pub struct SearchCounts {
+    pub markups: HashMap<String, Vec<MarkupData>>,
+    /// Pages that could not be searched, so have no counts
+    pub failed_pages: Vec<i32>,
+}
+
+/// Centre of a hit quad.
+pub fn hit_centre(hit: &SearchHit) -> Point {
+    let corners = [hit.ul, hit.ur, hit.lr, hit.ll];
+    Point {
+        x: corners.iter().map(|c| c.0).sum::<f32>() / 4.0,
+        y: corners.iter().map(|c| c.1).sum::<f32>() / 4.0,
+    }
+}
+
+/// Build count markups from each page's hits and matched texts (hits in
+/// frontend coordinates). `template` supplies the count properties (colour,
+/// shape, group...); its `name` defaults to `query`, and with `split_terms`
+/// each distinct matched string gets its own markup named after it, strings
+/// differing only in case counting as one term unless `case_sensitive` (as
+/// in `search::tally`).
+/// Pages without hits get no entry.
+pub fn build(
+    doc_id: &str,
+    query: &str,
+    pages: Vec<(i32, Vec<(SearchHit, String)>)>,
+    template: &Value,
+    split_terms: bool,
+    case_sensitive: bool,
+) -> HashMap<String, Vec<MarkupData>> {
+    let base_name = template
+        .get("name")
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|name| !name.is_empty() && *name != "Count")
+        .unwrap_or(query)
+        .to_string();
+
+    let mut result = HashMap::new();
+    for (page_idx, hits) in pages {
+        if hits.is_empty() {
+            continue;
+        }
+        let page_id = format!("{}:{}", doc_id, page_idx);
+
+        // Group points by term, keeping first-seen order
+        let mut groups: Vec<(String, String, Vec<Point>)> = Vec::new();
+        for (hit, text) in &hits {
+            let name = if split_terms { text.as_str() } else { base_name.as_str() };
+            let key = search::term_key(name, case_sensitive);
+            let point = hit_centre(hit);
+            match groups.iter_mut().find(|(k, _, _)| *k == key) {
+                Some((_, _, points)) => points.push(point),
+                None => groups.push((key, name.to_string(), vec![point])),
+            }
+        }
+
+        let markups = groups
+            .into_iter()
+            .map(|(_, name, points)| MarkupData {
+                id: uuid::Uuid::new_v4().to_string(),
+                tool_type: "count".to_string(),
+                properties: properties(template, &name, points.len()),
+                paths: vec![points],
+                page_id: Some(page_id.clone()),
+            })
+            .collect();
+        result.insert(page_id, markups);
+    }
+    result
+}
+
+/// Count properties: the template with `name` and `count` filled in and the
+/// defaults of the frontend count tool for anything it lacks.
+fn properties(template: &Value, name: &str, count: usize) -> Value {
+    let mut props = match template {
+        Value::Object(map) => map.clone(),
+        _ => Map::new(),
+    };
+    let defaults = [
+        ("color", Value::from("#10b981")),
+        ("shape", Value::from("circle")),
+        ("group", Value::from("")),
+        ("unitCost", Value::from(0)),
+        ("unit", Value::from("ea")),
+    ];
+    for (key, value) in defaults {
+        props.entry(key).or_insert(value);
+    }
+    props.insert("name".to_string(), Value::from(name));
+    props.insert("count".to_string(), Value::from(count));
+    Value::Object(props)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn hit(x: f32, y: f32) -> SearchHit {
+        SearchHit {
+            ul: (x, y),
+            ur: (x + 10.0, y),
+            lr: (x + 10.0, y + 4.0),
+            ll: (x, y + 4.0),
+        }
+    }
+
+    #[test]
+    fn test_build_one_markup_per_page() {
+        let pages = vec![
+            (0, vec![(hit(0.0, 0.0), "FD-1".to_string()), (hit(20.0, 10.0), "fd-1".to_string())]),
+            (1, Vec::new()),
+            (4, vec![(hit(5.0, 5.0), "FD-1".to_string())]),
+        ];
+        let template = json!({ "name": "Count", "color": "#123456", "shape": "square" });
+        let markups = build("doc", "FD-1", pages, &template, false, false);
+
+        assert_eq!(markups.len(), 2);
+        let page = &markups["doc:0"];
+        assert_eq!(page.len(), 1);
+        assert_eq!(page[0].page_id.as_deref(), Some("doc:0"));
+        assert_eq!(page[0].tool_type, "count");
+        assert_eq!(page[0].paths[0], vec![Point { x: 5.0, y: 2.0 }, Point { x: 25.0, y: 12.0 }]);
+        assert_eq!(page[0].properties["name"], "FD-1");
+        assert_eq!(page[0].properties["count"], 2);
+        assert_eq!(page[0].properties["color"], "#123456");
+        assert_eq!(page[0].properties["unit"], "ea");
+    }
+
+    #[test]
+    fn test_build_split_terms() {
+        let pages = vec![(
+            2,
+            vec![
+                (hit(0.0, 0.0), "WP-1".to_string()),
+                (hit(0.0, 20.0), "WP-2".to_string()),
+                (hit(0.0, 40.0), "WP-1".to_string()),
+            ],
+        )];
+        let markups = build("doc", r"WP-\d+", pages, &Value::Null, true, false);
+        assert_eq!(names(&markups["doc:2"]), vec![("WP-1", 2), ("WP-2", 1)]);
+    }
+
+    #[test]
+    fn test_build_split_terms_case_sensitive() {
+        let pages = || {
+            vec![(0, vec![(hit(0.0, 0.0), "Door".to_string()), (hit(0.0, 20.0), "DOOR".to_string())])]
+        };
+        let insensitive = build("doc", "door", pages(), &Value::Null, true, false);
+        assert_eq!(names(&insensitive["doc:0"]), vec![("Door", 2)]);
+        let sensitive = build("doc", "door", pages(), &Value::Null, true, true);
+        assert_eq!(names(&sensitive["doc:0"]), vec![("Door", 1), ("DOOR", 1)]);
+
+        // Terms fold case like the search tally does, beyond ASCII too
+        let hits = vec![(hit(0.0, 0.0), "Étage".to_string()), (hit(0.0, 20.0), "ÉTAGE".to_string())];
+        let texts = vec![(0, hits.iter().map(|(_, text)| text.clone()).collect())];
+        let markups = build("doc", "étage", vec![(0, hits)], &Value::Null, true, false);
+        assert_eq!(markups["doc:0"].len(), search::tally(texts, false).terms.len());
+        assert_eq!(names(&markups["doc:0"]), vec![("Étage", 2)]);
+    }
+
+    #[test]
+    fn test_markup_serializes_like_frontend_markup() {
+        let pages = vec![(3, vec![(hit(0.0, 0.0), "FD-1".to_string())])];
+        let markups = build("doc", "FD-1", pages, &Value::Null, false, false);
+        let value = serde_json::to_value(&markups["doc:3"][0]).unwrap();
+        assert_eq!(value["type"], "count");
+        assert_eq!(value["pageId"], "doc:3");
+        assert_eq!(value["paths"], json!([[{ "x": 5.0, "y": 2.0 }]]));
+    }
+
+    fn names(markups: &[MarkupData]) -> Vec<(&str, u64)> {
+        markups
+            .iter()
+            .map(|m| (m.properties["name"].as_str().unwrap(), m.properties["count"].as_u64().unwrap()))
+            .collect()
+    }
+}
@@ -0,0 +1,252 @@
+//! Disk Render Cache Module
+//!
+//! Content-addressed cache of whole-page renders in the app data dir. Entries
+//! are keyed by the SHA-256 of the PDF bytes plus page and zoom, so thumbnails
+//! survive restarts, project re-opens and moved files without being embedded
+//! in the .ptf. Pruned by last access time and total size.
+
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::{Duration, SystemTime};
+
+/// Entries not read for this long are removed by `prune`
+pub const DEFAULT_MAX_AGE: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+/// Size the cache is trimmed back to by `prune`
+pub const DEFAULT_MAX_BYTES: u64 = 1024 * 1024 * 1024;
+
+/// SHA-256 of a file's contents as lowercase hex.
+pub fn hash_file(path: &Path) -> io::Result<String> {
+    let mut file = fs::File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buf = vec![0u8; 1024 * 1024];
+    loop {
+        let n = file.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+    }
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect())
+}
+
+/// Result of a prune pass.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+pub struct PruneReport {
+    pub removed_files: usize,
+    pub removed_bytes: u64,
+    pub remaining_files: usize,
+    pub remaining_bytes: u64,
+}
+
+/// A cached file as seen by `prune`.
+struct CacheFile {
+    path: PathBuf,
+    size: u64,
+    accessed: SystemTime,
+}
+
+/// Pick the files to delete: everything older than `max_age`, then the least
+/// recently used until the rest fits in `max_bytes`.
+fn select_for_removal(
+    mut files: Vec<CacheFile>,
+    now: SystemTime,
+    max_age: Duration,
+    max_bytes: u64,
+) -> (Vec<CacheFile>, Vec<CacheFile>) {
+    // Newest first, so the tail is what goes when over budget
+    files.sort_by_key(|f| std::cmp::Reverse(f.accessed));
+
+    let mut keep = Vec::new();
+    let mut remove = Vec::new();
+    let mut kept_bytes = 0u64;
+    for file in files {
+        let age = now.duration_since(file.accessed).unwrap_or_default();
+        if age > max_age || kept_bytes + file.size > max_bytes {
+            remove.push(file);
+        } else {
+            kept_bytes += file.size;
+            keep.push(file);
+        }
+    }
+    (keep, remove)
+}
+
+/// Page renders stored under `<app data>/render_cache/<hash[..2]>/`.
+///
+/// The directory is only known once Tauri has started, so it is set from
+/// `setup`; until then every lookup misses and writes are skipped.
+#[derive(Default)]
+pub struct DiskCache {
+    dir: OnceLock<PathBuf>,
+}
+
+impl DiskCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_dir(&self, dir: PathBuf) -> io::Result<()> {
+        fs::create_dir_all(&dir)?;
+        let _ = self.dir.set(dir);
+        Ok(())
+    }
+
+    fn entry_path(&self, hash: &str, page: i32, zoom: f32) -> Option<PathBuf> {
+        let dir = self.dir.get()?;
+        let shard = hash.get(..2)?;
+        Some(
+            dir.join(shard)
+                .join(format!("{}_p{}_z{}.png", hash, page, zoom)),
+        )
+    }
+
+    /// Read a cached render, refreshing its access time for pruning.
+    pub fn get(&self, hash: &str, page: i32, zoom: f32) -> Option<Vec<u8>> {
+        let path = self.entry_path(hash, page, zoom)?;
+        let bytes = fs::read(&path).ok()?;
+        if let Ok(file) = fs::File::options().write(true).open(&path) {
+            let _ = file.set_modified(SystemTime::now());
+        }
+        Some(bytes)
+    }
+
+    /// Store a render. Failures are logged and otherwise ignored.
+    pub fn put(&self, hash: &str, page: i32, zoom: f32, bytes: &[u8]) {
+        let Some(path) = self.entry_path(hash, page, zoom) else {
+            return;
+        };
+        // Write to a temp name and rename, so readers never see a partial file
+        let tmp = path.with_extension(format!("{}.tmp", uuid::Uuid::new_v4()));
+        let result = path
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|_| fs::write(&tmp, bytes))
+            .and_then(|_| fs::rename(&tmp, &path));
+        if let Err(e) = result {
+            let _ = fs::remove_file(&tmp);
+            println!("[DiskCache] Failed to write {}: {}", path.display(), e);
+        }
+    }
+
+    /// Delete entries not used within `max_age`, then the least recently used
+    /// ones until the cache fits in `max_bytes`.
+    pub fn prune(&self, max_age: Duration, max_bytes: u64) -> io::Result<PruneReport> {
+        let Some(dir) = self.dir.get() else {
+            return Ok(PruneReport::default());
+        };
+
+        let mut files = Vec::new();
+        for shard in fs::read_dir(dir)? {
+            let shard = shard?;
+            if !shard.file_type()?.is_dir() {
+                continue;
+            }
+            for entry in fs::read_dir(shard.path())? {
+                let entry = entry?;
+                let meta = entry.metadata()?;
+                if meta.is_file() {
+                    files.push(CacheFile {
+                        path: entry.path(),
+                        size: meta.len(),
+                        accessed: meta.modified()?,
+                    });
+                }
+            }
+        }
+
+        let (keep, remove) = select_for_removal(files, SystemTime::now(), max_age, max_bytes);
+        let mut report = PruneReport {
+            remaining_files: keep.len(),
+            remaining_bytes: keep.iter().map(|f| f.size).sum(),
+            ..Default::default()
+        };
+        for file in remove {
+            match fs::remove_file(&file.path) {
+                Ok(()) => {
+                    report.removed_files += 1;
+                    report.removed_bytes += file.size;
+                }
+                Err(e) => println!(
+                    "[DiskCache] Failed to remove {}: {}",
+                    file.path.display(),
+                    e
+                ),
+            }
+        }
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(name: &str, size: u64, age_secs: u64, now: SystemTime) -> CacheFile {
+        CacheFile {
+            path: PathBuf::from(name),
+            size,
+            accessed: now - Duration::from_secs(age_secs),
+        }
+    }
+
+    #[test]
+    fn test_select_for_removal_by_age_then_size() {
+        let now = SystemTime::now();
+        let files = vec![
+            file("old", 10, 1000, now),
+            file("recent", 40, 10, now),
+            file("newest", 40, 1, now),
+            file("older", 40, 100, now),
+        ];
+        let (keep, remove) = select_for_removal(files, now, Duration::from_secs(500), 100);
+
+        let names = |v: &[CacheFile]| v.iter().map(|f| f.path.clone()).collect::<Vec<_>>();
+        assert_eq!(
+            names(&keep),
+            vec![PathBuf::from("newest"), PathBuf::from("recent")]
+        );
+        assert_eq!(
+            names(&remove),
+            vec![PathBuf::from("older"), PathBuf::from("old")]
+        );
+    }
+
+    #[test]
+    fn test_put_get_and_prune() {
+        let dir =
+            std::env::temp_dir().join(format!("protakeoff_disk_cache_{}", uuid::Uuid::new_v4()));
+        let cache = DiskCache::new();
+        assert!(cache.get("abcdef", 0, 0.15).is_none());
+
+        cache.set_dir(dir.clone()).unwrap();
+        cache.put("abcdef", 0, 0.15, b"png");
+        assert_eq!(cache.get("abcdef", 0, 0.15).as_deref(), Some(&b"png"[..]));
+        assert!(cache.get("abcdef", 0, 1.5).is_none());
+
+        let report = cache.prune(DEFAULT_MAX_AGE, 0).unwrap();
+        assert_eq!(report.removed_files, 1);
+        assert!(cache.get("abcdef", 0, 0.15).is_none());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_hash_file() {
+        let path =
+            std::env::temp_dir().join(format!("protakeoff_hash_{}.bin", uuid::Uuid::new_v4()));
+        fs::write(&path, b"abc").unwrap();
+        assert_eq!(
+            hash_file(&path).unwrap(),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        fs::remove_file(path).unwrap();
+    }
+}
@@ -1,24 +1,14 @@
+use crate::disk_cache::DiskCache;
 use crate::mupdf_wrapper::MuPdfDoc;
-use rstar::RTree;
+use crate::render_cache::{RenderCache, RenderKey};
+use crate::render_options::{self, ImageFormat, RenderOptions, RenderedImage};
+use crate::search::SearchRegistry;
+use crate::spatial::SpatialIndexStore;
 use std::sync::{Arc, RwLock};
 use pdfium_render::prelude::*;
 
-pub struct VectorObject {
-    #[allow(dead_code)]
-    pub id: usize,
-    pub geom: rstar::primitives::Rectangle<[f64; 2]>,
-    // Add other properties like color, layer, etc.
-}
-
-impl rstar::RTreeObject for VectorObject {
-    type Envelope = rstar::AABB<[f64; 2]>;
-
-    fn envelope(&self) -> Self::Envelope {
-        rstar::AABB::from_corners(self.geom.lower(), self.geom.upper())
-    }
-}
-
 use std::collections::HashMap;
+use std::path::PathBuf;
 
 /// Wrapper to assert thread safety for global Pdfium instance.
 /// Pdfium is generally thread-safe for rendering different docs/pages.
@@ -30,18 +20,85 @@ unsafe impl Sync for ThreadSafePdfium {}
 /// Uses RwLock to favor read-heavy workloads (viewport queries).
 pub struct AppState {
     pub documents: RwLock<HashMap<String, MuPdfDoc>>,
-    /// High-performance in-memory R-Tree for spatial indexing
-    pub spatial_index: RwLock<RTree<VectorObject>>,
+    /// Per-document, per-page R-Trees of vector linework (built lazily)
+    pub spatial_index: SpatialIndexStore,
+    /// Temp copies of embedded PDFs written by `load_project_file`, keyed by document id
+    pub temp_files: RwLock<HashMap<String, PathBuf>>,
+    /// Encoded page/tile renders (LRU, bounded by a byte budget)
+    pub render_cache: RenderCache,
+    /// Whole-page renders persisted in the app data dir, keyed by content hash
+    pub disk_cache: DiskCache,
+    /// SHA-256 of each open document's file, keyed by document id
+    pub content_hashes: RwLock<HashMap<String, String>>,
     /// Global Pdfium binding (manual for production bundles)
     pub pdfium: RwLock<Option<Arc<ThreadSafePdfium>>>,
+    /// Cancellation flags of running document searches
+    pub searches: SearchRegistry,
 }
 
 impl AppState {
     pub fn new() -> Self {
         Self {
             documents: RwLock::new(HashMap::new()),
-            spatial_index: RwLock::new(RTree::new()),
+            spatial_index: SpatialIndexStore::new(),
+            temp_files: RwLock::new(HashMap::new()),
+            render_cache: RenderCache::default(),
+            disk_cache: DiskCache::new(),
+            content_hashes: RwLock::new(HashMap::new()),
             pdfium: RwLock::new(None),
+            searches: SearchRegistry::new(),
         }
     }
+
+    /// Render a whole page to PNG, served from the memory or disk render cache
+    /// when possible.
+    pub fn render_page(&self, id: &str, page: i32, zoom: f32) -> Result<Arc<RenderedImage>, String> {
+        self.render(id, page, &RenderOptions::new(zoom))
+    }
+
+    /// Render a page (or a pixel clip of it) in the requested format, served
+    /// from the render cache when possible. Plain whole-page PNGs are also
+    /// persisted in the disk cache.
+    pub fn render(
+        &self,
+        id: &str,
+        page: i32,
+        options: &RenderOptions,
+    ) -> Result<Arc<RenderedImage>, String> {
+        self.render_cache
+            .get_or_render(RenderKey::new(id, page, options.clone()), || {
+                let hash = if options.is_default_page() {
+                    self.content_hashes
+                        .read()
+                        .ok()
+                        .and_then(|hashes| hashes.get(id).cloned())
+                } else {
+                    None
+                };
+                let cached = hash
+                    .as_deref()
+                    .and_then(|hash| self.disk_cache.get(hash, page, options.zoom));
+                if let Some(bytes) = cached {
+                    if let Some((width, height)) = render_options::png_dimensions(&bytes) {
+                        return Ok(RenderedImage {
+                            width,
+                            height,
+                            format: ImageFormat::Png,
+                            bytes,
+                        });
+                    }
+                }
+
+                let image = {
+                    let docs = self.documents.read().map_err(|_| "Failed to lock docs")?;
+                    let doc = docs.get(id).ok_or("Document not found")?;
+                    doc.render_page_to_buffer(page, options)
+                        .map_err(|e| e.to_string())?
+                };
+                if let Some(hash) = hash.as_deref() {
+                    self.disk_cache.put(hash, page, options.zoom, &image.bytes);
+                }
+                Ok(image)
+            })
+    }
 }
@@ -1,11 +1,16 @@
 use std::path::PathBuf;
 use std::sync::Arc;
 
+use crate::page_text::{self, PageText};
+
 // Define a result structure for frontend consumption
 #[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
 pub struct IngestionResult {
     pub doc_id: String,
     pub text: String,
+    /// Text of each page, with OCR flags (empty in results saved before pages were kept)
+    #[serde(default)]
+    pub pages: Vec<PageText>,
     pub page_count: i32,
     pub metadata: serde_json::Value,
 }
@@ -36,6 +41,11 @@ impl IngestionService {
                 language: "eng".to_string(),
                 ..Default::default()
             }),
+            // Keep page boundaries so search hits can name their page
+            pages: Some(kreuzberg::PageConfig {
+                extract_pages: true,
+                ..Default::default()
+            }),
             ..Default::default()
         };
         
@@ -56,10 +66,20 @@ impl IngestionService {
         let metadata_json = serde_json::to_value(&k_result.metadata)
             .unwrap_or(serde_json::json!({ "error": "failed to serialize metadata" }));
 
+        // Kreuzberg numbers pages from 1
+        let pages = match &k_result.pages {
+            Some(pages) if !pages.is_empty() => pages
+                .iter()
+                .map(|page| PageText::new(page.page_number.max(1) as i32 - 1, page.content.clone()))
+                .collect(),
+            _ => page_text::split_pages(&k_result.content),
+        };
+
         // Map result
         Ok(IngestionResult {
             doc_id,
             text: k_result.content,
+            pages,
             page_count: k_result.metadata.pages.map(|p| p.total_count as i32).unwrap_or(1),
             metadata: metadata_json,
         })
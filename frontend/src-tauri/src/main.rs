@@ -2,18 +2,156 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 mod commands;
+mod context_pool;
+mod count_markups;
+mod disk_cache;
 mod doc_state;
 mod error;
 mod geometry;
 mod menu;
 mod mupdf_wrapper;
+mod navigation;
+mod page_info;
+mod page_text;
 mod project_io;
+mod render_cache;
+mod render_options;
+mod scale_bar;
+mod scale_detection;
+mod search;
+#[allow(dead_code, unused_imports, clippy::all)]
+mod schema_generated;
+mod snap;
+mod spatial;
+mod structured_text;
+mod text_index;
+mod tiles;
+mod title_block;
 mod vello_renderer;
+mod viewport_buffer;
 mod ingestion_service;
 
 use crate::doc_state::AppState;
+use crate::render_options::{RenderOptions, RenderedImage};
 use tauri::Manager;
 
+/// Build a 200 response carrying an encoded render.
+///
+/// The pixel size is exposed in headers so raw RGBA bodies can be turned back
+/// into an `ImageData` on the frontend.
+fn image_response(image: &RenderedImage) -> tauri::http::Response<Vec<u8>> {
+    tauri::http::Response::builder()
+        .header("Content-Type", image.format.content_type())
+        .header("X-Image-Width", image.width)
+        .header("X-Image-Height", image.height)
+        .header("Access-Control-Allow-Origin", "*")
+        .header("Access-Control-Expose-Headers", "X-Image-Width, X-Image-Height")
+        .body(image.bytes.clone())
+        .unwrap_or_else(|e| {
+            eprintln!("Protocol: Failed to build response: {}", e);
+            tauri::http::Response::builder()
+                .status(500)
+                .header("Access-Control-Allow-Origin", "*")
+                .body(Vec::new())
+                .expect("Failed to build 500 error response")
+        })
+}
+
+/// Serve a `protakeoff://` request: whole pages (`/page/...`) and tiles (`/tile/...`).
+fn handle_protocol_request(
+    app_handle: &tauri::AppHandle,
+    request: &tauri::http::Request<Vec<u8>>,
+) -> tauri::http::Response<Vec<u8>> {
+    println!("Protocol: ENTERING HANDLER for {}", request.uri());
+    let state = app_handle.state::<AppState>();
+
+    let uri = request.uri();
+    let path = uri.path(); 
+    let query = uri.query().unwrap_or("");
+
+    println!("Protocol: Raw URI: {}", uri);
+    println!("Protocol: Parsed Path: {}", path);
+
+    // Handle potentially different host parsing behavior between OS
+    // We want to route based on the path segments
+    // Typical path: /page/{id}/{page_num}
+    // But if host is treated as 'page', path might differ?
+    // Let's rely on standard path parsing.
+
+    // Normalize path to ensure consistent handling
+    let normalized_path = if path.starts_with('/') { path.to_string() } else { format!("/{}", path) };
+
+    println!("Protocol: Normalized Path: {}", normalized_path);
+
+    if normalized_path.starts_with("/page/") {
+        // Format: /page/{id}/{page_num}
+        let segments: Vec<&str> = normalized_path.split('/').collect();
+        // segments[0] = "", segments[1] = "page", segments[2] = id, segments[3] = page_num
+
+        if segments.len() >= 4 {
+            let doc_id = segments[2];
+            if let Ok(page_num) = segments[3].trim_end_matches(".png").parse::<i32>() {
+                // Extract zoom
+                let mut zoom = 1.5;
+                for pair in query.split('&') {
+                    if let Some((key, value)) = pair.split_once('=') {
+                        if key == "zoom" {
+                            if let Ok(z) = value.parse::<f32>() {
+                                zoom = z;
+                            }
+                        }
+                    }
+                }
+
+                // Output format (format=png|jpeg|webp|rgba, quality=1-100) and colour mode
+                // (mode=grayscale|faded|inverted|monochrome, intensity=0-100, threshold=0-255)
+                // and hidden layers (hide=1,4 from list_layers)
+                let options = RenderOptions::new(zoom).apply_query(query);
+
+                // Render (or serve from the render cache)
+                match state.render(doc_id, page_num, &options) {
+                    Ok(image) => {
+                        println!("Protocol: Rendering success. Returning {} bytes.", image.bytes.len());
+                        return image_response(&image);
+                    }
+                    Err(e) => {
+                        eprintln!("Protocol: Render error for {} page {}: {}", doc_id, page_num, e);
+                    }
+                }
+            } else {
+                println!("Protocol: Invalid page number in path: {}", segments[3]);
+            }
+        } else {
+            println!("Protocol: Invalid segment length: {}", segments.len());
+        }
+    } else if normalized_path.starts_with("/tile/") {
+        // Format: /tile/{id}/{page}/{z}/{x}/{y}
+        match tiles::parse_tile_path(&normalized_path) {
+            Some(tile) => {
+                let (x, y) = tile.origin();
+                let options = RenderOptions::new(tile.zoom())
+                    .with_clip([x, y, tiles::TILE_SIZE, tiles::TILE_SIZE])
+                    .apply_query(query);
+                match state.render(tile.doc_id, tile.page, &options) {
+                    Ok(image) => return image_response(&image),
+                    // Also hit for tiles past the page edge, which the canvas may request
+                    Err(e) => println!("Protocol: Tile render error: {}", e),
+                }
+            }
+            None => println!("Protocol: Invalid tile path: {}", normalized_path),
+        }
+    } else {
+        println!("Protocol ERROR: Unknown route: '{}'", normalized_path);
+    }
+
+    println!("Protocol: Returning 404 for {}", uri);
+    tauri::http::Response::builder()
+        .status(404)
+        .header("Access-Control-Allow-Origin", "*")
+        .body(Vec::new())
+        .expect("Failed to build 404 response")
+}
+
 fn main() {
     println!("Tauri Application Starting...");
     tauri::Builder::default()
@@ -128,6 +266,30 @@ fn main() {
                 Err(e) => eprintln!("Tauri: Failed to get resource dir: {}", e),
             }
             
+            // Setup the on-disk render cache and prune it off the startup path
+            match app.path().app_data_dir() {
+                Ok(app_data_dir) => {
+                    let state = app.state::<AppState>();
+                    match state.disk_cache.set_dir(app_data_dir.join("render_cache")) {
+                        Ok(()) => {
+                            let handle = app.handle().clone();
+                            std::thread::spawn(move || {
+                                let state = handle.state::<AppState>();
+                                match state.disk_cache.prune(
+                                    disk_cache::DEFAULT_MAX_AGE,
+                                    disk_cache::DEFAULT_MAX_BYTES,
+                                ) {
+                                    Ok(report) => println!("Tauri: Pruned render cache: {:?}", report),
+                                    Err(e) => eprintln!("Tauri: Failed to prune render cache: {}", e),
+                                }
+                            });
+                        }
+                        Err(e) => eprintln!("Tauri: Failed to create render cache dir: {}", e),
+                    }
+                }
+                Err(e) => eprintln!("Tauri: Failed to get app data dir: {}", e),
+            }
+
             // Setup native menu
             if let Err(e) = menu::create_menu(app) {
                 eprintln!("Failed to create menu: {}", e);
@@ -142,103 +304,32 @@ fn main() {
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_os::init())
         .plugin(tauri_plugin_http::init())
-        .register_uri_scheme_protocol("protakeoff", |ctx, request| {
-            println!("Protocol: ENTERING HANDLER for {}", request.uri());
-            let app_handle = ctx.app_handle();
-            let state = app_handle.state::<AppState>();
-            
-            let uri = request.uri();
-            let path = uri.path(); 
-            let query = uri.query().unwrap_or("");
-            
-            println!("Protocol: Raw URI: {}", uri);
-            println!("Protocol: Parsed Path: {}", path);
-            
-            // Handle potentially different host parsing behavior between OS
-            // We want to route based on the path segments
-            // Typical path: /page/{id}/{page_num}
-            // But if host is treated as 'page', path might differ?
-            // Let's rely on standard path parsing.
-            
-            // Normalize path to ensure consistent handling
-            let normalized_path = if path.starts_with('/') { path.to_string() } else { format!("/{}", path) };
-            
-            println!("Protocol: Normalized Path: {}", normalized_path);
-
-            if normalized_path.starts_with("/page/") {
-                // Format: /page/{id}/{page_num}
-                let segments: Vec<&str> = normalized_path.split('/').collect();
-                // segments[0] = "", segments[1] = "page", segments[2] = id, segments[3] = page_num
-                
-                if segments.len() >= 4 {
-                    let doc_id = segments[2];
-                    if let Ok(page_num) = segments[3].trim_end_matches(".png").parse::<i32>() {
-                        // Extract zoom
-                        let mut zoom = 1.5;
-                        for pair in query.split('&') {
-                            if let Some((key, value)) = pair.split_once('=') {
-                                if key == "zoom" {
-                                    if let Ok(z) = value.parse::<f32>() {
-                                        zoom = z;
-                                    }
-                                }
-                            }
-                        }
-
-                        // Render
-                        // Use read lock since we're only reading the document
-                        match state.documents.read() {
-                            Ok(docs) => {
-                                if let Some(doc) = docs.get(doc_id) {
-                                    match doc.render_page_to_buffer(page_num, zoom) {
-                                        Ok(bytes) => {
-                                            println!("Protocol: Rendering success. Returning {} bytes.", bytes.len());
-                                            return tauri::http::Response::builder()
-                                                .header("Content-Type", "image/png")
-                                                .header("Access-Control-Allow-Origin", "*")
-                                                .body(bytes)
-                                                .unwrap_or_else(|e| {
-                                                    eprintln!("Protocol: Failed to build response: {}", e);
-                                                    tauri::http::Response::builder()
-                                                        .status(500)
-                                                        .header("Access-Control-Allow-Origin", "*")
-                                                        .body(Vec::new())
-                                                        .expect("Failed to build 500 error response")
-                                                });
-                                        }
-                                        Err(e) => {
-                                            eprintln!("Protocol: Render error: {}", e);
-                                        }
-                                    }
-                                } else {
-                                    println!("Protocol: Document ID {} NOT FOUND in state. Available keys: {:?}", doc_id, docs.keys());
-                                }
-                            }
-                            Err(e) => {
-                                eprintln!("Protocol: Failed to lock documents mutex: {}", e);
-                            }
-                        }
-                    } else {
-                        println!("Protocol: Invalid page number in path: {}", segments[3]);
-                    }
-                } else {
-                    println!("Protocol: Invalid segment length: {}", segments.len());
-                }
-            } else {
-                println!("Protocol ERROR: Path does not start with /page/: '{}'", normalized_path);
-            }
-
-            println!("Protocol: Returning 404 for {}", uri);
-            tauri::http::Response::builder()
-                .status(404)
-                .header("Access-Control-Allow-Origin", "*")
-                .body(Vec::new())
-                .expect("Failed to build 404 response")
+        .register_asynchronous_uri_scheme_protocol("protakeoff", |ctx, request, responder| {
+            // Render off the webview thread so pages and tiles are served in parallel
+            let app_handle = ctx.app_handle().clone();
+            tauri::async_runtime::spawn_blocking(move || {
+                responder.respond(handle_protocol_request(&app_handle, &request));
+            });
         })
         .invoke_handler(tauri::generate_handler![
             commands::open_file,
+            commands::close_document,
+            commands::close_all_documents,
             commands::get_viewport_vectors,
+            commands::get_viewport_buffer,
+            commands::snap_to_geometry,
             commands::get_page_image_bytes,
+            commands::get_render_cache_stats,
+            commands::set_render_cache_budget,
+            commands::clear_render_cache,
+            commands::get_page_info,
+            commands::extract_sheet_names,
+            commands::list_layers,
+            commands::get_outline,
+            commands::get_page_links,
+            commands::prune_disk_cache,
+            commands::get_render_workers,
+            commands::set_render_workers,
             commands::export_pdf,
             commands::generate_page_thumbnail,
             project_io::save_project_file,
@@ -247,8 +338,14 @@ fn main() {
             project_io::write_base64_to_temp_file,
             commands::get_machine_id,
             commands::extract_page_text,
+            commands::extract_structured_text,
             commands::search_page,
             commands::search_document,
+            commands::search_project,
+            commands::search_to_count_markups,
+            commands::cancel_search,
+            commands::detect_page_scales,
+            commands::detect_scale_bars,
             commands::has_ocr_support,
             commands::ingest_file,
             commands::search_index
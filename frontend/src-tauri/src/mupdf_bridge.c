@@ -1,12 +1,35 @@
 #include "mupdf_bridge.h"
 #include <mupdf/fitz.h>
 #include <mupdf/pdf.h>
+#include <limits.h>
 #include <stdio.h>
 #include <stdlib.h>
 #include <string.h>
 
+#ifdef _WIN32
+#include <windows.h>
+typedef CRITICAL_SECTION mupdf_mutex;
+#define mupdf_mutex_init(m) InitializeCriticalSection(m)
+#define mupdf_mutex_destroy(m) DeleteCriticalSection(m)
+#define mupdf_mutex_lock(m) EnterCriticalSection(m)
+#define mupdf_mutex_unlock(m) LeaveCriticalSection(m)
+#else
+#include <pthread.h>
+typedef pthread_mutex_t mupdf_mutex;
+#define mupdf_mutex_init(m) pthread_mutex_init(m, NULL)
+#define mupdf_mutex_destroy(m) pthread_mutex_destroy(m)
+#define mupdf_mutex_lock(m) pthread_mutex_lock(m)
+#define mupdf_mutex_unlock(m) pthread_mutex_unlock(m)
+#endif
+
+// One mutex per MuPDF lock slot, shared by a root context and all its clones
+typedef struct {
+    mupdf_mutex mutexes[FZ_LOCK_MAX];
+} mupdf_bridge_locks;
+
 struct mupdf_bridge_context {
     fz_context *ctx;
+    mupdf_bridge_locks *locks; // Owned by the root context, NULL for clones
 };
 
 struct mupdf_bridge_document {
@@ -24,19 +47,62 @@ struct mupdf_overlay_context {
 
 // --- Existing Context Functions ---
 
+static void mupdf_lock(void *user, int lock) {
+    mupdf_mutex_lock(&((mupdf_bridge_locks *)user)->mutexes[lock]);
+}
+
+static void mupdf_unlock(void *user, int lock) {
+    mupdf_mutex_unlock(&((mupdf_bridge_locks *)user)->mutexes[lock]);
+}
+
 mupdf_bridge_context *mupdf_new_context(void) {
+    mupdf_bridge_locks *locks = (mupdf_bridge_locks *)malloc(sizeof(mupdf_bridge_locks));
+    if (!locks) return NULL;
+    for (int i = 0; i < FZ_LOCK_MAX; i++) {
+        mupdf_mutex_init(&locks->mutexes[i]);
+    }
+
+    // Locking callbacks are what allow fz_clone_context for worker threads
+    fz_locks_context lock_ctx;
+    lock_ctx.user = locks;
+    lock_ctx.lock = mupdf_lock;
+    lock_ctx.unlock = mupdf_unlock;
+
     // FZ_STORE_DEFAULT allows the context to cache fonts, etc.
-    fz_context *ctx = fz_new_context(NULL, NULL, FZ_STORE_DEFAULT);
-    if (!ctx) return NULL;
+    fz_context *ctx = fz_new_context(NULL, &lock_ctx, FZ_STORE_DEFAULT);
+    if (!ctx) {
+        for (int i = 0; i < FZ_LOCK_MAX; i++) {
+            mupdf_mutex_destroy(&locks->mutexes[i]);
+        }
+        free(locks);
+        return NULL;
+    }
     fz_register_document_handlers(ctx);
     mupdf_bridge_context *bctx = (mupdf_bridge_context *)malloc(sizeof(mupdf_bridge_context));
     bctx->ctx = ctx;
+    bctx->locks = locks;
+    return bctx;
+}
+
+mupdf_bridge_context *mupdf_clone_context(mupdf_bridge_context *ctx) {
+    if (!ctx) return NULL;
+    fz_context *clone = fz_clone_context(ctx->ctx);
+    if (!clone) return NULL;
+    mupdf_bridge_context *bctx = (mupdf_bridge_context *)malloc(sizeof(mupdf_bridge_context));
+    bctx->ctx = clone;
+    bctx->locks = NULL;
     return bctx;
 }
 
 void mupdf_drop_context(mupdf_bridge_context *ctx) {
     if (ctx) {
         fz_drop_context(ctx->ctx);
+        if (ctx->locks) {
+            for (int i = 0; i < FZ_LOCK_MAX; i++) {
+                mupdf_mutex_destroy(&ctx->locks->mutexes[i]);
+            }
+            free(ctx->locks);
+        }
         free(ctx);
     }
 }
@@ -81,34 +147,199 @@ int mupdf_get_page_count(mupdf_bridge_context *ctx, mupdf_bridge_document *doc)
     return count;
 }
 
-uint8_t *mupdf_render_page_to_png(mupdf_bridge_context *ctx, mupdf_bridge_document *doc, int page_number, float zoom, int *out_size, int *error_code) {
+int mupdf_get_page_info(mupdf_bridge_context *ctx, mupdf_bridge_document *doc, int page_number, mupdf_page_info *info) {
+    fz_page *page = NULL;
+    int result = MUPDF_SUCCESS;
+
+    fz_var(page);
+
+    memset(info, 0, sizeof(*info));
+    fz_try(ctx->ctx) {
+        page = fz_load_page(ctx->ctx, doc->doc, page_number);
+        pdf_page *pdf_pg = pdf_page_from_fz_page(ctx->ctx, page);
+
+        if (pdf_pg) {
+            pdf_obj *obj = pdf_pg->obj;
+            fz_rect media = pdf_to_rect(ctx->ctx, pdf_dict_get_inheritable(ctx->ctx, obj, PDF_NAME(MediaBox)));
+            fz_rect crop = pdf_to_rect(ctx->ctx, pdf_dict_get_inheritable(ctx->ctx, obj, PDF_NAME(CropBox)));
+            if (fz_is_empty_rect(media))
+                media = fz_make_rect(0, 0, 612, 792); // US Letter, as MuPDF assumes
+            info->media_box[0] = media.x0; info->media_box[1] = media.y0;
+            info->media_box[2] = media.x1; info->media_box[3] = media.y1;
+            info->crop_box[0] = crop.x0; info->crop_box[1] = crop.y0;
+            info->crop_box[2] = crop.x1; info->crop_box[3] = crop.y1;
+            info->rotation = pdf_to_int(ctx->ctx, pdf_dict_get_inheritable(ctx->ctx, obj, PDF_NAME(Rotate)));
+            info->user_unit = pdf_dict_get_real(ctx->ctx, obj, PDF_NAME(UserUnit));
+        } else {
+            // Not a PDF: the bounds are the only box
+            fz_rect bounds = fz_bound_page(ctx->ctx, page);
+            info->media_box[0] = bounds.x0; info->media_box[1] = bounds.y0;
+            info->media_box[2] = bounds.x1; info->media_box[3] = bounds.y1;
+            memcpy(info->crop_box, info->media_box, sizeof(info->crop_box));
+            info->user_unit = 1.0f;
+        }
+
+        // The page transform already scales by /UserUnit, so these are
+        // points of 1/72 paper inch
+        fz_rect bounds = fz_bound_page(ctx->ctx, page);
+        info->width = bounds.x1 - bounds.x0;
+        info->height = bounds.y1 - bounds.y0;
+
+        fz_page_label(ctx->ctx, page, info->label, sizeof(info->label));
+    }
+    fz_always(ctx->ctx) {
+        fz_drop_page(ctx->ctx, page);
+    }
+    fz_catch(ctx->ctx) {
+        printf("ERROR reading page %d info: %s\n", page_number, fz_caught_message(ctx->ctx));
+        result = MUPDF_ERROR_INTERNAL;
+    }
+    return result;
+}
+
+// Applies a MUPDF_RENDER_* colour mode in place to one row of RGBA pixels
+static void mupdf_apply_render_mode(unsigned char *p, int count, int mode, float amount) {
+    if (amount < 0.0f) amount = 0.0f;
+    if (amount > 1.0f) amount = 1.0f;
+    int level = (int)(amount * 255.0f + 0.5f);
+
+    for (int i = 0; i < count; i++, p += 4) {
+        int r = p[0], g = p[1], b = p[2];
+        // Rec. 601 luma in 0-255
+        int lum = (r * 77 + g * 150 + b * 29) >> 8;
+
+        switch (mode) {
+        case MUPDF_RENDER_GRAYSCALE:
+            p[0] = p[1] = p[2] = (unsigned char)lum;
+            break;
+        case MUPDF_RENDER_FADED:
+            p[0] = (unsigned char)(r + ((255 - r) * level) / 255);
+            p[1] = (unsigned char)(g + ((255 - g) * level) / 255);
+            p[2] = (unsigned char)(b + ((255 - b) * level) / 255);
+            break;
+        case MUPDF_RENDER_INVERTED: {
+            // Shift every channel so the luma becomes 255 - luma
+            int shift = 255 - 2 * lum;
+            int c[3] = { r + shift, g + shift, b + shift };
+            for (int k = 0; k < 3; k++) {
+                p[k] = (unsigned char)(c[k] < 0 ? 0 : (c[k] > 255 ? 255 : c[k]));
+            }
+            break;
+        }
+        case MUPDF_RENDER_MONOCHROME:
+            p[0] = p[1] = p[2] = (unsigned char)(lum < level ? 0 : 255);
+            break;
+        default:
+            return;
+        }
+    }
+}
+
+// Restores layer visibility saved by mupdf_render_page_to_rgba. Must not
+// throw, as it runs in an fz_always block.
+static void mupdf_restore_layers(fz_context *ctx, pdf_document *pdf_doc, const int *states, int count) {
+    fz_try(ctx) {
+        for (int i = 0; i < count; i++) {
+            pdf_enable_layer(ctx, pdf_doc, i, states[i]);
+        }
+    }
+    fz_catch(ctx) {
+        printf("ERROR restoring layer visibility: %s\n", fz_caught_message(ctx));
+    }
+}
+
+uint8_t *mupdf_render_page_to_rgba(mupdf_bridge_context *ctx, mupdf_bridge_document *doc, int page_number, float zoom, int x, int y, int width, int height, int mode, float amount, const int *hidden_layers, int hidden_count, int *out_width, int *out_height, int *error_code) {
+    fz_page *page = NULL;
     fz_pixmap *pix = NULL;
-    fz_buffer *buf = NULL;
+    fz_device *dev = NULL;
     uint8_t *result = NULL;
+    pdf_document *pdf_doc = NULL;
+    int *layer_states = NULL;
+    int layer_count = 0;
 
+    fz_var(page);
     fz_var(pix);
-    fz_var(buf);
+    fz_var(dev);
+    fz_var(result);
+    fz_var(pdf_doc);
+    fz_var(layer_states);
+    fz_var(layer_count);
 
     fz_try(ctx->ctx) {
+        // Hide the requested optional content groups for this render only
+        if (hidden_count > 0)
+            pdf_doc = pdf_specifics(ctx->ctx, doc->doc);
+        if (pdf_doc) {
+            int count = pdf_count_layers(ctx->ctx, pdf_doc);
+            layer_states = (int *)fz_malloc(ctx->ctx, (count > 0 ? count : 1) * sizeof(int));
+            for (int i = 0; i < count; i++) {
+                layer_states[i] = pdf_layer_is_enabled(ctx->ctx, pdf_doc, i);
+            }
+            layer_count = count;
+            for (int i = 0; i < hidden_count; i++) {
+                if (hidden_layers[i] >= 0 && hidden_layers[i] < count)
+                    pdf_enable_layer(ctx->ctx, pdf_doc, hidden_layers[i], 0);
+            }
+        }
+
+        page = fz_load_page(ctx->ctx, doc->doc, page_number);
         fz_matrix ctm = fz_scale(zoom, zoom);
-        pix = fz_new_pixmap_from_page_number(ctx->ctx, doc->doc, page_number, ctm, fz_device_rgb(ctx->ctx), 0);
-        // Using fz_new_buffer_from_pixmap_as_png (native PNG writer)
-        buf = fz_new_buffer_from_pixmap_as_png(ctx->ctx, pix, fz_default_color_params);
+        fz_irect page_box = fz_round_rect(fz_transform_rect(fz_bound_page(ctx->ctx, page), ctm));
+
+        fz_irect bbox = page_box;
+        if (width > 0 && height > 0) {
+            // Clip rectangle in device space (the scaled page does not always start at 0,0),
+            // summed in 64 bits so large requests cannot overflow
+            int64_t x0 = (int64_t)page_box.x0 + x;
+            int64_t y0 = (int64_t)page_box.y0 + y;
+            int64_t x1 = x0 + width;
+            int64_t y1 = y0 + height;
+            if (x0 < INT_MIN || y0 < INT_MIN || x1 > INT_MAX || y1 > INT_MAX)
+                fz_throw(ctx->ctx, FZ_ERROR_GENERIC, "Clip rectangle out of range");
+            bbox = fz_make_irect((int)x0, (int)y0, (int)x1, (int)y1);
+            if (fz_is_empty_irect(fz_intersect_irect(bbox, page_box)))
+                fz_throw(ctx->ctx, FZ_ERROR_GENERIC, "Clip rectangle outside page");
+        }
 
-        size_t size;
-        unsigned char *data;
-        size = fz_buffer_storage(ctx->ctx, buf, &data);
-        
-        result = (uint8_t *)malloc(size);
-        memcpy(result, data, size);
-        *out_size = (int)size;
+        // Opaque white background, so the alpha channel is always 255
+        pix = fz_new_pixmap_with_bbox(ctx->ctx, fz_device_rgb(ctx->ctx), bbox, NULL, 1);
+        fz_clear_pixmap_with_value(ctx->ctx, pix, 0xff);
+
+        // The draw device only rasterises what falls inside the pixmap bbox
+        dev = fz_new_draw_device(ctx->ctx, fz_identity, pix);
+        fz_run_page(ctx->ctx, page, dev, ctm, NULL);
+        fz_close_device(ctx->ctx, dev);
+
+        int w = fz_pixmap_width(ctx->ctx, pix);
+        int h = fz_pixmap_height(ctx->ctx, pix);
+        size_t row = (size_t)w * 4;
+        ptrdiff_t stride = fz_pixmap_stride(ctx->ctx, pix);
+        unsigned char *samples = fz_pixmap_samples(ctx->ctx, pix);
+
+        result = (uint8_t *)malloc(row * (size_t)h);
+        if (!result)
+            fz_throw(ctx->ctx, FZ_ERROR_GENERIC, "Out of memory copying pixels");
+        for (int r = 0; r < h; r++) {
+            memcpy(result + row * (size_t)r, samples + stride * r, row);
+            if (mode != MUPDF_RENDER_NORMAL)
+                mupdf_apply_render_mode(result + row * (size_t)r, w, mode, amount);
+        }
+
+        *out_width = w;
+        *out_height = h;
         *error_code = MUPDF_SUCCESS;
     }
     fz_always(ctx->ctx) {
+        fz_drop_device(ctx->ctx, dev);
         fz_drop_pixmap(ctx->ctx, pix);
-        fz_drop_buffer(ctx->ctx, buf);
+        fz_drop_page(ctx->ctx, page);
+        if (layer_states) {
+            mupdf_restore_layers(ctx->ctx, pdf_doc, layer_states, layer_count);
+            fz_free(ctx->ctx, layer_states);
+        }
     }
     fz_catch(ctx->ctx) {
+        free(result);
         *error_code = MUPDF_ERROR_RENDER;
         return NULL;
     }
@@ -667,33 +898,96 @@ void mupdf_free_text(char *text) {
     if (text) free(text);
 }
 
-int mupdf_search_page(
+// Returns the structured text to search on a page: 'text' as extracted, or,
+// when it is sparse and MuPDF was built with Tesseract, the page re-read
+// through OCR ('text' is dropped then). Scanned sheets have no text layer.
+static fz_stext_page *mupdf_load_search_text(fz_context *ctx, fz_page *page, fz_stext_page *text) {
+    fz_stext_options opts = { 0 };
+
+    // Check Text Density
+    int visible_char_count = 0;
+    for (fz_stext_block *block = text->first_block; block; block = block->next) {
+        if (block->type == FZ_STEXT_BLOCK_TEXT) {
+            for (fz_stext_line *line = block->u.t.first_line; line; line = line->next) {
+                for (fz_stext_char *ch = line->first_char; ch; ch = ch->next) {
+                    if (ch->c > 32) visible_char_count++;
+                }
+            }
+        }
+    }
+
+    // Fallback to OCR if sparse
+    // Increased threshold to 100 to catch more 'sparse' but valid looking vector docs
+    if (visible_char_count < 100) {
+        #ifdef HAVE_TESSERACT
+        fz_drop_stext_page(ctx, text);
+        text = NULL;
+        
+        // Explicit OCR Device Chain (Since FZ_STEXT_OCR_APPLY is missing/version-dependent)
+        fz_rect mediabox;
+        mediabox = fz_bound_page(ctx, page);
+        
+        // OCR usually works better with some scaling? Standard PDF unit is 72dpi. 
+        // Tesseract likes 300dpi. So scale ~4.16x?
+        // However, fz_new_ocr_device doc says "ctm" is used to get size/resolution.
+        // Let's try 1.0 first, or maybe 2.0 for better detection if small text.
+        fz_matrix ctm = fz_identity; 
+        
+        text = fz_new_stext_page(ctx, mediabox);
+        if (text) {
+            fz_device *text_dev = fz_new_stext_device(ctx, text, &opts);
+            fz_device *ocr_dev = NULL;
+            
+            fz_try(ctx) {
+                ocr_dev = fz_new_ocr_device(ctx, text_dev, ctm, mediabox, 1, NULL, NULL, NULL, NULL);
+                if (ocr_dev) {
+                    fz_run_page(ctx, page, ocr_dev, ctm, NULL);
+                    fz_close_device(ctx, ocr_dev);
+                    fz_close_device(ctx, text_dev); // Fix: Ensure text device is also closed
+                }
+            }
+            fz_always(ctx) {
+               if (ocr_dev) fz_drop_device(ctx, ocr_dev);
+               if (text_dev) fz_drop_device(ctx, text_dev);
+            }
+            fz_catch(ctx) {
+                fz_drop_stext_page(ctx, text);
+                text = NULL;
+            }
+        }
+        #endif
+    }
+    
+    return text;
+}
+
+float *mupdf_search_page(
     mupdf_bridge_context *bridge_ctx,
     mupdf_bridge_document *bridge_doc,
     int page_idx,
     const char *needle,
-    float *out_quads,
-    int max_results
+    int *out_count
 ) {
-    if (!bridge_ctx || !bridge_doc || !needle || !out_quads) return 0;
+    if (!bridge_ctx || !bridge_doc || !needle || !out_count) return NULL;
     
     fz_context *ctx = bridge_ctx->ctx;
     fz_document *doc = bridge_doc->doc;
     int hit_count = 0;
+    float *out_quads = NULL;
     
     fz_quad *quads = NULL;
     int *marks = NULL;
     fz_var(quads);
     fz_var(marks);
+    fz_var(out_quads);
+    fz_var(hit_count);
     
     fz_stext_page *text = NULL;
     fz_stext_options opts = { 0 };
 
-    fz_try(ctx) {
-        // Allocate quad array
-        quads = fz_malloc_array(ctx, max_results, fz_quad);
-        marks = fz_malloc_array(ctx, max_results, int);
+    *out_count = 0;
 
+    fz_try(ctx) {
         // 1. Initial Extraction (Fast, no OCR)
         // Note: fz_new_stext_page_from_page_number is not always available in minimal headers, 
         // using valid fz_new_stext_page_from_page logic:
@@ -701,110 +995,35 @@ int mupdf_search_page(
         fz_page *page = fz_load_page(ctx, doc, page_idx);
         text = fz_new_stext_page_from_page(ctx, page, &opts);
         
-        // 2. Check Text Density
-        int char_count = 0;
-        int visible_char_count = 0;
-        for (fz_stext_block *block = text->first_block; block; block = block->next) {
-            if (block->type == FZ_STEXT_BLOCK_TEXT) {
-                for (fz_stext_line *line = block->u.t.first_line; line; line = line->next) {
-                    for (fz_stext_char *ch = line->first_char; ch; ch = ch->next) {
-                        char_count++;
-                        if (ch->c > 32) visible_char_count++;
-                    }
-                }
-            }
-        }
-
-        printf("DEBUG: Page %d Analysis - Total Chars: %d, Visible: %d. OCR Available: %d\n", 
-               page_idx, char_count, visible_char_count, mupdf_has_ocr_support());
+        // 2. Retry with OCR if the page has (almost) no text layer
+        text = mupdf_load_search_text(ctx, page, text);
 
-        // 3. Fallback to OCR if sparse
-        // Increased threshold to 100 to catch more 'sparse' but valid looking vector docs
-        if (visible_char_count < 100) {
-            #ifdef HAVE_TESSERACT
-            printf("DEBUG: Low text count (<100). Retrying with OCR for page %d...\n", page_idx);
-            fz_drop_stext_page(ctx, text);
-            text = NULL;
-            
-            text = NULL;
-            
-            // Explicit OCR Device Chain (Since FZ_STEXT_OCR_APPLY is missing/version-dependent)
-            fz_rect mediabox;
-            mediabox = fz_bound_page(ctx, page);
-            
-            // OCR usually works better with some scaling? Standard PDF unit is 72dpi. 
-            // Tesseract likes 300dpi. So scale ~4.16x?
-            // However, fz_new_ocr_device doc says "ctm" is used to get size/resolution.
-            // Let's try 1.0 first, or maybe 2.0 for better detection if small text.
-            fz_matrix ctm = fz_identity; 
-            
-            text = fz_new_stext_page(ctx, mediabox);
-            if (text) {
-                fz_device *text_dev = fz_new_stext_device(ctx, text, &opts);
-                fz_device *ocr_dev = NULL;
-                
-                fz_try(ctx) {
-                    ocr_dev = fz_new_ocr_device(ctx, text_dev, ctm, mediabox, 1, NULL, NULL, NULL, NULL);
-                    if (ocr_dev) {
-                        fz_run_page(ctx, page, ocr_dev, ctm, NULL);
-                        fz_close_device(ctx, ocr_dev);
-                        fz_close_device(ctx, text_dev); // Fix: Ensure text device is also closed
-                    } else {
-                        printf("DEBUG: fz_new_ocr_device returned NULL.\n");
-                    }
-                }
-                fz_always(ctx) {
-                   if (ocr_dev) fz_drop_device(ctx, ocr_dev);
-                   if (text_dev) fz_drop_device(ctx, text_dev);
-                }
-                fz_catch(ctx) {
-                    printf("DEBUG: OCR failed with exception.\n");
-                    fz_drop_stext_page(ctx, text);
-                    text = NULL;
-                }
-            }
-            
-            if (text) {
-                 printf("DEBUG: OCR Retry successful. New text block generated.\n");
-                 printf("DEBUG-TEXT-START: >>>\n");
-                 int limit = 600;
-                 for (fz_stext_block *block = text->first_block; block && limit > 0; block = block->next) {
-                    if (block->type == FZ_STEXT_BLOCK_TEXT) {
-                        for (fz_stext_line *line = block->u.t.first_line; line && limit > 0; line = line->next) {
-                            for (fz_stext_char *ch = line->first_char; ch && limit > 0; ch = ch->next) {
-                                if (ch->c >= 32 && ch->c < 127) putchar(ch->c);
-                                else if (ch->c == '\n') putchar('\n');
-                                else putchar('?');
-                                limit--;
-                            }
-                            putchar('\n'); 
-                        }
-                        putchar('\n');
-                    }
-                 }
-                 printf("<<< DEBUG-TEXT-END\n");
-            } else {
-                 printf("DEBUG: OCR Retry FAILED (text is null).\n");
-            }
-            #else
-            printf("DEBUG: Low text count detected, but HAVE_TESSERACT is NOT defined. Skipping OCR.\n");
-            #endif
-        } else {
-             printf("DEBUG: Page has sufficient text (%d chars). Skipping OCR.\n", visible_char_count);
-        }
-        
         fz_drop_page(ctx, page); // Page no longer needed, we have the text
 
-        // 4. Perform Search
-        hit_count = fz_search_stext_page(ctx, text, needle, marks, quads, max_results);
+        // 3. Perform Search, growing the quad array until every hit fits
+        int capacity = 256;
+        for (;;) {
+            quads = fz_malloc_array(ctx, capacity, fz_quad);
+            marks = fz_malloc_array(ctx, capacity, int);
+            hit_count = text ? fz_search_stext_page(ctx, text, needle, marks, quads, capacity) : 0;
+            if (hit_count < capacity) break;
+            fz_free(ctx, quads);
+            fz_free(ctx, marks);
+            quads = NULL;
+            marks = NULL;
+            capacity *= 4;
+        }
         
-        // Convert quads
-        for (int i = 0; i < hit_count && i < max_results; i++) {
-            float *q = out_quads + (i * 8);
+        // Convert quads, with the mark MuPDF sets on the first quad of each hit
+        out_quads = (float *)malloc((hit_count > 0 ? hit_count : 1) * 9 * sizeof(float));
+        if (!out_quads) fz_throw(ctx, FZ_ERROR_GENERIC, "Out of memory");
+        for (int i = 0; i < hit_count; i++) {
+            float *q = out_quads + (i * 9);
             q[0] = quads[i].ul.x; q[1] = quads[i].ul.y;
             q[2] = quads[i].ur.x; q[3] = quads[i].ur.y;
             q[4] = quads[i].lr.x; q[5] = quads[i].lr.y;
             q[6] = quads[i].ll.x; q[7] = quads[i].ll.y;
+            q[8] = (i == 0 || marks[i]) ? 1.0f : 0.0f;
         }
     }
     fz_always(ctx) {
@@ -813,10 +1032,229 @@ int mupdf_search_page(
         fz_free(ctx, marks);
     }
     fz_catch(ctx) {
-        hit_count = 0;
+        free(out_quads);
+        return NULL;
     }
     
-    return hit_count;
+    *out_count = hit_count;
+    return out_quads;
+}
+
+// Appends 'str' to 'buf', storing its position in *offset / *length
+static void mupdf_append_string(fz_context *ctx, fz_buffer *buf, const char *str, int *offset, int *length) {
+    *offset = (int)fz_buffer_storage(ctx, buf, NULL);
+    if (str) fz_append_string(ctx, buf, str);
+    *length = (int)fz_buffer_storage(ctx, buf, NULL) - *offset;
+}
+
+// Copies the buffer into a malloc'd, NUL-terminated string
+static char *mupdf_buffer_to_string(fz_context *ctx, fz_buffer *buf) {
+    unsigned char *data = NULL;
+    size_t len = fz_buffer_storage(ctx, buf, &data);
+    char *result = (char *)malloc(len + 1);
+    if (!result) fz_throw(ctx, FZ_ERROR_GENERIC, "Out of memory");
+    if (len > 0) memcpy(result, data, len);
+    result[len] = '\0';
+    return result;
+}
+
+mupdf_text_line *mupdf_extract_page_lines(
+    mupdf_bridge_context *bridge_ctx,
+    mupdf_bridge_document *bridge_doc,
+    int page_idx,
+    int *out_count,
+    char **out_text,
+    int *out_text_length,
+    int *error_code
+) {
+    if (!bridge_ctx || !bridge_doc || !out_count || !out_text || !out_text_length || !error_code) return NULL;
+
+    fz_context *ctx = bridge_ctx->ctx;
+    fz_page *page = NULL;
+    fz_stext_page *text = NULL;
+    fz_buffer *buf = NULL;
+    mupdf_text_line *lines = NULL;
+    char *chars = NULL;
+    int count = 0;
+
+    fz_var(page);
+    fz_var(text);
+    fz_var(buf);
+    fz_var(lines);
+    fz_var(chars);
+    fz_var(count);
+
+    *out_count = 0;
+    *out_text = NULL;
+    *out_text_length = 0;
+    *error_code = MUPDF_SUCCESS;
+
+    fz_try(ctx) {
+        fz_stext_options opts = { 0 };
+        page = fz_load_page(ctx, bridge_doc->doc, page_idx);
+        text = fz_new_stext_page_from_page(ctx, page, &opts);
+
+        int total = 0;
+        for (fz_stext_block *block = text->first_block; block; block = block->next) {
+            if (block->type != FZ_STEXT_BLOCK_TEXT) continue;
+            for (fz_stext_line *line = block->u.t.first_line; line; line = line->next) {
+                if (line->first_char) total++;
+            }
+        }
+
+        lines = (mupdf_text_line *)calloc(total > 0 ? total : 1, sizeof(mupdf_text_line));
+        if (!lines) fz_throw(ctx, FZ_ERROR_GENERIC, "Out of memory");
+        buf = fz_new_buffer(ctx, 1024);
+
+        // Line texts are concatenated (UTF-8, no separators) into one buffer
+        for (fz_stext_block *block = text->first_block; block; block = block->next) {
+            if (block->type != FZ_STEXT_BLOCK_TEXT) continue;
+            for (fz_stext_line *line = block->u.t.first_line; line; line = line->next) {
+                if (!line->first_char) continue;
+                mupdf_text_line *out = &lines[count++];
+                out->x0 = line->bbox.x0;
+                out->y0 = line->bbox.y0;
+                out->x1 = line->bbox.x1;
+                out->y1 = line->bbox.y1;
+                out->text_offset = (int)fz_buffer_storage(ctx, buf, NULL);
+                for (fz_stext_char *ch = line->first_char; ch; ch = ch->next) {
+                    fz_append_rune(ctx, buf, ch->c);
+                    if (ch->size > out->font_size) out->font_size = ch->size;
+                }
+                out->text_length = (int)fz_buffer_storage(ctx, buf, NULL) - out->text_offset;
+            }
+        }
+
+        // Text may contain U+0000, so the length is returned alongside
+        *out_text_length = (int)fz_buffer_storage(ctx, buf, NULL);
+        chars = mupdf_buffer_to_string(ctx, buf);
+    }
+    fz_always(ctx) {
+        fz_drop_buffer(ctx, buf);
+        fz_drop_stext_page(ctx, text);
+        fz_drop_page(ctx, page);
+    }
+    fz_catch(ctx) {
+        free(lines);
+        free(chars);
+        *out_text_length = 0;
+        *error_code = MUPDF_ERROR_INTERNAL;
+        return NULL;
+    }
+
+    *out_count = count;
+    *out_text = chars;
+    return lines;
+}
+
+void mupdf_free_text_lines(mupdf_text_line *lines, char *text) {
+    free(lines);
+    free(text);
+}
+
+mupdf_text_char *mupdf_extract_page_chars(
+    mupdf_bridge_context *bridge_ctx,
+    mupdf_bridge_document *bridge_doc,
+    int page_idx,
+    int ocr,
+    int *out_count,
+    char **out_fonts,
+    int *error_code
+) {
+    if (!bridge_ctx || !bridge_doc || !out_count || !out_fonts || !error_code) return NULL;
+
+    fz_context *ctx = bridge_ctx->ctx;
+    fz_page *page = NULL;
+    fz_stext_page *text = NULL;
+    fz_buffer *buf = NULL;
+    mupdf_text_char *chars = NULL;
+    fz_font **fonts = NULL;
+    char *names = NULL;
+    int count = 0;
+
+    fz_var(page);
+    fz_var(text);
+    fz_var(buf);
+    fz_var(chars);
+    fz_var(fonts);
+    fz_var(names);
+    fz_var(count);
+
+    *out_count = 0;
+    *out_fonts = NULL;
+    *error_code = MUPDF_SUCCESS;
+
+    fz_try(ctx) {
+        fz_stext_options opts = { 0 };
+        page = fz_load_page(ctx, bridge_doc->doc, page_idx);
+        text = fz_new_stext_page_from_page(ctx, page, &opts);
+        if (ocr) text = mupdf_load_search_text(ctx, page, text);
+        fz_stext_block *first_block = text ? text->first_block : NULL;
+
+        int total = 0;
+        for (fz_stext_block *block = first_block; block; block = block->next) {
+            if (block->type != FZ_STEXT_BLOCK_TEXT) continue;
+            for (fz_stext_line *line = block->u.t.first_line; line; line = line->next)
+                for (fz_stext_char *ch = line->first_char; ch; ch = ch->next) total++;
+        }
+
+        chars = (mupdf_text_char *)calloc(total > 0 ? total : 1, sizeof(mupdf_text_char));
+        fonts = (fz_font **)calloc(total > 0 ? total : 1, sizeof(fz_font *));
+        if (!chars || !fonts) fz_throw(ctx, FZ_ERROR_GENERIC, "Out of memory");
+        buf = fz_new_buffer(ctx, 256);
+
+        // Pages use a handful of fonts, a linear lookup is enough
+        int font_count = 0;
+        int block_idx = 0;
+        for (fz_stext_block *block = first_block; block; block = block->next) {
+            if (block->type != FZ_STEXT_BLOCK_TEXT) continue;
+            int line_idx = 0;
+            for (fz_stext_line *line = block->u.t.first_line; line; line = line->next, line_idx++) {
+                for (fz_stext_char *ch = line->first_char; ch; ch = ch->next) {
+                    int font = 0;
+                    while (font < font_count && fonts[font] != ch->font) font++;
+                    if (font == font_count) {
+                        fonts[font_count++] = ch->font;
+                        if (font > 0) fz_append_byte(ctx, buf, '\n');
+                        fz_append_string(ctx, buf, fz_font_name(ctx, ch->font));
+                    }
+
+                    fz_rect box = fz_rect_from_quad(ch->quad);
+                    mupdf_text_char *out = &chars[count++];
+                    out->x0 = box.x0;
+                    out->y0 = box.y0;
+                    out->x1 = box.x1;
+                    out->y1 = box.y1;
+                    out->size = ch->size;
+                    out->c = ch->c;
+                    out->block = block_idx;
+                    out->line = line_idx;
+                    out->dir_x = line->dir.x;
+                    out->dir_y = line->dir.y;
+                    out->font = font;
+                }
+            }
+            block_idx++;
+        }
+
+        names = mupdf_buffer_to_string(ctx, buf);
+    }
+    fz_always(ctx) {
+        free(fonts);
+        fz_drop_buffer(ctx, buf);
+        fz_drop_stext_page(ctx, text);
+        fz_drop_page(ctx, page);
+    }
+    fz_catch(ctx) {
+        free(chars);
+        free(names);
+        *error_code = MUPDF_ERROR_INTERNAL;
+        return NULL;
+    }
+
+    *out_count = count;
+    *out_fonts = names;
+    return chars;
 }
 
 int mupdf_has_ocr_support(void) {
@@ -826,3 +1264,346 @@ int mupdf_has_ocr_support(void) {
     return 0;
     #endif
 }
+
+// --- Vector Geometry Extraction ---
+
+typedef struct {
+    mupdf_vector_segment *items;
+    int count;
+    int capacity;
+} vector_list;
+
+typedef struct {
+    vector_list *list;
+    fz_matrix ctm;
+    fz_point start;   // First point of the current subpath (target of closepath)
+    fz_point current; // Current pen position
+} vector_walker_state;
+
+typedef struct {
+    fz_device super;
+    vector_list *list;
+} vector_device;
+
+static void vector_list_push(fz_context *ctx, vector_list *list, const mupdf_vector_segment *seg) {
+    if (list->count == list->capacity) {
+        int new_capacity = list->capacity ? list->capacity * 2 : 1024;
+        mupdf_vector_segment *items = (mupdf_vector_segment *)realloc(list->items, new_capacity * sizeof(mupdf_vector_segment));
+        if (!items) fz_throw(ctx, FZ_ERROR_GENERIC, "Out of memory collecting vectors");
+        list->items = items;
+        list->capacity = new_capacity;
+    }
+    list->items[list->count++] = *seg;
+}
+
+static void vector_push_line(fz_context *ctx, vector_walker_state *st, fz_point to) {
+    // Zero-length segments carry no linework (dots, degenerate moveto/lineto pairs)
+    if (st->current.x == to.x && st->current.y == to.y) return;
+
+    mupdf_vector_segment seg;
+    memset(&seg, 0, sizeof(seg));
+    seg.kind = MUPDF_SEGMENT_LINE;
+    seg.x0 = st->current.x; seg.y0 = st->current.y;
+    seg.x1 = to.x; seg.y1 = to.y;
+    vector_list_push(ctx, st->list, &seg);
+}
+
+static void vector_moveto(fz_context *ctx, void *arg, float x, float y) {
+    vector_walker_state *st = (vector_walker_state *)arg;
+    st->current = fz_transform_point_xy(x, y, st->ctm);
+    st->start = st->current;
+}
+
+static void vector_lineto(fz_context *ctx, void *arg, float x, float y) {
+    vector_walker_state *st = (vector_walker_state *)arg;
+    fz_point p = fz_transform_point_xy(x, y, st->ctm);
+    vector_push_line(ctx, st, p);
+    st->current = p;
+}
+
+static void vector_curveto(fz_context *ctx, void *arg, float x1, float y1, float x2, float y2, float x3, float y3) {
+    vector_walker_state *st = (vector_walker_state *)arg;
+    fz_point c1 = fz_transform_point_xy(x1, y1, st->ctm);
+    fz_point c2 = fz_transform_point_xy(x2, y2, st->ctm);
+    fz_point p = fz_transform_point_xy(x3, y3, st->ctm);
+
+    mupdf_vector_segment seg;
+    seg.kind = MUPDF_SEGMENT_CURVE;
+    seg.x0 = st->current.x; seg.y0 = st->current.y;
+    seg.x1 = c1.x; seg.y1 = c1.y;
+    seg.x2 = c2.x; seg.y2 = c2.y;
+    seg.x3 = p.x; seg.y3 = p.y;
+    vector_list_push(ctx, st->list, &seg);
+    st->current = p;
+}
+
+static void vector_closepath(fz_context *ctx, void *arg) {
+    vector_walker_state *st = (vector_walker_state *)arg;
+    vector_push_line(ctx, st, st->start);
+    st->current = st->start;
+}
+
+// quadto/curvetov/curvetoy/rectto are left NULL so fz_walk_path expands them
+// into the basic moveto/lineto/curveto/closepath operations above.
+static const fz_path_walker vector_path_walker = {
+    vector_moveto,
+    vector_lineto,
+    vector_curveto,
+    vector_closepath,
+    NULL,
+    NULL,
+    NULL,
+    NULL
+};
+
+static void vector_walk_path(fz_context *ctx, fz_device *dev, const fz_path *path, fz_matrix ctm) {
+    vector_walker_state st;
+    memset(&st, 0, sizeof(st));
+    st.list = ((vector_device *)dev)->list;
+    st.ctm = ctm;
+    fz_walk_path(ctx, path, &vector_path_walker, &st);
+}
+
+static void vector_fill_path(fz_context *ctx, fz_device *dev, const fz_path *path, int even_odd, fz_matrix ctm, fz_colorspace *colorspace, const float *color, float alpha, fz_color_params color_params) {
+    vector_walk_path(ctx, dev, path, ctm);
+}
+
+static void vector_stroke_path(fz_context *ctx, fz_device *dev, const fz_path *path, const fz_stroke_state *stroke, fz_matrix ctm, fz_colorspace *colorspace, const float *color, float alpha, fz_color_params color_params) {
+    vector_walk_path(ctx, dev, path, ctm);
+}
+
+mupdf_vector_segment *mupdf_extract_page_vectors(
+    mupdf_bridge_context *bridge_ctx,
+    mupdf_bridge_document *bridge_doc,
+    int page_idx,
+    int *out_count,
+    int *error_code
+) {
+    if (!bridge_ctx || !bridge_doc || !out_count || !error_code) return NULL;
+
+    fz_context *ctx = bridge_ctx->ctx;
+    fz_page *page = NULL;
+    fz_display_list *list = NULL;
+    fz_device *dev = NULL;
+    vector_list segments = { NULL, 0, 0 };
+    *out_count = 0;
+
+    fz_var(page);
+    fz_var(list);
+    fz_var(dev);
+
+    fz_try(ctx) {
+        page = fz_load_page(ctx, bridge_doc->doc, page_idx);
+        list = fz_new_display_list_from_page(ctx, page);
+
+        vector_device *vdev = fz_new_derived_device(ctx, vector_device);
+        vdev->super.fill_path = vector_fill_path;
+        vdev->super.stroke_path = vector_stroke_path;
+        vdev->list = &segments;
+        dev = (fz_device *)vdev;
+
+        // Identity CTM keeps coordinates in page space (points, top-left origin)
+        fz_run_display_list(ctx, list, dev, fz_identity, fz_infinite_rect, NULL);
+        fz_close_device(ctx, dev);
+
+        *out_count = segments.count;
+        *error_code = MUPDF_SUCCESS;
+    }
+    fz_always(ctx) {
+        fz_drop_device(ctx, dev);
+        fz_drop_display_list(ctx, list);
+        fz_drop_page(ctx, page);
+    }
+    fz_catch(ctx) {
+        free(segments.items);
+        *out_count = 0;
+        *error_code = MUPDF_ERROR_INTERNAL;
+        return NULL;
+    }
+    return segments.items;
+}
+
+void mupdf_free_vectors(mupdf_vector_segment *segments) {
+    if (segments) free(segments);
+}
+
+// --- Optional Content (Layers) ---
+
+int mupdf_count_layers(mupdf_bridge_context *ctx, mupdf_bridge_document *doc) {
+    int count = 0;
+    fz_try(ctx->ctx) {
+        pdf_document *pdf_doc = pdf_specifics(ctx->ctx, doc->doc);
+        if (pdf_doc)
+            count = pdf_count_layers(ctx->ctx, pdf_doc);
+    }
+    fz_catch(ctx->ctx) {
+        count = 0;
+    }
+    return count;
+}
+
+int mupdf_get_layer(mupdf_bridge_context *ctx, mupdf_bridge_document *doc, int layer, char *name_buf, int buf_size, int *enabled) {
+    int result = MUPDF_SUCCESS;
+    fz_try(ctx->ctx) {
+        pdf_document *pdf_doc = pdf_specifics(ctx->ctx, doc->doc);
+        if (!pdf_doc || layer < 0 || layer >= pdf_count_layers(ctx->ctx, pdf_doc))
+            fz_throw(ctx->ctx, FZ_ERROR_GENERIC, "No such layer");
+
+        const char *name = pdf_layer_name(ctx->ctx, pdf_doc, layer);
+        snprintf(name_buf, buf_size, "%s", name ? name : "");
+        *enabled = pdf_layer_is_enabled(ctx->ctx, pdf_doc, layer);
+    }
+    fz_catch(ctx->ctx) {
+        if (buf_size > 0) name_buf[0] = '\0';
+        *enabled = 0;
+        result = MUPDF_ERROR_INTERNAL;
+    }
+    return result;
+}
+
+// --- Outline & Links ---
+
+typedef struct {
+    mupdf_outline_item *items;
+    int count;
+    int capacity;
+} outline_list;
+
+// Depth-first walk flattening the outline tree into 'list'
+static void mupdf_flatten_outline(fz_context *ctx, fz_document *doc, fz_outline *node, int level, outline_list *list, fz_buffer *buf) {
+    for (; node; node = node->next) {
+        if (list->count == list->capacity) {
+            int capacity = list->capacity ? list->capacity * 2 : 64;
+            mupdf_outline_item *items = (mupdf_outline_item *)realloc(list->items, capacity * sizeof(mupdf_outline_item));
+            if (!items) fz_throw(ctx, FZ_ERROR_GENERIC, "Out of memory");
+            list->items = items;
+            list->capacity = capacity;
+        }
+        mupdf_outline_item *item = &list->items[list->count++];
+        item->level = level;
+        item->page = node->page.page >= 0 ? fz_page_number_from_location(ctx, doc, node->page) : -1;
+        item->x = node->x;
+        item->y = node->y;
+        item->is_open = node->is_open;
+        mupdf_append_string(ctx, buf, node->title, &item->title_offset, &item->title_length);
+        mupdf_append_string(ctx, buf, node->uri, &item->uri_offset, &item->uri_length);
+
+        if (node->down)
+            mupdf_flatten_outline(ctx, doc, node->down, level + 1, list, buf);
+    }
+}
+
+mupdf_outline_item *mupdf_load_outline(mupdf_bridge_context *ctx, mupdf_bridge_document *doc, int *out_count, char **out_text, int *error_code) {
+    fz_outline *outline = NULL;
+    fz_buffer *buf = NULL;
+    outline_list list = { NULL, 0, 0 };
+    char *text = NULL;
+
+    fz_var(outline);
+    fz_var(buf);
+    fz_var(list);
+    fz_var(text);
+
+    *out_count = 0;
+    *out_text = NULL;
+    *error_code = MUPDF_SUCCESS;
+
+    fz_try(ctx->ctx) {
+        outline = fz_load_outline(ctx->ctx, doc->doc);
+        buf = fz_new_buffer(ctx->ctx, 1024);
+        mupdf_flatten_outline(ctx->ctx, doc->doc, outline, 0, &list, buf);
+        if (!list.items) {
+            list.items = (mupdf_outline_item *)calloc(1, sizeof(mupdf_outline_item));
+            if (!list.items) fz_throw(ctx->ctx, FZ_ERROR_GENERIC, "Out of memory");
+        }
+        text = mupdf_buffer_to_string(ctx->ctx, buf);
+    }
+    fz_always(ctx->ctx) {
+        fz_drop_buffer(ctx->ctx, buf);
+        fz_drop_outline(ctx->ctx, outline);
+    }
+    fz_catch(ctx->ctx) {
+        printf("ERROR loading outline: %s\n", fz_caught_message(ctx->ctx));
+        free(list.items);
+        free(text);
+        *error_code = MUPDF_ERROR_INTERNAL;
+        return NULL;
+    }
+
+    *out_count = list.count;
+    *out_text = text;
+    return list.items;
+}
+
+mupdf_link_item *mupdf_load_page_links(mupdf_bridge_context *ctx, mupdf_bridge_document *doc, int page_idx, int *out_count, char **out_text, int *error_code) {
+    fz_page *page = NULL;
+    fz_link *links = NULL;
+    fz_buffer *buf = NULL;
+    mupdf_link_item *items = NULL;
+    char *text = NULL;
+    int count = 0;
+
+    fz_var(page);
+    fz_var(links);
+    fz_var(buf);
+    fz_var(items);
+    fz_var(text);
+    fz_var(count);
+
+    *out_count = 0;
+    *out_text = NULL;
+    *error_code = MUPDF_SUCCESS;
+
+    fz_try(ctx->ctx) {
+        page = fz_load_page(ctx->ctx, doc->doc, page_idx);
+        links = fz_load_links(ctx->ctx, page);
+
+        int total = 0;
+        for (fz_link *link = links; link; link = link->next) total++;
+        items = (mupdf_link_item *)calloc(total > 0 ? total : 1, sizeof(mupdf_link_item));
+        if (!items) fz_throw(ctx->ctx, FZ_ERROR_GENERIC, "Out of memory");
+        buf = fz_new_buffer(ctx->ctx, 256);
+
+        for (fz_link *link = links; link; link = link->next) {
+            mupdf_link_item *item = &items[count++];
+            item->x0 = link->rect.x0;
+            item->y0 = link->rect.y0;
+            item->x1 = link->rect.x1;
+            item->y1 = link->rect.y1;
+            item->target_page = -1;
+            if (link->uri && !fz_is_external_link(ctx->ctx, link->uri)) {
+                float x = 0, y = 0;
+                fz_location loc = fz_resolve_link(ctx->ctx, doc->doc, link->uri, &x, &y);
+                if (loc.page >= 0) {
+                    item->target_page = fz_page_number_from_location(ctx->ctx, doc->doc, loc);
+                    item->target_x = x;
+                    item->target_y = y;
+                }
+            }
+            mupdf_append_string(ctx->ctx, buf, link->uri, &item->uri_offset, &item->uri_length);
+        }
+
+        text = mupdf_buffer_to_string(ctx->ctx, buf);
+    }
+    fz_always(ctx->ctx) {
+        fz_drop_buffer(ctx->ctx, buf);
+        fz_drop_link(ctx->ctx, links);
+        fz_drop_page(ctx->ctx, page);
+    }
+    fz_catch(ctx->ctx) {
+        printf("ERROR loading links of page %d: %s\n", page_idx, fz_caught_message(ctx->ctx));
+        free(items);
+        free(text);
+        *error_code = MUPDF_ERROR_INTERNAL;
+        return NULL;
+    }
+
+    *out_count = count;
+    *out_text = text;
+    return items;
+}
+
+void mupdf_free_items(void *items, char *text) {
+    free(items);
+    free(text);
+}
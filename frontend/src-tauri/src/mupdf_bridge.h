@@ -19,6 +19,13 @@ typedef struct mupdf_overlay_context mupdf_overlay_context;
 #define MUPDF_ERROR_INTERNAL 8
 
 mupdf_bridge_context *mupdf_new_context();
+
+// Clones a context for use on another thread. The clone shares the resource
+// store and locks of 'ctx', which must outlive it. Documents are not shared:
+// each clone opens its own copy. Returns NULL on failure.
+mupdf_bridge_context *mupdf_clone_context(mupdf_bridge_context *ctx);
+
+// Drops a context. Clones must be dropped before the context they came from.
 void mupdf_drop_context(mupdf_bridge_context *ctx);
 
 mupdf_bridge_document *mupdf_open_document(mupdf_bridge_context *ctx, const char *path, int *error_code);
@@ -26,11 +33,43 @@ void mupdf_drop_document(mupdf_bridge_context *ctx, mupdf_bridge_document *doc);
 
 int mupdf_get_page_count(mupdf_bridge_context *ctx, mupdf_bridge_document *doc);
 
-// Renders a page to PNG and returns the pointer to bytes.
-// 'out_size' will contain the length of the PNG buffer.
-uint8_t *mupdf_render_page_to_png(mupdf_bridge_context *ctx, mupdf_bridge_document *doc, int page_number, float zoom, int *out_size, int *error_code);
+// Page geometry as declared by the document. Boxes are x0, y0, x1, y1 in PDF
+// user space; crop_box is all zero when the page has none. user_unit is 0 when
+// the page has no /UserUnit. width and height are the displayed size in points
+// (fz_bound_page, which applies the crop box, /Rotate and /UserUnit). label is
+// empty when the document defines no labels.
+typedef struct {
+    float media_box[4];
+    float crop_box[4];
+    int rotation;
+    float user_unit;
+    float width;
+    float height;
+    char label[64];
+} mupdf_page_info;
+
+// Fills 'info' for one page. Returns MUPDF_SUCCESS or MUPDF_ERROR_INTERNAL.
+int mupdf_get_page_info(mupdf_bridge_context *ctx, mupdf_bridge_document *doc, int page_number, mupdf_page_info *info);
+
+// Colour modes for mupdf_render_page_to_rgba
+#define MUPDF_RENDER_NORMAL 0
+#define MUPDF_RENDER_GRAYSCALE 1
+#define MUPDF_RENDER_FADED 2     // 'amount' (0-1) blends towards white
+#define MUPDF_RENDER_INVERTED 3  // Luminance inverted, hues kept
+#define MUPDF_RENDER_MONOCHROME 4 // Luminance below 'amount' (0-1) -> black, else white
+
+// Renders a page scaled by 'zoom' to tightly packed 8-bit RGBA on an opaque
+// white background and returns the pointer to the pixels ('out_width' *
+// 'out_height' * 4 bytes). If 'width' and 'height' are positive only the pixel
+// rectangle (x, y, width, height) is rendered, relative to the top-left of the
+// scaled page; parts of it outside the page stay white. Fails with
+// MUPDF_ERROR_RENDER if the rectangle does not overlap the page at all.
+// 'mode' (MUPDF_RENDER_*) and 'amount' are applied to the rendered pixmap.
+// The 'hidden_count' layers listed in 'hidden_layers' (indices as reported by
+// mupdf_get_layer) are hidden for this render only; pass NULL, 0 for none.
+uint8_t *mupdf_render_page_to_rgba(mupdf_bridge_context *ctx, mupdf_bridge_document *doc, int page_number, float zoom, int x, int y, int width, int height, int mode, float amount, const int *hidden_layers, int hidden_count, int *out_width, int *out_height, int *error_code);
 
-// Must call this to free the PNG buffer returned by mupdf_render_page_to_png
+// Must call this to free the pixel buffer returned by mupdf_render_page_to_rgba
 void mupdf_free_buffer(uint8_t *buffer);
 
 // --- PDF Export Functions ---
@@ -90,18 +129,147 @@ char* mupdf_extract_page_text(
 // Free text buffer returned by mupdf_extract_page_text
 void mupdf_free_text(char *text);
 
-// Search for text on a page, returns number of hits found
-// out_quads: flat array of floats, 8 per hit (4 x,y corner points of quad)
-int mupdf_search_page(
+// Search for text on a page. Returns every hit quad as a flat array of
+// floats, 9 per quad: its 4 x,y corner points, then 1 if the quad starts a new
+// hit or 0 if it continues the previous one (a hit wrapping a line has a quad
+// per line). The quad count is stored in *out_count. Free with
+// mupdf_free_items(). Returns NULL on error.
+float *mupdf_search_page(
     mupdf_bridge_context *ctx,
     mupdf_bridge_document *doc,
     int page_idx,
     const char *needle,
-    float *out_quads,
-    int max_results
+    int *out_count
+);
+
+// A line of text with its bounding box (page space, top-left origin) and the
+// largest font size on it. Its UTF-8 text is text_length bytes at text_offset
+// in the buffer returned alongside.
+typedef struct {
+    float x0, y0, x1, y1;
+    float font_size;
+    int text_offset;
+    int text_length;
+} mupdf_text_line;
+
+// Extract the text lines of a page in reading order (no OCR).
+// 'out_text' receives the concatenated line texts, 'out_text_length' their
+// byte length (the text may contain NUL characters). Free both with
+// mupdf_free_text_lines(). Returns NULL on error.
+mupdf_text_line *mupdf_extract_page_lines(
+    mupdf_bridge_context *ctx,
+    mupdf_bridge_document *doc,
+    int page_idx,
+    int *out_count,
+    char **out_text,
+    int *out_text_length,
+    int *error_code
+);
+
+void mupdf_free_text_lines(mupdf_text_line *lines, char *text);
+
+// A character of structured text (page space, top-left origin). 'block' and
+// 'line' number the MuPDF text blocks and lines it belongs to, dir_x/dir_y is
+// the writing direction of its line and 'font' indexes the font names
+// returned alongside.
+typedef struct {
+    float x0, y0, x1, y1;
+    float size;
+    int c;
+    int block;
+    int line;
+    float dir_x, dir_y;
+    int font;
+} mupdf_text_char;
+
+// Extract every character of a page in reading order. With 'ocr' set, a page
+// with (almost) no text layer is read through OCR like mupdf_search_page does.
+// 'out_fonts' receives the names of the fonts used, one per line, in index
+// order. Free both with mupdf_free_items(). Returns NULL on error.
+mupdf_text_char *mupdf_extract_page_chars(
+    mupdf_bridge_context *ctx,
+    mupdf_bridge_document *doc,
+    int page_idx,
+    int ocr,
+    int *out_count,
+    char **out_fonts,
+    int *error_code
 );
 
 // Check if Tesseract OCR is available (built with tesseract=yes)
 int mupdf_has_ocr_support(void);
 
+// --- Vector Geometry Extraction ---
+
+#define MUPDF_SEGMENT_LINE 0
+#define MUPDF_SEGMENT_CURVE 1
+
+// A single path segment in page space (points, top-left origin, rotation applied).
+// Lines use (x0,y0)-(x1,y1). Curves are cubic beziers: start (x0,y0),
+// controls (x1,y1) and (x2,y2), end (x3,y3).
+typedef struct {
+    int kind;
+    float x0, y0, x1, y1, x2, y2, x3, y3;
+} mupdf_vector_segment;
+
+// Walks the page's display list and returns every stroked and filled path
+// flattened into line and curve segments. 'out_count' receives the number of segments.
+// Returns NULL with MUPDF_SUCCESS when the page has no vector content.
+// Caller must free the result with mupdf_free_vectors()
+mupdf_vector_segment *mupdf_extract_page_vectors(
+    mupdf_bridge_context *ctx,
+    mupdf_bridge_document *doc,
+    int page_idx,
+    int *out_count,
+    int *error_code
+);
+
+void mupdf_free_vectors(mupdf_vector_segment *segments);
+
+// --- Optional Content (Layers) ---
+
+// Number of optional content groups (layers) in the document; 0 for non-PDFs.
+int mupdf_count_layers(mupdf_bridge_context *ctx, mupdf_bridge_document *doc);
+
+// Copies the name of layer 'layer' into 'name_buf' (truncated to 'buf_size')
+// and whether it is visible by default into 'enabled'.
+// Returns MUPDF_SUCCESS, or MUPDF_ERROR_INTERNAL for an invalid index.
+int mupdf_get_layer(mupdf_bridge_context *ctx, mupdf_bridge_document *doc, int layer, char *name_buf, int buf_size, int *enabled);
+
+// --- Outline & Links ---
+
+// One bookmark of the flattened outline tree, in depth-first order. Its title
+// and URI are UTF-8 ranges of the text buffer returned alongside.
+typedef struct {
+    int level;   // 0 for top-level entries
+    int page;    // Target page, -1 for none (e.g. external URIs)
+    float x, y;  // Target position on the page
+    int is_open;
+    int title_offset;
+    int title_length;
+    int uri_offset;
+    int uri_length;
+} mupdf_outline_item;
+
+// A link annotation: its rect on the page and, for internal links, the target
+// page (-1 for external links) and position. Its URI is a range of the text
+// buffer returned alongside.
+typedef struct {
+    float x0, y0, x1, y1;
+    int target_page;
+    float target_x, target_y;
+    int uri_offset;
+    int uri_length;
+} mupdf_link_item;
+
+// Loads the document outline. Returns NULL on error; an empty outline gives
+// 'out_count' 0. Free with mupdf_free_items().
+mupdf_outline_item *mupdf_load_outline(mupdf_bridge_context *ctx, mupdf_bridge_document *doc, int *out_count, char **out_text, int *error_code);
+
+// Loads the links of a page. Free with mupdf_free_items().
+mupdf_link_item *mupdf_load_page_links(mupdf_bridge_context *ctx, mupdf_bridge_document *doc, int page_idx, int *out_count, char **out_text, int *error_code);
+
+// Frees the items and text buffer returned by the outline and link loaders
+void mupdf_free_items(void *items, char *text);
+
 #endif // MUPDF_BRIDGE_H
@@ -3,22 +3,33 @@
 //! # Safety Invariants
 //!
 //! ## Thread Safety Model
-//! MuPDF contexts are NOT inherently thread-safe. We ensure safety via:
-//! 1. `Mutex<MuPdfInner>` - Only one thread can access ctx/doc at a time
-//! 2. `Arc<RwLock<AppState>>` in Tauri - Protects the HashMap of documents
-//! 3. Protocol handler uses read lock only (no concurrent mutations)
+//! MuPDF contexts and documents are NOT inherently thread-safe. We ensure safety via:
+//! 1. `ContextPool<MuPdfInner>` - Each ctx/doc pair sits behind its own mutex,
+//!    so only one thread uses a given pair at a time
+//! 2. Worker pairs use contexts cloned from the primary one (sharing its
+//!    resource store through the C bridge's locking callbacks) and open their
+//!    own `fz_document`, so renders and text extraction run in parallel
+//! 3. `Arc<RwLock<AppState>>` in Tauri - Protects the HashMap of documents
+//! 4. Protocol handler uses read lock only (no concurrent mutations)
 //!
 //! ## Memory Management
 //! - `own_context: bool` tracks context ownership to prevent double-free
-//! - `Drop` impl releases resources in correct order (doc before ctx)
+//! - `Drop` impls release resources in correct order (doc before ctx,
+//!   cloned contexts before the primary context)
 //! - Sibling documents share context but don't own it
 //!
 //! ## Pointer Validity
 //! - Context pointer remains valid for document lifetime (checked in C)
 //! - Mutex prevents use-after-free via mutation lock
 
+use crate::context_pool::{self, ContextPool, PoolGuard};
+use crate::navigation::{self, OutlineEntry, PageLink};
+use crate::page_info::PageInfo;
+use crate::render_options::{self, RenderOptions, RenderedImage};
+use crate::search::{self, Matcher, SearchMatch};
+use crate::structured_text::{self, StructuredPage, TextChar};
 use std::error::Error;
-use std::ffi::CString;
+use std::ffi::{CStr, CString};
 use std::os::raw::{c_char, c_float, c_int, c_void};
 use std::sync::Mutex;
 
@@ -33,8 +44,76 @@ struct BridgeDocument(c_void);
 #[repr(C)]
 pub struct BridgeOverlay(c_void);
 
+const MUPDF_SEGMENT_CURVE: c_int = 1;
+
+/// Mirror of `mupdf_page_info` in mupdf_bridge.h
+#[repr(C)]
+struct BridgePageInfo {
+    media_box: [c_float; 4],
+    crop_box: [c_float; 4],
+    rotation: c_int,
+    user_unit: c_float,
+    width: c_float,
+    height: c_float,
+    label: [c_char; 64],
+}
+
+/// Mirror of `mupdf_text_line` in mupdf_bridge.h
+#[repr(C)]
+struct BridgeTextLine {
+    bbox: [c_float; 4],
+    font_size: c_float,
+    text_offset: c_int,
+    text_length: c_int,
+}
+
+/// Mirror of `mupdf_text_char` in mupdf_bridge.h
+#[repr(C)]
+struct BridgeTextChar {
+    bbox: [c_float; 4],
+    size: c_float,
+    c: c_int,
+    block: c_int,
+    line: c_int,
+    dir: [c_float; 2],
+    font: c_int,
+}
+
+/// Mirror of `mupdf_outline_item` in mupdf_bridge.h
+#[repr(C)]
+struct BridgeOutlineItem {
+    level: c_int,
+    page: c_int,
+    x: c_float,
+    y: c_float,
+    is_open: c_int,
+    title_offset: c_int,
+    title_length: c_int,
+    uri_offset: c_int,
+    uri_length: c_int,
+}
+
+/// Mirror of `mupdf_link_item` in mupdf_bridge.h
+#[repr(C)]
+struct BridgeLinkItem {
+    bbox: [c_float; 4],
+    target_page: c_int,
+    target_x: c_float,
+    target_y: c_float,
+    uri_offset: c_int,
+    uri_length: c_int,
+}
+
+/// Mirror of `mupdf_vector_segment` in mupdf_bridge.h
+#[repr(C)]
+struct BridgeVectorSegment {
+    kind: c_int,
+    points: [c_float; 8],
+}
+
 extern "C" {
     fn mupdf_new_context() -> *mut BridgeContext;
+    fn mupdf_clone_context(ctx: *mut BridgeContext) -> *mut BridgeContext;
     fn mupdf_drop_context(ctx: *mut BridgeContext);
     fn mupdf_open_document(
         ctx: *mut BridgeContext,
@@ -43,12 +122,27 @@ extern "C" {
     ) -> *mut BridgeDocument;
     fn mupdf_drop_document(ctx: *mut BridgeContext, doc: *mut BridgeDocument);
     fn mupdf_get_page_count(ctx: *mut BridgeContext, doc: *mut BridgeDocument) -> c_int;
-    fn mupdf_render_page_to_png(
+    fn mupdf_get_page_info(
+        ctx: *mut BridgeContext,
+        doc: *mut BridgeDocument,
+        page_number: c_int,
+        info: *mut BridgePageInfo,
+    ) -> c_int;
+    fn mupdf_render_page_to_rgba(
         ctx: *mut BridgeContext,
         doc: *mut BridgeDocument,
         page_number: c_int,
         zoom: c_float,
-        out_size: *mut c_int,
+        x: c_int,
+        y: c_int,
+        width: c_int,
+        height: c_int,
+        mode: c_int,
+        amount: c_float,
+        hidden_layers: *const c_int,
+        hidden_count: c_int,
+        out_width: *mut c_int,
+        out_height: *mut c_int,
         error_code: *mut c_int,
     ) -> *mut u8;
     fn mupdf_free_buffer(buffer: *mut u8);
@@ -158,56 +252,147 @@ extern "C" {
         doc: *mut BridgeDocument,
         page_idx: c_int,
         needle: *const c_char,
-        out_quads: *mut c_float,
-        max_results: c_int,
-    ) -> c_int;
+        out_count: *mut c_int,
+    ) -> *mut c_float;
+
+    fn mupdf_extract_page_lines(
+        ctx: *mut BridgeContext,
+        doc: *mut BridgeDocument,
+        page_idx: c_int,
+        out_count: *mut c_int,
+        out_text: *mut *mut c_char,
+        out_text_length: *mut c_int,
+        error_code: *mut c_int,
+    ) -> *mut BridgeTextLine;
+    fn mupdf_free_text_lines(lines: *mut BridgeTextLine, text: *mut c_char);
 
     fn mupdf_has_ocr_support() -> c_int;
+
+    // Vector geometry extraction
+    fn mupdf_extract_page_vectors(
+        ctx: *mut BridgeContext,
+        doc: *mut BridgeDocument,
+        page_idx: c_int,
+        out_count: *mut c_int,
+        error_code: *mut c_int,
+    ) -> *mut BridgeVectorSegment;
+
+    fn mupdf_free_vectors(segments: *mut BridgeVectorSegment);
+
+    fn mupdf_extract_page_chars(
+        ctx: *mut BridgeContext,
+        doc: *mut BridgeDocument,
+        page_idx: c_int,
+        ocr: c_int,
+        out_count: *mut c_int,
+        out_fonts: *mut *mut c_char,
+        error_code: *mut c_int,
+    ) -> *mut BridgeTextChar;
+
+    // Outline & links
+    fn mupdf_load_outline(
+        ctx: *mut BridgeContext,
+        doc: *mut BridgeDocument,
+        out_count: *mut c_int,
+        out_text: *mut *mut c_char,
+        error_code: *mut c_int,
+    ) -> *mut BridgeOutlineItem;
+    fn mupdf_load_page_links(
+        ctx: *mut BridgeContext,
+        doc: *mut BridgeDocument,
+        page_idx: c_int,
+        out_count: *mut c_int,
+        out_text: *mut *mut c_char,
+        error_code: *mut c_int,
+    ) -> *mut BridgeLinkItem;
+    fn mupdf_free_items(items: *mut c_void, text: *mut c_char);
+
+    // Optional content (layers)
+    fn mupdf_count_layers(ctx: *mut BridgeContext, doc: *mut BridgeDocument) -> c_int;
+    fn mupdf_get_layer(
+        ctx: *mut BridgeContext,
+        doc: *mut BridgeDocument,
+        layer: c_int,
+        name_buf: *mut c_char,
+        buf_size: c_int,
+        enabled: *mut c_int,
+    ) -> c_int;
 }
 
 struct MuPdfInner {
     ctx: *mut BridgeContext,
     doc: *mut BridgeDocument,
+    own_context: bool,
 }
 
-// SAFETY: Internal pointers are handled by Mutex in MuPdfDoc
+// SAFETY: Internal pointers are handled by the pool mutex in MuPdfDoc
 unsafe impl Send for MuPdfInner {}
 
+/// Releases the document, then the context if this pair owns it.
+impl Drop for MuPdfInner {
+    fn drop(&mut self) {
+        unsafe {
+            if !self.doc.is_null() {
+                mupdf_drop_document(self.ctx, self.doc);
+            }
+            if !self.ctx.is_null() && self.own_context {
+                mupdf_drop_context(self.ctx);
+            }
+        }
+    }
+}
+
+/// A context cloned from a document's primary context, not yet bound to a
+/// worker document.
+struct SpareContext(*mut BridgeContext);
+
+// SAFETY: Spare contexts are unused until moved into a worker under a mutex
+unsafe impl Send for SpareContext {}
+
+impl Drop for SpareContext {
+    fn drop(&mut self) {
+        unsafe { mupdf_drop_context(self.0) }
+    }
+}
+
 pub struct MuPdfDoc {
-    inner: Mutex<MuPdfInner>,
+    /// Slot 0 is the primary ctx/doc pair; the rest are workers opened on demand
+    pool: ContextPool<MuPdfInner>,
+    /// Contexts cloned up front for workers that have not been opened yet
+    spare_contexts: Mutex<Vec<SpareContext>>,
+    /// File the workers re-open; `None` for documents built in memory
+    path: Option<CString>,
     page_count: i32,
-    own_context: bool,
 }
 
-/// MuPdfDoc wraps raw C pointers to MuPDF context and document.
+/// MuPdfDoc wraps raw C pointers to MuPDF contexts and documents.
 ///
 /// # RAII Pattern
-/// Drop implementation ensures proper cleanup of C resources.
+/// Drop implementation ensures proper cleanup of C resources: everything
+/// cloned from the primary context is released before it.
 impl Drop for MuPdfDoc {
     fn drop(&mut self) {
-        if let Ok(inner) = self.inner.lock() {
-            unsafe {
-                if !inner.doc.is_null() {
-                    mupdf_drop_document(inner.ctx, inner.doc);
-                }
-                if !inner.ctx.is_null() && self.own_context {
-                    mupdf_drop_context(inner.ctx);
-                }
-            }
+        if let Ok(spares) = self.spare_contexts.get_mut() {
+            spares.clear();
+        }
+        // Workers first, primary last
+        for inner in self.pool.take_values() {
+            drop(inner);
         }
     }
 }
 
 // SAFETY: MuPdfDoc can be sent between threads because:
-// 1. The MuPDF C library's context is designed to be thread-safe when each thread
-//    uses its own context or the context is protected by external synchronization.
-// 2. We protect access via RwLock in AppState, ensuring single-writer/multi-reader.
-// 3. The ctx and doc pointers are only accessed through &self methods (immutable).
+// 1. Every ctx/doc pair is only reachable through its own mutex in the pool.
+// 2. Cloned contexts share state only through MuPDF's internal locking, which
+//    the C bridge backs with real mutexes.
+// 3. We protect access via RwLock in AppState, ensuring single-writer/multi-reader.
 unsafe impl Send for MuPdfDoc {}
 
 // SAFETY: MuPdfDoc can be shared between threads (&MuPdfDoc) because:
 // 1. All methods that access the pointers are &self (shared reference).
-// 2. The MuPDF rendering functions are thread-safe for read operations.
+// 2. Each call locks one ctx/doc pair for its whole duration, and no pair is
+//    used by two threads at once.
 // 3. External synchronization (RwLock) ensures no concurrent writes.
 unsafe impl Sync for MuPdfDoc {}
 
@@ -233,11 +418,15 @@ impl MuPdfDoc {
 
             let page_count = mupdf_get_page_count(ctx, doc);
 
-            Ok(Self {
-                inner: Mutex::new(MuPdfInner { ctx, doc }),
+            Ok(Self::with_workers(
+                MuPdfInner {
+                    ctx,
+                    doc,
+                    own_context: true,
+                },
+                Some(c_path),
                 page_count,
-                own_context: true,
-            })
+            ))
         }
     }
 
@@ -257,18 +446,92 @@ impl MuPdfDoc {
                 return Err(format!("Failed to create PDF (ec: {})", ec).into());
             }
 
-            Ok(Self {
-                inner: Mutex::new(MuPdfInner { ctx, doc }),
-                page_count: 0,
-                own_context: true,
-            })
+            Ok(Self::with_workers(
+                MuPdfInner {
+                    ctx,
+                    doc,
+                    own_context: true,
+                },
+                None,
+                0,
+            ))
+        }
+    }
+
+    /// Wrap a primary ctx/doc pair, cloning worker contexts when the document
+    /// is backed by a file the workers can re-open.
+    ///
+    /// Contexts are cloned here, before the primary context is shared with
+    /// other threads; opening their documents is deferred until a worker is
+    /// actually needed.
+    fn with_workers(primary: MuPdfInner, path: Option<CString>, page_count: i32) -> Self {
+        let mut spares = Vec::new();
+        if path.is_some() {
+            for _ in 1..context_pool::worker_count() {
+                // SAFETY: primary.ctx is a valid root context with locking installed
+                let ctx = unsafe { mupdf_clone_context(primary.ctx) };
+                if ctx.is_null() {
+                    break;
+                }
+                spares.push(SpareContext(ctx));
+            }
+        }
+
+        Self {
+            pool: ContextPool::new(primary, spares.len() + 1),
+            spare_contexts: Mutex::new(spares),
+            path,
+            page_count,
+        }
+    }
+
+    /// Open another copy of the document on a spare cloned context.
+    fn open_worker(&self) -> Result<MuPdfInner, String> {
+        let path = self.path.as_ref().ok_or("Document has no backing file")?;
+        let spare = self
+            .spare_contexts
+            .lock()
+            .map_err(|_| "Mutex poisoned")?
+            .pop()
+            .ok_or("No spare MuPDF context")?;
+
+        let mut error_code: c_int = 0;
+        // SAFETY: spare.0 is a valid cloned context used by this thread only
+        let doc = unsafe { mupdf_open_document(spare.0, path.as_ptr(), &mut error_code) };
+        if doc.is_null() {
+            // Keep the context for a later attempt
+            if let Ok(mut spares) = self.spare_contexts.lock() {
+                spares.push(spare);
+            }
+            return Err(format!(
+                "Failed to open worker document (error code: {})",
+                error_code
+            ));
         }
+
+        let ctx = spare.0;
+        std::mem::forget(spare);
+        Ok(MuPdfInner {
+            ctx,
+            doc,
+            own_context: true,
+        })
+    }
+
+    /// Any idle ctx/doc pair, opening a new worker when all are busy.
+    fn worker(&self) -> Result<PoolGuard<'_, MuPdfInner>, Box<dyn Error>> {
+        Ok(self.pool.acquire(|| self.open_worker())?)
+    }
+
+    /// Maximum number of threads that can use this document at once.
+    pub fn worker_capacity(&self) -> usize {
+        self.pool.capacity()
     }
 
     /// Open a document using the same context as this one.
     /// The returned doc shares the context (own_context=false) so it won't drop it.
     pub fn open_sibling(&self, path: &str) -> Result<Self, Box<dyn Error>> {
-        let inner = self.inner.lock().map_err(|_| "Mutex poisoned")?;
+        let inner = self.pool.primary()?;
         unsafe {
             let c_path = CString::new(path)?;
             let mut ec = 0;
@@ -277,14 +540,15 @@ impl MuPdfDoc {
                 return Err(format!("Failed to open sibling doc (ec: {})", ec).into());
             }
             let page_count = mupdf_get_page_count(inner.ctx, doc);
-            Ok(Self {
-                inner: Mutex::new(MuPdfInner {
+            Ok(Self::with_workers(
+                MuPdfInner {
                     ctx: inner.ctx,
                     doc,
-                }),
+                    own_context: false, // Do NOT drop context when this doc is dropped
+                },
+                None,
                 page_count,
-                own_context: false, // Do NOT drop context when this doc is dropped
-            })
+            ))
         }
     }
 
@@ -292,22 +556,93 @@ impl MuPdfDoc {
         self.page_count
     }
 
+    /// Boxes, rotation, UserUnit and label of a page.
+    pub fn page_info(&self, page_index: i32) -> Result<PageInfo, Box<dyn Error>> {
+        let inner = self.worker()?;
+        let mut raw = BridgePageInfo {
+            media_box: [0.0; 4],
+            crop_box: [0.0; 4],
+            rotation: 0,
+            user_unit: 0.0,
+            width: 0.0,
+            height: 0.0,
+            label: [0; 64],
+        };
+        let res = unsafe { mupdf_get_page_info(inner.ctx, inner.doc, page_index, &mut raw) };
+        if res != MUPDF_SUCCESS {
+            return Err(format!(
+                "Failed to read page {} info (error code: {})",
+                page_index, res
+            )
+            .into());
+        }
+
+        // SAFETY: C NUL-terminates the label within its buffer
+        let label = unsafe { CStr::from_ptr(raw.label.as_ptr()) }
+            .to_string_lossy()
+            .into_owned();
+        Ok(PageInfo::from_raw(
+            page_index,
+            raw.media_box,
+            raw.crop_box,
+            raw.rotation,
+            raw.user_unit,
+            (raw.width, raw.height),
+            Some(label),
+        ))
+    }
+
+    /// Render a page (or the pixel clip in `options`) with the requested
+    /// colour mode and encode it in the requested format.
     pub fn render_page_to_buffer(
         &self,
         page_index: i32,
-        zoom: f32,
-    ) -> Result<Vec<u8>, Box<dyn Error>> {
-        let inner = self.inner.lock().map_err(|_| "Mutex poisoned")?;
+        options: &RenderOptions,
+    ) -> Result<RenderedImage, Box<dyn Error>> {
+        let (pixels, width, height) = self.render_page_to_rgba(page_index, options)?;
+        Ok(render_options::encode_rgba(
+            pixels,
+            width,
+            height,
+            options.format,
+            options.quality,
+        )?)
+    }
+
+    /// Rasterise a page to tightly packed RGBA, returning (pixels, width, height).
+    ///
+    /// With a clip, `x`/`y` are relative to the top-left of the scaled page.
+    /// Used for tiled rendering, so only the visible part of a large sheet is
+    /// rasterised. Hidden layers are switched off on the worker's own document
+    /// for the duration of the render only.
+    fn render_page_to_rgba(
+        &self,
+        page_index: i32,
+        options: &RenderOptions,
+    ) -> Result<(Vec<u8>, u32, u32), Box<dyn Error>> {
+        let [x, y, width, height] = options.clip.unwrap_or([0, 0, 0, 0]);
+        let (mode, amount) = options.mode.to_bridge();
+        let inner = self.worker()?;
         unsafe {
-            let mut out_size: c_int = 0;
+            let mut out_width: c_int = 0;
+            let mut out_height: c_int = 0;
             let mut error_code: c_int = 0;
 
-            let ptr = mupdf_render_page_to_png(
+            let ptr = mupdf_render_page_to_rgba(
                 inner.ctx,
                 inner.doc,
                 page_index,
-                zoom,
-                &mut out_size,
+                options.zoom,
+                x,
+                y,
+                width,
+                height,
+                mode,
+                amount,
+                options.hidden_layers.as_ptr(),
+                options.hidden_layers.len() as c_int,
+                &mut out_width,
+                &mut out_height,
                 &mut error_code,
             );
 
@@ -319,18 +654,19 @@ impl MuPdfDoc {
                 .into());
             }
 
-            // Copy bytes before freeing - the slice is valid because out_size was set by C
-            let bytes = std::slice::from_raw_parts(ptr, out_size as usize).to_vec();
+            // Copy pixels before freeing - the size is valid because C set the dimensions
+            let len = out_width as usize * out_height as usize * 4;
+            let pixels = std::slice::from_raw_parts(ptr, len).to_vec();
             mupdf_free_buffer(ptr);
 
-            Ok(bytes)
+            Ok((pixels, out_width as u32, out_height as u32))
         }
     }
 
     // Export Helpers
 
     pub fn save(&self, path: &str) -> Result<(), Box<dyn Error>> {
-        let inner = self.inner.lock().map_err(|_| "Mutex poisoned")?;
+        let inner = self.pool.primary()?;
         unsafe {
             let c_path = CString::new(path)?;
             let res = mupdf_save_document(inner.ctx, inner.doc, c_path.as_ptr());
@@ -345,8 +681,8 @@ impl MuPdfDoc {
     }
 
     pub fn copy_page_from(&self, src: &MuPdfDoc, src_page_idx: i32) -> Result<(), Box<dyn Error>> {
-        let dest_inner = self.inner.lock().map_err(|_| "Dest Mutex poisoned")?;
-        let src_inner = src.inner.lock().map_err(|_| "Src Mutex poisoned")?;
+        let dest_inner = self.pool.primary()?;
+        let src_inner = src.pool.primary()?;
         unsafe {
             let mut ec = 0;
             let res = mupdf_copy_page(
@@ -364,7 +700,7 @@ impl MuPdfDoc {
     }
 
     pub fn begin_overlay(&self, page_idx: i32) -> Result<MuPdfOverlay, Box<dyn Error>> {
-        let inner = self.inner.lock().map_err(|_| "Mutex poisoned")?;
+        let inner = self.pool.primary()?;
         unsafe {
             let mut ec = 0;
             let overlay = mupdf_begin_overlay(inner.ctx, inner.doc, page_idx, &mut ec);
@@ -380,7 +716,7 @@ impl MuPdfDoc {
 
     /// Extract all text from a page as a string
     pub fn extract_text(&self, page_idx: i32) -> Result<String, Box<dyn Error>> {
-        let inner = self.inner.lock().map_err(|e| format!("Lock error: {}", e))?;
+        let inner = self.worker()?;
 
         let mut out_length: c_int = 0;
         let text_ptr = unsafe {
@@ -406,40 +742,426 @@ impl MuPdfDoc {
         Ok(text)
     }
 
-    /// Search for text on a specific page, returns hit quads
+    /// Search for text on a specific page, returns the quads of every hit
     pub fn search_page(&self, page_idx: i32, needle: &str) -> Result<Vec<SearchHit>, Box<dyn Error>> {
-        let inner = self.inner.lock().map_err(|e| format!("Lock error: {}", e))?;
+        Ok(self
+            .search_quads(page_idx, needle)?
+            .into_iter()
+            .map(|(hit, _)| hit)
+            .collect())
+    }
 
-        let c_needle = CString::new(needle)?;
-        const MAX_RESULTS: usize = 100;
-        let mut quads: [c_float; MAX_RESULTS * 8] = [0.0; MAX_RESULTS * 8];
+    /// Hit quads of a substring search, each flagged if it starts a new hit.
+    fn search_quads(&self, page_idx: i32, needle: &str) -> Result<Vec<(SearchHit, bool)>, Box<dyn Error>> {
+        let inner = self.worker()?;
 
-        let hit_count = unsafe {
+        let c_needle = CString::new(needle)?;
+        let mut quad_count: c_int = 0;
+        let ptr = unsafe {
             mupdf_search_page(
                 inner.ctx,
                 inner.doc,
                 page_idx,
                 c_needle.as_ptr(),
-                quads.as_mut_ptr(),
-                MAX_RESULTS as c_int,
+                &mut quad_count,
+            )
+        };
+        if ptr.is_null() {
+            return Err(format!("Search failed on page {}", page_idx).into());
+        }
+
+        // SAFETY: C guarantees 9 floats per quad at `ptr`, valid until freed
+        let results = unsafe {
+            let quads = std::slice::from_raw_parts(ptr, quad_count as usize * 9);
+            let result = quads
+                .chunks_exact(9)
+                .map(|q| {
+                    let hit = SearchHit {
+                        ul: (q[0], q[1]),
+                        ur: (q[2], q[3]),
+                        lr: (q[4], q[5]),
+                        ll: (q[6], q[7]),
+                    };
+                    (hit, q[8] != 0.0)
+                })
+                .collect();
+            mupdf_free_items(ptr as *mut c_void, std::ptr::null_mut());
+            result
+        };
+
+        Ok(results)
+    }
+
+    /// Extract the text lines of a page with their boxes, in reading order.
+    /// Coordinates are in PDF points with a top-left origin.
+    pub fn extract_lines(&self, page_idx: i32) -> Result<Vec<TextLine>, Box<dyn Error>> {
+        let inner = self.worker()?;
+
+        let mut count: c_int = 0;
+        let mut text: *mut c_char = std::ptr::null_mut();
+        let mut text_length: c_int = 0;
+        let mut error_code: c_int = 0;
+        let ptr = unsafe {
+            mupdf_extract_page_lines(
+                inner.ctx,
+                inner.doc,
+                page_idx,
+                &mut count,
+                &mut text,
+                &mut text_length,
+                &mut error_code,
+            )
+        };
+        if ptr.is_null() {
+            return Err(format!(
+                "Failed to extract text lines from page {} (error code: {})",
+                page_idx, error_code
+            )
+            .into());
+        }
+
+        // SAFETY: C guarantees `count` lines at `ptr` and `text_length` bytes
+        // at `text`, both valid until freed. The text can contain NULs, so it
+        // is not read as a C string.
+        let lines = unsafe {
+            let raw = std::slice::from_raw_parts(ptr, count as usize);
+            let bytes = std::slice::from_raw_parts(text as *const u8, text_length.max(0) as usize);
+            let result = raw
+                .iter()
+                .map(|line| TextLine {
+                    bbox: line.bbox,
+                    font_size: line.font_size,
+                    text: text_range(bytes, line.text_offset, line.text_length).unwrap_or_default(),
+                })
+                .collect();
+            mupdf_free_text_lines(ptr, text);
+            result
+        };
+
+        Ok(lines)
+    }
+
+    /// Extract the text of a page as blocks, lines and words with boxes and
+    /// fonts. Coordinates are in PDF points with a top-left origin.
+    pub fn structured_text(&self, page_idx: i32) -> Result<StructuredPage, Box<dyn Error>> {
+        let (chars, fonts) = self.page_chars(page_idx)?;
+        Ok(structured_text::build(page_idx, &chars, &fonts))
+    }
+
+    /// Search a page with a compiled query, returning each hit with its quads
+    /// and matched text. Plain queries use MuPDF's search and report the
+    /// query itself, as MuPDF does not return what it matched; patterns run
+    /// over the page's characters. Both read scanned pages through OCR.
+    pub fn search_matches(
+        &self,
+        page_idx: i32,
+        matcher: &Matcher,
+    ) -> Result<Vec<SearchMatch>, Box<dyn Error>> {
+        match matcher {
+            Matcher::Plain(query) => Ok(search::group_quads(self.search_quads(page_idx, query)?, query)),
+            Matcher::Pattern { regex, whole_word } => {
+                let (chars, _) = self.chars(page_idx, true)?;
+                Ok(search::find_matches(&chars, regex, *whole_word)
+                    .into_iter()
+                    .map(|(hit, text)| SearchMatch {
+                        quads: vec![hit],
+                        text,
+                    })
+                    .collect())
+            }
+        }
+    }
+
+    /// Every character of a page in reading order, with the names of the
+    /// fonts they index. Coordinates are in PDF points with a top-left origin.
+    pub fn page_chars(&self, page_idx: i32) -> Result<(Vec<TextChar>, Vec<String>), Box<dyn Error>> {
+        self.chars(page_idx, false)
+    }
+
+    /// `page_chars`, reading pages without a text layer through OCR if `ocr`.
+    fn chars(&self, page_idx: i32, ocr: bool) -> Result<(Vec<TextChar>, Vec<String>), Box<dyn Error>> {
+        let inner = self.worker()?;
+
+        let mut count: c_int = 0;
+        let mut fonts: *mut c_char = std::ptr::null_mut();
+        let mut error_code: c_int = 0;
+        let ptr = unsafe {
+            mupdf_extract_page_chars(
+                inner.ctx,
+                inner.doc,
+                page_idx,
+                ocr as c_int,
+                &mut count,
+                &mut fonts,
+                &mut error_code,
+            )
+        };
+        if ptr.is_null() {
+            return Err(format!(
+                "Failed to extract structured text from page {} (error code: {})",
+                page_idx, error_code
+            )
+            .into());
+        }
+
+        // SAFETY: C guarantees `count` chars at `ptr` and a NUL-terminated
+        // `fonts`, both valid until freed
+        let (chars, font_names) = unsafe {
+            let raw = std::slice::from_raw_parts(ptr, count as usize);
+            let chars: Vec<TextChar> = raw
+                .iter()
+                .map(|ch| TextChar {
+                    bbox: ch.bbox,
+                    size: ch.size,
+                    c: char::from_u32(ch.c as u32).unwrap_or(char::REPLACEMENT_CHARACTER),
+                    block: ch.block,
+                    line: ch.line,
+                    dir: (ch.dir[0], ch.dir[1]),
+                    font: ch.font.max(0) as usize,
+                })
+                .collect();
+            let font_names: Vec<String> = CStr::from_ptr(fonts)
+                .to_string_lossy()
+                .split('\n')
+                .map(str::to_string)
+                .collect();
+            mupdf_free_items(ptr as *mut c_void, fonts);
+            (chars, font_names)
+        };
+
+        Ok((chars, font_names))
+    }
+
+    /// Extract the vector linework (stroked and filled paths) of a page.
+    /// Coordinates are in PDF points with a top-left origin.
+    pub fn extract_vectors(&self, page_idx: i32) -> Result<Vec<PathSegment>, Box<dyn Error>> {
+        let inner = self.worker()?;
+
+        let mut count: c_int = 0;
+        let mut error_code: c_int = 0;
+        let ptr = unsafe {
+            mupdf_extract_page_vectors(
+                inner.ctx,
+                inner.doc,
+                page_idx,
+                &mut count,
+                &mut error_code,
+            )
+        };
+
+        if ptr.is_null() {
+            if error_code != MUPDF_SUCCESS {
+                return Err(format!(
+                    "Failed to extract vectors from page {} (error code: {})",
+                    page_idx, error_code
+                )
+                .into());
+            }
+            return Ok(Vec::new());
+        }
+
+        // SAFETY: C guarantees `count` valid segments at `ptr` until freed
+        let segments = unsafe {
+            let raw = std::slice::from_raw_parts(ptr, count as usize);
+            let result = raw.iter().map(PathSegment::from_raw).collect();
+            mupdf_free_vectors(ptr);
+            result
+        };
+
+        Ok(segments)
+    }
+
+    /// The document outline (bookmarks) as a tree. Empty when there is none.
+    pub fn outline(&self) -> Result<Vec<OutlineEntry>, Box<dyn Error>> {
+        let inner = self.worker()?;
+
+        let mut count: c_int = 0;
+        let mut text: *mut c_char = std::ptr::null_mut();
+        let mut error_code: c_int = 0;
+        let ptr = unsafe {
+            mupdf_load_outline(inner.ctx, inner.doc, &mut count, &mut text, &mut error_code)
+        };
+        if ptr.is_null() {
+            return Err(format!("Failed to load outline (error code: {})", error_code).into());
+        }
+
+        // SAFETY: C guarantees `count` items at `ptr` whose ranges lie within
+        // the NUL-terminated `text`, both valid until freed
+        let flat = unsafe {
+            let raw = std::slice::from_raw_parts(ptr, count as usize);
+            let bytes = CStr::from_ptr(text).to_bytes();
+            let result = raw
+                .iter()
+                .map(|item| {
+                    let entry = OutlineEntry {
+                        title: text_range(bytes, item.title_offset, item.title_length)
+                            .unwrap_or_default(),
+                        page: (item.page >= 0).then_some(item.page),
+                        x: item.x,
+                        y: item.y,
+                        uri: text_range(bytes, item.uri_offset, item.uri_length),
+                        open: item.is_open != 0,
+                        children: Vec::new(),
+                    };
+                    (item.level.max(0) as usize, entry)
+                })
+                .collect();
+            mupdf_free_items(ptr as *mut c_void, text);
+            result
+        };
+
+        Ok(navigation::build_tree(flat))
+    }
+
+    /// Link annotations of a page, in PDF points with a top-left origin.
+    pub fn page_links(&self, page_idx: i32) -> Result<Vec<PageLink>, Box<dyn Error>> {
+        let inner = self.worker()?;
+
+        let mut count: c_int = 0;
+        let mut text: *mut c_char = std::ptr::null_mut();
+        let mut error_code: c_int = 0;
+        let ptr = unsafe {
+            mupdf_load_page_links(
+                inner.ctx,
+                inner.doc,
+                page_idx,
+                &mut count,
+                &mut text,
+                &mut error_code,
             )
         };
+        if ptr.is_null() {
+            return Err(format!(
+                "Failed to load links of page {} (error code: {})",
+                page_idx, error_code
+            )
+            .into());
+        }
+
+        // SAFETY: as in `outline`
+        let links = unsafe {
+            let raw = std::slice::from_raw_parts(ptr, count as usize);
+            let bytes = CStr::from_ptr(text).to_bytes();
+            let result = raw
+                .iter()
+                .map(|item| PageLink {
+                    bbox: item.bbox,
+                    target_page: (item.target_page >= 0).then_some(item.target_page),
+                    target_x: item.target_x,
+                    target_y: item.target_y,
+                    uri: text_range(bytes, item.uri_offset, item.uri_length),
+                })
+                .collect();
+            mupdf_free_items(ptr as *mut c_void, text);
+            result
+        };
+
+        Ok(links)
+    }
+
+    /// List the optional content groups (layers) of the document with their
+    /// default visibility. Empty for documents without layers.
+    pub fn list_layers(&self) -> Result<Vec<LayerInfo>, Box<dyn Error>> {
+        let inner = self.worker()?;
 
-        let mut results = Vec::new();
-        for i in 0..(hit_count as usize).min(MAX_RESULTS) {
-            let base = i * 8;
-            results.push(SearchHit {
-                ul: (quads[base], quads[base + 1]),
-                ur: (quads[base + 2], quads[base + 3]),
-                lr: (quads[base + 4], quads[base + 5]),
-                ll: (quads[base + 6], quads[base + 7]),
+        let count = unsafe { mupdf_count_layers(inner.ctx, inner.doc) };
+        let mut layers = Vec::with_capacity(count.max(0) as usize);
+        let mut name_buf = [0 as c_char; 256];
+        for index in 0..count {
+            let mut enabled: c_int = 0;
+            let res = unsafe {
+                mupdf_get_layer(
+                    inner.ctx,
+                    inner.doc,
+                    index,
+                    name_buf.as_mut_ptr(),
+                    name_buf.len() as c_int,
+                    &mut enabled,
+                )
+            };
+            if res != MUPDF_SUCCESS {
+                return Err(format!("Failed to read layer {} (error code: {})", index, res).into());
+            }
+            // SAFETY: C always NUL-terminates within buf_size
+            let name = unsafe { CStr::from_ptr(name_buf.as_ptr()) }
+                .to_string_lossy()
+                .into_owned();
+            layers.push(LayerInfo {
+                index,
+                name,
+                visible: enabled != 0,
             });
         }
 
-        Ok(results)
+        Ok(layers)
+    }
+}
+
+/// An optional content group (PDF layer)
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct LayerInfo {
+    /// Index to pass back when hiding the layer
+    pub index: i32,
+    pub name: String,
+    /// Visibility in the document's default configuration
+    pub visible: bool,
+}
+
+/// A single segment of page linework, in PDF points
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PathSegment {
+    Line {
+        from: (f32, f32),
+        to: (f32, f32),
+    },
+    /// Cubic bezier curve
+    Curve {
+        from: (f32, f32),
+        ctrl1: (f32, f32),
+        ctrl2: (f32, f32),
+        to: (f32, f32),
+    },
+}
+
+impl PathSegment {
+    fn from_raw(raw: &BridgeVectorSegment) -> Self {
+        let p = &raw.points;
+        if raw.kind == MUPDF_SEGMENT_CURVE {
+            PathSegment::Curve {
+                from: (p[0], p[1]),
+                ctrl1: (p[2], p[3]),
+                ctrl2: (p[4], p[5]),
+                to: (p[6], p[7]),
+            }
+        } else {
+            PathSegment::Line {
+                from: (p[0], p[1]),
+                to: (p[2], p[3]),
+            }
+        }
     }
 }
 
+/// UTF-8 text of a bridge string range, `None` when empty.
+fn text_range(bytes: &[u8], offset: c_int, length: c_int) -> Option<String> {
+    let start = offset.max(0) as usize;
+    let end = start + length.max(0) as usize;
+    bytes
+        .get(start..end)
+        .filter(|range| !range.is_empty())
+        .map(|range| String::from_utf8_lossy(range).into_owned())
+}
+
+/// A line of page text, in PDF points
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct TextLine {
+    /// `[x0, y0, x1, y1]`
+    pub bbox: [f32; 4],
+    /// Largest font size on the line
+    pub font_size: f32,
+    pub text: String,
+}
+
 /// A text search hit with bounding quad coordinates
 #[derive(Debug, Clone, serde::Serialize)]
 pub struct SearchHit {
@@ -599,3 +1321,27 @@ impl MuPdfOverlay {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture(name: &str) -> String {
+        format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), name)
+    }
+
+    #[test]
+    fn test_page_info_of_user_unit_page() {
+        // MediaBox 1728 x 432 at /UserUnit 10, rotated 90: a 240 x 60 in sheet
+        let doc = MuPdfDoc::open(&fixture("user_unit.pdf")).unwrap();
+        let info = doc.page_info(0).unwrap();
+        assert_eq!(info.user_unit, 10.0);
+        assert_eq!(info.rotation, 90);
+        assert_eq!((info.width.round(), info.height.round()), (4320.0, 17280.0));
+        assert_eq!(info.size_inches(), (60.0, 240.0));
+
+        // Renders measure the same points as the reported size
+        let (_, width, height) = doc.render_page_to_rgba(0, &RenderOptions::new(0.1)).unwrap();
+        assert_eq!((width, height), (432, 1728));
+    }
+}
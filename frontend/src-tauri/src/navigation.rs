@@ -0,0 +1,127 @@
+//! Navigation Module
+//!
+//! Document outline (bookmarks) and page link annotations, used to jump
+//! between sheets and from detail callouts to their details. The bridge
+//! returns the outline flattened depth-first with a level per entry; it is
+//! rebuilt into a tree here.
+
+use serde::Serialize;
+
+/// One bookmark and its children.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct OutlineEntry {
+    pub title: String,
+    /// Target page, `None` for bookmarks without one (e.g. web links)
+    pub page: Option<i32>,
+    /// Target position on the page
+    pub x: f32,
+    pub y: f32,
+    pub uri: Option<String>,
+    /// Expanded by default
+    pub open: bool,
+    pub children: Vec<OutlineEntry>,
+}
+
+/// A link annotation on a page.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PageLink {
+    /// `[x0, y0, x1, y1]` of the clickable area
+    pub bbox: [f32; 4],
+    /// Target page of internal links
+    pub target_page: Option<i32>,
+    pub target_x: f32,
+    pub target_y: f32,
+    pub uri: Option<String>,
+}
+
+impl OutlineEntry {
+    /// Multiply target positions by `factor`, recursively.
+    pub fn scale(&mut self, factor: f32) {
+        self.x *= factor;
+        self.y *= factor;
+        for child in &mut self.children {
+            child.scale(factor);
+        }
+    }
+}
+
+impl PageLink {
+    /// Multiply the link area and target position by `factor`.
+    pub fn scale(&mut self, factor: f32) {
+        for v in &mut self.bbox {
+            *v *= factor;
+        }
+        self.target_x *= factor;
+        self.target_y *= factor;
+    }
+}
+
+/// Rebuild the tree from `(level, entry)` pairs in depth-first order. A level
+/// deeper than the previous entry's level + 1 is treated as a direct child.
+pub fn build_tree(flat: Vec<(usize, OutlineEntry)>) -> Vec<OutlineEntry> {
+    let mut roots = Vec::new();
+    // Entries still open for children, one per level
+    let mut open: Vec<OutlineEntry> = Vec::new();
+
+    fn close(open: &mut Vec<OutlineEntry>, roots: &mut Vec<OutlineEntry>) {
+        if let Some(done) = open.pop() {
+            match open.last_mut() {
+                Some(parent) => parent.children.push(done),
+                None => roots.push(done),
+            }
+        }
+    }
+
+    for (level, entry) in flat {
+        while open.len() > level {
+            close(&mut open, &mut roots);
+        }
+        open.push(entry);
+    }
+    while !open.is_empty() {
+        close(&mut open, &mut roots);
+    }
+    roots
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(title: &str, page: Option<i32>) -> OutlineEntry {
+        OutlineEntry {
+            title: title.to_string(),
+            page,
+            x: 10.0,
+            y: 20.0,
+            uri: None,
+            open: false,
+            children: Vec::new(),
+        }
+    }
+
+    fn sample() -> Vec<OutlineEntry> {
+        build_tree(vec![
+            (0, entry("Architectural", Some(0))),
+            (1, entry("A-101 Floor Plan", Some(0))),
+            (1, entry("A-201 Elevations", Some(1))),
+            (3, entry("Detail 4", Some(1))),
+            (0, entry("Electrical", Some(2))),
+            (0, entry("Website", None)),
+        ])
+    }
+
+    #[test]
+    fn test_build_tree() {
+        let tree = sample();
+        let titles: Vec<&str> = tree.iter().map(|e| e.title.as_str()).collect();
+        assert_eq!(titles, vec!["Architectural", "Electrical", "Website"]);
+        assert_eq!(tree[0].children.len(), 2);
+        assert_eq!(tree[0].children[1].children[0].title, "Detail 4");
+        assert!(build_tree(Vec::new()).is_empty());
+
+        let mut scaled = tree[0].clone();
+        scaled.scale(1.5);
+        assert_eq!((scaled.children[1].children[0].x, scaled.y), (15.0, 30.0));
+    }
+}
@@ -0,0 +1,177 @@
+//! Page Info Module
+//!
+//! Geometry of a page as the PDF declares it: media and crop boxes, `/Rotate`
+//! and `/UserUnit`, plus its page label. The frontend needs these to convert
+//! between rendered pixels and paper inches on rotated or oversized sheets.
+//!
+//! MuPDF's page transform applies `/UserUnit`, so rendered points are always
+//! 1/72 paper inch; an oversized sheet simply renders larger.
+
+use serde::Serialize;
+
+/// Rendered points per paper inch
+pub const POINTS_PER_INCH: f32 = 72.0;
+
+/// Metadata of one page. Boxes are `[x0, y0, x1, y1]` in PDF user space
+/// (bottom-left origin, unrotated).
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PageInfo {
+    pub page_idx: i32,
+    pub media_box: [f32; 4],
+    /// Crop box clipped to the media box (the media box when absent)
+    pub crop_box: [f32; 4],
+    /// Clockwise rotation applied when displaying: 0, 90, 180 or 270
+    pub rotation: i32,
+    /// Size of one user-space unit in 1/72 inch (1 unless the page is oversized)
+    pub user_unit: f32,
+    /// Displayed size in points (crop box after rotation and `/UserUnit`),
+    /// which is what renders at zoom 1 measure in pixels
+    pub width: f32,
+    pub height: f32,
+    /// Page label such as "A-101" or "iv", when the document defines one
+    pub label: Option<String>,
+}
+
+impl PageInfo {
+    /// Build from the raw values read from the page dictionary. `crop_box`
+    /// may be empty, `rotation` any multiple of 90 (including negative) and
+    /// `user_unit` 0 when absent. `size` is the displayed size MuPDF bounds
+    /// the page to; when it is empty it is derived from the boxes.
+    pub fn from_raw(
+        page_idx: i32,
+        media_box: [f32; 4],
+        crop_box: [f32; 4],
+        rotation: i32,
+        user_unit: f32,
+        size: (f32, f32),
+        label: Option<String>,
+    ) -> Self {
+        let media_box = normalize_box(media_box);
+        let crop_box = intersect(normalize_box(crop_box), media_box).unwrap_or(media_box);
+        let rotation = normalize_rotation(rotation);
+        let user_unit = if user_unit.is_finite() && user_unit > 0.0 {
+            user_unit
+        } else {
+            1.0
+        };
+
+        let (width, height) = if size.0 > 0.0 && size.1 > 0.0 {
+            size
+        } else {
+            let w = (crop_box[2] - crop_box[0]) * user_unit;
+            let h = (crop_box[3] - crop_box[1]) * user_unit;
+            if rotation % 180 == 0 {
+                (w, h)
+            } else {
+                (h, w)
+            }
+        };
+
+        Self {
+            page_idx,
+            media_box,
+            crop_box,
+            rotation,
+            user_unit,
+            width,
+            height,
+            label: label.filter(|l| !l.is_empty()),
+        }
+    }
+
+    /// Displayed size in paper inches.
+    pub fn size_inches(&self) -> (f32, f32) {
+        (self.width / POINTS_PER_INCH, self.height / POINTS_PER_INCH)
+    }
+}
+
+/// Reduce any `/Rotate` value to 0, 90, 180 or 270. Values that are not a
+/// multiple of 90 are invalid and treated as 0, as viewers do.
+pub fn normalize_rotation(rotation: i32) -> i32 {
+    if rotation % 90 != 0 {
+        return 0;
+    }
+    rotation.rem_euclid(360)
+}
+
+/// Order a box's corners so x0 <= x1 and y0 <= y1.
+fn normalize_box([x0, y0, x1, y1]: [f32; 4]) -> [f32; 4] {
+    [x0.min(x1), y0.min(y1), x0.max(x1), y0.max(y1)]
+}
+
+/// Intersection of two normalized boxes, `None` when it is empty.
+fn intersect(a: [f32; 4], b: [f32; 4]) -> Option<[f32; 4]> {
+    let r = [a[0].max(b[0]), a[1].max(b[1]), a[2].min(b[2]), a[3].min(b[3])];
+    (r[0] < r[2] && r[1] < r[3]).then_some(r)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ARCH_D: [f32; 4] = [0.0, 0.0, 2592.0, 1728.0]; // 36x24 in
+
+    #[test]
+    fn test_rotation_and_crop() {
+        let info = PageInfo::from_raw(
+            0,
+            ARCH_D,
+            [0.0; 4],
+            -90,
+            0.0,
+            (0.0, 0.0),
+            Some(String::new()),
+        );
+        assert_eq!(info.rotation, 270);
+        assert_eq!(info.crop_box, ARCH_D);
+        assert_eq!((info.width, info.height), (1728.0, 2592.0));
+        assert_eq!(info.user_unit, 1.0);
+        assert_eq!(info.label, None);
+
+        // Crop box is clipped to the media box and may be given corner-swapped
+        let info = PageInfo::from_raw(
+            1,
+            ARCH_D,
+            [2600.0, 1700.0, 100.0, 28.0],
+            180,
+            1.0,
+            (0.0, 0.0),
+            None,
+        );
+        assert_eq!(info.crop_box, [100.0, 28.0, 2592.0, 1700.0]);
+        assert_eq!((info.width, info.height), (2492.0, 1672.0));
+
+        assert_eq!(normalize_rotation(450), 90);
+        assert_eq!(normalize_rotation(45), 0);
+    }
+
+    #[test]
+    fn test_user_unit_scales_inches() {
+        // A 20 ft banner stored at UserUnit 10 (each unit is 10/72 in)
+        let info = PageInfo::from_raw(
+            0,
+            [0.0, 0.0, 1728.0, 432.0],
+            [0.0; 4],
+            0,
+            10.0,
+            (0.0, 0.0),
+            Some("E-1".into()),
+        );
+        assert_eq!((info.width, info.height), (17280.0, 4320.0));
+        let (w, h) = info.size_inches();
+        assert!((w - 240.0).abs() < 1e-3 && (h - 60.0).abs() < 1e-3);
+        assert_eq!(info.label.as_deref(), Some("E-1"));
+
+        // The size MuPDF reports wins over the one derived from the boxes
+        let info = PageInfo::from_raw(
+            0,
+            [0.0, 0.0, 1728.0, 432.0],
+            [0.0; 4],
+            90,
+            10.0,
+            (4320.0, 17280.0),
+            None,
+        );
+        assert_eq!(info.size_inches(), (60.0, 240.0));
+    }
+}
@@ -0,0 +1,116 @@
+//! Page Text Module
+//!
+//! Per-page text of an ingested document. Extraction output is kept per page
+//! so index snippets can be located, and pages whose text came from OCR are
+//! flagged.
+
+use serde::{Deserialize, Serialize};
+
+/// Native text shorter than this (non-whitespace characters) means the page
+/// has no usable text layer, so its extracted text came from OCR
+const MIN_NATIVE_CHARS: usize = 20;
+
+/// Text of one page.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PageText {
+    pub page_idx: i32,
+    pub text: String,
+    /// The text was recognised from the page image
+    #[serde(default)]
+    pub ocr: bool,
+}
+
+impl PageText {
+    pub fn new(page_idx: i32, text: String) -> Self {
+        Self {
+            page_idx,
+            text,
+            ocr: false,
+        }
+    }
+}
+
+/// Split extraction output into pages on form feeds, the page separator of
+/// plain-text extraction (which also ends the last page). Text without one
+/// is a single page.
+pub fn split_pages(content: &str) -> Vec<PageText> {
+    content
+        .strip_suffix('\u{c}')
+        .unwrap_or(content)
+        .split('\u{c}')
+        .enumerate()
+        .map(|(i, text)| PageText::new(i as i32, text.to_string()))
+        .collect()
+}
+
+/// Text of every page for indexing: the extracted text where there is some,
+/// otherwise the native text layer (`native[i]` for page `i`).
+pub fn page_texts(pages: &[PageText], native: &[String]) -> Vec<String> {
+    let count = pages
+        .iter()
+        .map(|page| page.page_idx.max(0) as usize + 1)
+        .max()
+        .unwrap_or(0)
+        .max(native.len());
+    let mut texts = native.to_vec();
+    texts.resize(count, String::new());
+    for page in pages {
+        if !page.text.trim().is_empty() {
+            texts[page.page_idx.max(0) as usize] = page.text.clone();
+        }
+    }
+    texts
+}
+
+/// Flag pages whose native text layer (`native[i]` for page `i`, e.g. from
+/// MuPDF) is empty while extraction produced text. Without native text to
+/// compare against (`native` empty, e.g. the document is not open) no page
+/// is flagged.
+pub fn mark_ocr(pages: &mut [PageText], native: &[String]) {
+    if native.is_empty() {
+        return;
+    }
+    for page in pages {
+        let native_chars = native
+            .get(page.page_idx as usize)
+            .map_or(0, |text| text.chars().filter(|c| !c.is_whitespace()).count());
+        page.ocr = native_chars < MIN_NATIVE_CHARS && !page.text.trim().is_empty();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_pages() {
+        let pages = split_pages("FIRST FLOOR PLAN\u{c}SECOND FLOOR PLAN\u{c}");
+        assert_eq!(pages.len(), 2);
+        assert_eq!(pages[1].page_idx, 1);
+        assert_eq!(pages[1].text, "SECOND FLOOR PLAN");
+        assert_eq!(split_pages("one page").len(), 1);
+
+        let native = vec!["native 0".to_string(), "native 1".to_string()];
+        let texts = page_texts(&[PageText::new(1, "ocr 1".to_string()), PageText::new(2, " ".to_string())], &native);
+        assert_eq!(texts, vec!["native 0", "ocr 1", ""]);
+    }
+
+    #[test]
+    fn test_mark_ocr() {
+        let mut pages = vec![
+            PageText::new(0, "GENERAL NOTES 1. ALL WORK PER CODE".to_string()),
+            PageText::new(1, "FIRST FLOOR PLAN A-101 SCALE 1/4\" = 1'-0\"".to_string()),
+            PageText::new(2, "~~ |l| %$# rrt Wl, ;;".to_string()),
+        ];
+        let native = vec!["GENERAL NOTES 1. ALL WORK PER CODE".to_string(), String::new()];
+        mark_ocr(&mut pages, &native);
+
+        assert!(!pages[0].ocr);
+        assert!(pages[1].ocr && pages[2].ocr);
+
+        // Without native text nothing is known to be OCR'd
+        let mut pages = vec![PageText::new(0, "FIRST FLOOR PLAN".to_string())];
+        mark_ocr(&mut pages, &[]);
+        assert!(!pages[0].ocr);
+    }
+}
@@ -3,8 +3,10 @@
 //! Handles reading and writing .ptf (ProTakeoff File) project files.
 //! Cross-platform compatible for macOS and Windows App Store submissions.
 
+use crate::doc_state::AppState;
 use std::fs;
 use std::path::{Path, PathBuf};
+use tauri::State;
 
 /// Validates a file path for security issues.
 ///
@@ -170,8 +172,11 @@ pub async fn save_project_file(path: String, data: String) -> Result<(), String>
 ///
 /// # Returns
 /// * JSON string containing the project data
+///
+/// Embedded PDFs are extracted to temp files, which are registered in
+/// `AppState::temp_files` so `close_document` can delete them.
 #[tauri::command]
-pub async fn load_project_file(path: String) -> Result<String, String> {
+pub async fn load_project_file(path: String, state: State<'_, AppState>) -> Result<String, String> {
     // Security: Validate path before any operations
     let validated_path = validate_path_security(&path)?;
     let file_path = validated_path.as_path();
@@ -235,6 +240,7 @@ pub async fn load_project_file(path: String) -> Result<String, String> {
                                     if file.write_all(&bytes).is_ok() {
                                         let path_str = file_path.to_string_lossy().to_string();
                                         extracted_paths.insert(pdf_id.clone(), path_str.clone());
+                                        register_temp_file(&state, &pdf_id, file_path.clone());
                                         println!(
                                             "[ProjectIO] Extracted PDF {} to {}",
                                             pdf_id, path_str
@@ -280,6 +286,31 @@ pub async fn load_project_file(path: String) -> Result<String, String> {
     Ok(optimized_json)
 }
 
+/// Remember the temp file extracted for a document, replacing (and deleting)
+/// any previous extraction for the same id.
+fn register_temp_file(state: &AppState, pdf_id: &str, path: PathBuf) {
+    let previous = match state.temp_files.write() {
+        Ok(mut temp_files) => temp_files.insert(pdf_id.to_string(), path),
+        Err(_) => return,
+    };
+    if let Some(old) = previous {
+        remove_temp_file(&old);
+    }
+}
+
+/// Delete a temp file created by this module, logging rather than failing.
+pub fn remove_temp_file(path: &Path) {
+    match fs::remove_file(path) {
+        Ok(()) => println!("[ProjectIO] Removed temp file: {}", path.display()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => println!(
+            "[ProjectIO] Failed to remove temp file {}: {}",
+            path.display(),
+            e
+        ),
+    }
+}
+
 /// Read a file as base64-encoded string (for embedding PDFs)
 ///
 /// # Arguments
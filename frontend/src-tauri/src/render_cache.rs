@@ -0,0 +1,358 @@
+//! Render Cache Module
+//!
+//! In-memory LRU of encoded page and tile renders. Panning back over a sheet
+//! re-requests the same tiles, and MuPDF rasterisation dominates their cost,
+//! so finished images are kept until the byte budget forces them out.
+//!
+//! Each document has a generation that eviction bumps. A render started
+//! before the document was replaced or closed is returned to its caller but
+//! not cached, so a reused id never serves the old document's pages.
+
+use crate::render_options::{RenderOptions, RenderedImage};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+
+/// Default memory budget for cached renders
+pub const DEFAULT_BUDGET_BYTES: usize = 256 * 1024 * 1024;
+
+/// Identifies one rendered image.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RenderKey {
+    pub doc_id: String,
+    pub page: i32,
+    pub options: RenderOptions,
+}
+
+impl RenderKey {
+    pub fn new(doc_id: &str, page: i32, options: RenderOptions) -> Self {
+        Self {
+            doc_id: doc_id.to_string(),
+            page,
+            options,
+        }
+    }
+
+    /// Whole page as PNG.
+    pub fn page(doc_id: &str, page: i32, zoom: f32) -> Self {
+        Self::new(doc_id, page, RenderOptions::new(zoom))
+    }
+
+    /// Pixel rectangle (x, y, width, height) of the page as PNG.
+    pub fn clip(doc_id: &str, page: i32, zoom: f32, clip: [i32; 4]) -> Self {
+        Self::new(doc_id, page, RenderOptions::new(zoom).with_clip(clip))
+    }
+}
+
+/// Cache counters reported to the frontend.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct RenderCacheStats {
+    pub entries: usize,
+    pub bytes: usize,
+    pub budget_bytes: usize,
+    pub hits: u64,
+    pub misses: u64,
+    pub evictions: u64,
+}
+
+struct Entry {
+    data: Arc<RenderedImage>,
+    /// Position in `CacheInner::lru`
+    last_used: u64,
+}
+
+struct CacheInner {
+    entries: HashMap<RenderKey, Entry>,
+    /// Access tick -> key, oldest first
+    lru: BTreeMap<u64, RenderKey>,
+    tick: u64,
+    bytes: usize,
+    budget_bytes: usize,
+    hits: u64,
+    misses: u64,
+    evictions: u64,
+    /// Bumped per document by `evict_document`
+    generations: HashMap<String, u64>,
+    /// Bumped by `clear`, invalidating every document
+    epoch: u64,
+}
+
+impl CacheInner {
+    fn generation(&self, doc_id: &str) -> (u64, u64) {
+        (self.epoch, self.generations.get(doc_id).copied().unwrap_or(0))
+    }
+
+    fn next_tick(&mut self) -> u64 {
+        self.tick += 1;
+        self.tick
+    }
+
+    fn remove(&mut self, key: &RenderKey) {
+        if let Some(entry) = self.entries.remove(key) {
+            self.lru.remove(&entry.last_used);
+            self.bytes -= entry.data.bytes.len();
+        }
+    }
+
+    /// Drop least recently used entries until `bytes` fits the budget.
+    fn shrink_to_budget(&mut self) {
+        while self.bytes > self.budget_bytes {
+            let Some((_, key)) = self.lru.pop_first() else {
+                break;
+            };
+            if let Some(entry) = self.entries.remove(&key) {
+                self.bytes -= entry.data.bytes.len();
+                self.evictions += 1;
+            }
+        }
+    }
+}
+
+/// Thread-safe LRU of encoded renders bounded by total byte size.
+pub struct RenderCache {
+    inner: Mutex<CacheInner>,
+}
+
+impl Default for RenderCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_BUDGET_BYTES)
+    }
+}
+
+impl RenderCache {
+    pub fn new(budget_bytes: usize) -> Self {
+        Self {
+            inner: Mutex::new(CacheInner {
+                entries: HashMap::new(),
+                lru: BTreeMap::new(),
+                tick: 0,
+                bytes: 0,
+                budget_bytes,
+                hits: 0,
+                misses: 0,
+                evictions: 0,
+                generations: HashMap::new(),
+                epoch: 0,
+            }),
+        }
+    }
+
+    /// Look up a render, marking it as most recently used.
+    pub fn get(&self, key: &RenderKey) -> Option<Arc<RenderedImage>> {
+        let mut inner = self.inner.lock().ok()?;
+        let tick = inner.next_tick();
+        let inner = &mut *inner;
+        match inner.entries.get_mut(key) {
+            Some(entry) => {
+                inner.lru.remove(&entry.last_used);
+                inner.lru.insert(tick, key.clone());
+                entry.last_used = tick;
+                inner.hits += 1;
+                Some(Arc::clone(&entry.data))
+            }
+            None => {
+                inner.misses += 1;
+                None
+            }
+        }
+    }
+
+    /// Store a render, evicting old entries if the budget is exceeded. With a
+    /// `generation`, the render is dropped if its document was evicted since
+    /// that generation was read.
+    ///
+    /// Images larger than the whole budget are returned without being cached.
+    fn insert(
+        &self,
+        key: RenderKey,
+        data: RenderedImage,
+        generation: Option<(u64, u64)>,
+    ) -> Arc<RenderedImage> {
+        let data = Arc::new(data);
+        let Ok(mut inner) = self.inner.lock() else {
+            return data;
+        };
+        if data.bytes.len() > inner.budget_bytes {
+            return data;
+        }
+        if let Some(generation) = generation {
+            if inner.generation(&key.doc_id) != generation {
+                return data;
+            }
+        }
+
+        inner.remove(&key);
+        let tick = inner.next_tick();
+        inner.lru.insert(tick, key.clone());
+        inner.bytes += data.bytes.len();
+        inner.entries.insert(
+            key,
+            Entry {
+                data: Arc::clone(&data),
+                last_used: tick,
+            },
+        );
+        inner.shrink_to_budget();
+        data
+    }
+
+    /// Return the cached render for `key`, or produce and cache it with `render`.
+    ///
+    /// `render` runs without the cache lock held; if the document is evicted
+    /// meanwhile the result is returned but not cached.
+    pub fn get_or_render<F>(&self, key: RenderKey, render: F) -> Result<Arc<RenderedImage>, String>
+    where
+        F: FnOnce() -> Result<RenderedImage, String>,
+    {
+        if let Some(data) = self.get(&key) {
+            return Ok(data);
+        }
+        let generation = self
+            .inner
+            .lock()
+            .map(|inner| inner.generation(&key.doc_id))
+            .ok();
+        let image = render()?;
+        Ok(self.insert(key, image, generation))
+    }
+
+    /// Drop every render of a document.
+    pub fn evict_document(&self, doc_id: &str) {
+        if let Ok(mut inner) = self.inner.lock() {
+            *inner.generations.entry(doc_id.to_string()).or_insert(0) += 1;
+            let keys: Vec<RenderKey> = inner
+                .entries
+                .keys()
+                .filter(|k| k.doc_id == doc_id)
+                .cloned()
+                .collect();
+            for key in &keys {
+                inner.remove(key);
+            }
+        }
+    }
+
+    pub fn clear(&self) {
+        if let Ok(mut inner) = self.inner.lock() {
+            inner.entries.clear();
+            inner.lru.clear();
+            inner.bytes = 0;
+            inner.epoch += 1;
+            inner.generations.clear();
+        }
+    }
+
+    /// Change the byte budget, evicting immediately if it shrank.
+    pub fn set_budget(&self, budget_bytes: usize) {
+        if let Ok(mut inner) = self.inner.lock() {
+            inner.budget_bytes = budget_bytes;
+            inner.shrink_to_budget();
+        }
+    }
+
+    pub fn stats(&self) -> RenderCacheStats {
+        let inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        RenderCacheStats {
+            entries: inner.entries.len(),
+            bytes: inner.bytes,
+            budget_bytes: inner.budget_bytes,
+            hits: inner.hits,
+            misses: inner.misses,
+            evictions: inner.evictions,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render_options::ImageFormat;
+
+    fn image(len: usize) -> RenderedImage {
+        RenderedImage {
+            width: 1,
+            height: 1,
+            format: ImageFormat::Png,
+            bytes: vec![0; len],
+        }
+    }
+
+    #[test]
+    fn test_hits_misses_and_lru_eviction() {
+        let cache = RenderCache::new(30);
+        let a = RenderKey::page("doc", 0, 1.5);
+        let b = RenderKey::clip("doc", 0, 1.5, [0, 0, 256, 256]);
+        let c = RenderKey::page("doc", 1, 1.5);
+
+        assert!(cache.get(&a).is_none());
+        cache.insert(a.clone(), image(10), None);
+        cache.insert(b.clone(), image(10), None);
+        assert!(cache.get(&a).is_some()); // `b` is now least recently used
+
+        cache.insert(c.clone(), image(15), None);
+        assert!(cache.get(&b).is_none());
+        assert!(cache.get(&a).is_some());
+        assert!(cache.get(&c).is_some());
+
+        let stats = cache.stats();
+        assert_eq!(stats.entries, 2);
+        assert_eq!(stats.bytes, 25);
+        assert_eq!((stats.hits, stats.misses, stats.evictions), (3, 2, 1));
+    }
+
+    #[test]
+    fn test_oversized_and_budget_change() {
+        let cache = RenderCache::new(20);
+        cache.insert(RenderKey::page("doc", 0, 1.0), image(50), None);
+        assert_eq!(cache.stats().entries, 0);
+
+        cache.insert(RenderKey::page("doc", 0, 1.0), image(8), None);
+        cache.insert(RenderKey::page("doc", 0, 2.0), image(8), None);
+        cache.set_budget(10);
+        assert_eq!(cache.stats().entries, 1);
+        assert!(cache.get(&RenderKey::page("doc", 0, 2.0)).is_some());
+    }
+
+    #[test]
+    fn test_get_or_render_and_evict_document() {
+        let cache = RenderCache::default();
+        let key = RenderKey::page("doc", 0, 1.5);
+        cache.get_or_render(key.clone(), || Ok(image(3))).unwrap();
+        let cached = cache
+            .get_or_render(key.clone(), || Err("should not render".to_string()))
+            .unwrap();
+        assert_eq!(cached.bytes.len(), 3);
+
+        cache.insert(RenderKey::page("other", 0, 1.5), image(1), None);
+        cache.evict_document("doc");
+        assert!(cache.get(&key).is_none());
+        assert_eq!(cache.stats().entries, 1);
+    }
+
+    #[test]
+    fn test_render_racing_eviction_is_not_cached() {
+        let cache = RenderCache::default();
+        let key = RenderKey::page("doc", 0, 1.5);
+
+        // The document is replaced while the old one is still rendering
+        let rendered = cache
+            .get_or_render(key.clone(), || {
+                cache.evict_document("doc");
+                Ok(image(3))
+            })
+            .unwrap();
+        assert_eq!(rendered.bytes.len(), 3);
+        assert!(cache.get(&key).is_none());
+
+        cache
+            .get_or_render(key.clone(), || {
+                cache.clear();
+                Ok(image(3))
+            })
+            .unwrap();
+        assert!(cache.get(&key).is_none());
+
+        cache.get_or_render(key.clone(), || Ok(image(3))).unwrap();
+        assert!(cache.get(&key).is_some());
+    }
+}
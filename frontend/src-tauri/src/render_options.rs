@@ -0,0 +1,397 @@
+//! Render Options Module
+//!
+//! Describes how a page is rasterised (zoom, optional pixel clip, colour
+//! mode, hidden layers) and how the resulting RGBA pixels are encoded. MuPDF only produces
+//! pixels; encoding happens here so previews can pick a cheaper format than PNG.
+
+use std::hash::{Hash, Hasher};
+
+/// Default quality for lossy formats (1-100)
+pub const DEFAULT_QUALITY: u8 = 85;
+
+/// Output encoding of a render.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ImageFormat {
+    #[default]
+    Png,
+    Jpeg,
+    Webp,
+    /// Unencoded, tightly packed 8-bit RGBA rows
+    Rgba,
+}
+
+impl ImageFormat {
+    /// Parse a `format=` query value.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "png" => Some(Self::Png),
+            "jpg" | "jpeg" => Some(Self::Jpeg),
+            "webp" => Some(Self::Webp),
+            "rgba" | "raw" => Some(Self::Rgba),
+            _ => None,
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Self::Png => "image/png",
+            Self::Jpeg => "image/jpeg",
+            Self::Webp => "image/webp",
+            Self::Rgba => "application/octet-stream",
+        }
+    }
+
+    /// Whether `quality` affects the output.
+    pub fn is_lossy(&self) -> bool {
+        matches!(self, Self::Jpeg | Self::Webp)
+    }
+}
+
+/// Colour treatment applied by the MuPDF bridge to the rasterised page, so
+/// muted sheets look the same on screen and in exported images.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum RenderMode {
+    #[default]
+    Normal,
+    Grayscale,
+    /// Blend towards white by 0-100 percent, so coloured markups stand out
+    Faded(u8),
+    /// Luminance inverted for dark mode; hues are kept
+    Inverted,
+    /// Pixels darker than the 0-255 threshold become black, the rest white.
+    /// Drops light fills, hatching and images, leaving the linework.
+    Monochrome(u8),
+}
+
+/// Fade used when `mode=faded` has no `intensity=`
+pub const DEFAULT_FADE_PERCENT: u8 = 60;
+
+/// Threshold used when `mode=monochrome` has no `threshold=`
+pub const DEFAULT_MONOCHROME_THRESHOLD: u8 = 160;
+
+// Mode codes understood by mupdf_render_page_to_rgba (MUPDF_RENDER_* in mupdf_bridge.h)
+const BRIDGE_NORMAL: i32 = 0;
+const BRIDGE_GRAYSCALE: i32 = 1;
+const BRIDGE_FADED: i32 = 2;
+const BRIDGE_INVERTED: i32 = 3;
+const BRIDGE_MONOCHROME: i32 = 4;
+
+impl RenderMode {
+    /// Parse `mode=` with its optional `intensity=` (percent) or
+    /// `threshold=` (0-255) parameter.
+    pub fn from_query(name: &str, intensity: Option<u8>, threshold: Option<u8>) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "normal" => Some(Self::Normal),
+            "grayscale" | "greyscale" | "gray" | "grey" => Some(Self::Grayscale),
+            "faded" | "fade" => Some(Self::Faded(
+                intensity.unwrap_or(DEFAULT_FADE_PERCENT).min(100),
+            )),
+            "inverted" | "invert" | "dark" => Some(Self::Inverted),
+            "monochrome" | "mono" => Some(Self::Monochrome(
+                threshold.unwrap_or(DEFAULT_MONOCHROME_THRESHOLD),
+            )),
+            _ => None,
+        }
+    }
+
+    /// Mode code and 0-1 amount for the C bridge.
+    pub fn to_bridge(&self) -> (i32, f32) {
+        match *self {
+            Self::Normal => (BRIDGE_NORMAL, 0.0),
+            Self::Grayscale => (BRIDGE_GRAYSCALE, 0.0),
+            Self::Faded(percent) => (BRIDGE_FADED, percent as f32 / 100.0),
+            Self::Inverted => (BRIDGE_INVERTED, 0.0),
+            Self::Monochrome(threshold) => (BRIDGE_MONOCHROME, threshold as f32 / 255.0),
+        }
+    }
+}
+
+/// Everything that determines the bytes of one render of a page.
+#[derive(Debug, Clone)]
+pub struct RenderOptions {
+    pub zoom: f32,
+    /// Pixel rectangle (x, y, width, height) of the scaled page, `None` for the whole page
+    pub clip: Option<[i32; 4]>,
+    pub format: ImageFormat,
+    /// 1-100, only used by lossy formats
+    pub quality: u8,
+    pub mode: RenderMode,
+    /// Optional content groups (layer indices) hidden for this render, sorted
+    pub hidden_layers: Vec<i32>,
+}
+
+impl RenderOptions {
+    /// Whole page as PNG.
+    pub fn new(zoom: f32) -> Self {
+        Self {
+            zoom,
+            clip: None,
+            format: ImageFormat::Png,
+            quality: DEFAULT_QUALITY,
+            mode: RenderMode::Normal,
+            hidden_layers: Vec::new(),
+        }
+    }
+
+    pub fn with_clip(self, clip: [i32; 4]) -> Self {
+        Self {
+            clip: Some(clip),
+            ..self
+        }
+    }
+
+    /// Hide the given layers; order and duplicates do not matter.
+    pub fn with_hidden_layers(self, mut layers: Vec<i32>) -> Self {
+        layers.retain(|&layer| layer >= 0);
+        layers.sort_unstable();
+        layers.dedup();
+        Self {
+            hidden_layers: layers,
+            ..self
+        }
+    }
+
+    /// Apply `format=`, `quality=`, `mode=` (with `intensity=` or
+    /// `threshold=`) and `hide=` (comma separated layer indices) from a
+    /// protocol query string. Unknown values are ignored.
+    pub fn apply_query(mut self, query: &str) -> Self {
+        let mut mode = None;
+        let mut intensity = None;
+        let mut threshold = None;
+        let mut hidden = None;
+        for (key, value) in query.split('&').filter_map(|pair| pair.split_once('=')) {
+            match key {
+                "format" => {
+                    if let Some(format) = ImageFormat::from_name(value) {
+                        self.format = format;
+                    }
+                }
+                "quality" => {
+                    if let Ok(quality) = value.parse::<u8>() {
+                        self.quality = quality.clamp(1, 100);
+                    }
+                }
+                "mode" => mode = Some(value),
+                "intensity" => intensity = value.parse::<u8>().ok(),
+                "threshold" => threshold = value.parse::<u8>().ok(),
+                "hide" => {
+                    hidden = Some(
+                        value
+                            .split(',')
+                            .filter_map(|layer| layer.parse::<i32>().ok())
+                            .collect::<Vec<_>>(),
+                    )
+                }
+                _ => {}
+            }
+        }
+        if let Some(mode) = mode.and_then(|m| RenderMode::from_query(m, intensity, threshold)) {
+            self.mode = mode;
+        }
+        match hidden {
+            Some(layers) => self.with_hidden_layers(layers),
+            None => self,
+        }
+    }
+
+    /// A plain whole-page PNG, the only kind of render persisted to disk.
+    pub fn is_default_page(&self) -> bool {
+        self == &Self::new(self.zoom)
+    }
+}
+
+// Zoom compared by bits so options can key the render cache; quality is
+// ignored for lossless formats so it does not split cache entries.
+impl PartialEq for RenderOptions {
+    fn eq(&self, other: &Self) -> bool {
+        self.zoom.to_bits() == other.zoom.to_bits()
+            && self.clip == other.clip
+            && self.format == other.format
+            && (!self.format.is_lossy() || self.quality == other.quality)
+            && self.mode == other.mode
+            && self.hidden_layers == other.hidden_layers
+    }
+}
+
+impl Eq for RenderOptions {}
+
+impl Hash for RenderOptions {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.zoom.to_bits().hash(state);
+        self.clip.hash(state);
+        self.format.hash(state);
+        if self.format.is_lossy() {
+            self.quality.hash(state);
+        }
+        self.mode.hash(state);
+        self.hidden_layers.hash(state);
+    }
+}
+
+/// An encoded render and its pixel size.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RenderedImage {
+    pub width: u32,
+    pub height: u32,
+    pub format: ImageFormat,
+    pub bytes: Vec<u8>,
+}
+
+/// Largest width or height libwebp accepts
+const WEBP_MAX_DIMENSION: u32 = 16383;
+
+/// Encode tightly packed RGBA pixels.
+pub fn encode_rgba(
+    pixels: Vec<u8>,
+    width: u32,
+    height: u32,
+    format: ImageFormat,
+    quality: u8,
+) -> Result<RenderedImage, String> {
+    if pixels.len() != width as usize * height as usize * 4 {
+        return Err(format!(
+            "Pixel buffer of {} bytes does not match {}x{} RGBA",
+            pixels.len(),
+            width,
+            height
+        ));
+    }
+
+    let bytes = match format {
+        ImageFormat::Rgba => pixels,
+        ImageFormat::Png => {
+            let mut out = Vec::new();
+            let mut encoder = png::Encoder::new(&mut out, width, height);
+            encoder.set_color(png::ColorType::Rgba);
+            encoder.set_depth(png::BitDepth::Eight);
+            // Large sheets spend most of their time in deflate; favour speed
+            encoder.set_compression(png::Compression::Fast);
+            let mut writer = encoder.write_header().map_err(|e| e.to_string())?;
+            writer
+                .write_image_data(&pixels)
+                .map_err(|e| e.to_string())?;
+            writer.finish().map_err(|e| e.to_string())?;
+            out
+        }
+        ImageFormat::Jpeg => {
+            let (w, h) = match (u16::try_from(width), u16::try_from(height)) {
+                (Ok(w), Ok(h)) => (w, h),
+                _ => return Err(format!("{}x{} is too large for JPEG", width, height)),
+            };
+            let mut out = Vec::new();
+            jpeg_encoder::Encoder::new(&mut out, quality)
+                .encode(&pixels, w, h, jpeg_encoder::ColorType::Rgba)
+                .map_err(|e| e.to_string())?;
+            out
+        }
+        ImageFormat::Webp => {
+            if width > WEBP_MAX_DIMENSION || height > WEBP_MAX_DIMENSION {
+                return Err(format!("{}x{} is too large for WebP", width, height));
+            }
+            webp::Encoder::from_rgba(&pixels, width, height)
+                .encode_simple(false, quality as f32)
+                .map_err(|e| format!("WebP encoding failed: {:?}", e))?
+                .to_vec()
+        }
+    };
+
+    Ok(RenderedImage {
+        width,
+        height,
+        format,
+        bytes,
+    })
+}
+
+/// Width and height from a PNG's IHDR chunk.
+pub fn png_dimensions(bytes: &[u8]) -> Option<(u32, u32)> {
+    const SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+    if bytes.len() < 24 || !bytes.starts_with(SIGNATURE) || &bytes[12..16] != b"IHDR" {
+        return None;
+    }
+    let width = u32::from_be_bytes(bytes[16..20].try_into().ok()?);
+    let height = u32::from_be_bytes(bytes[20..24].try_into().ok()?);
+    Some((width, height))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_query_and_cache_identity() {
+        let options = RenderOptions::new(1.5).apply_query("zoom=1.5&format=webp&quality=250");
+        assert_eq!(options.format, ImageFormat::Webp);
+        assert_eq!(options.quality, 100);
+        assert!(!options.is_default_page());
+
+        let ignored = RenderOptions::new(1.5).apply_query("format=gif&quality=abc");
+        assert!(ignored.is_default_page());
+
+        // Quality only distinguishes lossy renders
+        let png = RenderOptions::new(1.0).apply_query("quality=10");
+        assert_eq!(png, RenderOptions::new(1.0));
+        let jpeg = RenderOptions::new(1.0).apply_query("format=jpg");
+        assert_ne!(jpeg, jpeg.clone().apply_query("quality=10"));
+        assert_ne!(RenderOptions::new(1.0), RenderOptions::new(1.0).with_clip([0, 0, 256, 256]));
+    }
+
+    #[test]
+    fn test_render_mode_query() {
+        let faded = RenderOptions::new(1.5).apply_query("intensity=30&mode=faded");
+        assert_eq!(faded.mode, RenderMode::Faded(30));
+        assert_eq!(faded.mode.to_bridge(), (BRIDGE_FADED, 0.3));
+        assert!(!faded.is_default_page());
+
+        let mono = RenderOptions::new(1.5).apply_query("mode=monochrome");
+        assert_eq!(
+            mono.mode,
+            RenderMode::Monochrome(DEFAULT_MONOCHROME_THRESHOLD)
+        );
+        assert_eq!(
+            RenderOptions::new(1.5)
+                .apply_query("mode=faded&intensity=250")
+                .mode,
+            RenderMode::Faded(100)
+        );
+        assert_eq!(
+            RenderOptions::new(1.5).apply_query("mode=sepia").mode,
+            RenderMode::Normal
+        );
+        assert_ne!(
+            faded,
+            RenderOptions::new(1.5).apply_query("mode=faded&intensity=31")
+        );
+    }
+
+    #[test]
+    fn test_hidden_layers_query() {
+        let options = RenderOptions::new(1.0).apply_query("hide=3,1,x,3,-2");
+        assert_eq!(options.hidden_layers, vec![1, 3]);
+        assert!(!options.is_default_page());
+        assert_eq!(options, RenderOptions::new(1.0).apply_query("hide=1,3"));
+        assert!(RenderOptions::new(1.0).apply_query("hide=").is_default_page());
+    }
+
+    #[test]
+    fn test_encode_formats() {
+        let (w, h) = (4, 3);
+        let pixels: Vec<u8> = (0..w * h).flat_map(|i| [i as u8 * 20, 128, 255, 255]).collect();
+
+        let raw = encode_rgba(pixels.clone(), w, h, ImageFormat::Rgba, 85).unwrap();
+        assert_eq!(raw.bytes, pixels);
+
+        let png = encode_rgba(pixels.clone(), w, h, ImageFormat::Png, 85).unwrap();
+        assert_eq!(png_dimensions(&png.bytes), Some((w, h)));
+
+        let jpeg = encode_rgba(pixels.clone(), w, h, ImageFormat::Jpeg, 50).unwrap();
+        assert_eq!(&jpeg.bytes[..2], &[0xff, 0xd8]);
+
+        let webp = encode_rgba(pixels.clone(), w, h, ImageFormat::Webp, 50).unwrap();
+        assert_eq!(&webp.bytes[8..12], b"WEBP");
+
+        assert!(encode_rgba(vec![0; 3], w, h, ImageFormat::Png, 85).is_err());
+        // Beyond libwebp's size limit is an error, not a panic
+        assert!(encode_rgba(vec![0; 16384 * 4], 16384, 1, ImageFormat::Webp, 50).is_err());
+    }
+}
@@ -0,0 +1,452 @@
+//! Scale Bar Module
+//!
+//! Recognises graphic scale bars in a page's linework: a horizontal bar with
+//! vertical ticks at its ends and divisions, where some ticks carry numeric
+//! labels ("0  5  10  20 FEET"). The distance between two labelled ticks gives
+//! a calibration in the shape of the frontend's `PageCalibration`, which works
+//! on sheets marked "NOT TO SCALE" or that were scaled when plotted.
+//!
+//! Coordinates are in frontend space, the same as the spatial index and
+//! search hits.
+
+use serde::Serialize;
+use std::collections::HashMap;
+
+type Pt = [f64; 2];
+
+/// Shortest bar considered, in PDF points (half an inch)
+const MIN_BAR_LENGTH: f64 = 36.0;
+
+/// Tick height range, in PDF points
+const MIN_TICK_LENGTH: f64 = 1.0;
+const MAX_TICK_LENGTH: f64 = 24.0;
+
+/// Slack for "horizontal", "vertical" and "touching", in PDF points
+const TOLERANCE: f64 = 0.75;
+
+/// Ticks needed for a bar, including both ends
+const MIN_TICKS: usize = 3;
+
+/// Labels agreeing with a calibration may deviate by this share of its range
+const FIT_TOLERANCE: f64 = 0.03;
+
+/// Distinct numbers searched for per page; each one costs a page search
+pub const MAX_LABEL_TOKENS: usize = 48;
+
+/// Largest value taken for a scale bar label
+const MAX_LABEL_VALUE: f64 = 10_000.0;
+
+/// Unit of a real-world distance, serialised like `PageCalibration.realUnit`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LengthUnit {
+    Ft,
+    In,
+    M,
+    Cm,
+}
+
+impl LengthUnit {
+    fn from_suffix(suffix: &str) -> Option<Self> {
+        match suffix.to_ascii_uppercase().as_str() {
+            "'" | "\u{2019}" | "\u{2032}" | "FT" | "FT." | "FEET" | "FOOT" => Some(Self::Ft),
+            "\"" | "\u{201D}" | "\u{2033}" | "IN" | "IN." | "INCHES" => Some(Self::In),
+            "M" | "METERS" | "METRES" => Some(Self::M),
+            "CM" => Some(Self::Cm),
+            _ => None,
+        }
+    }
+
+    fn feet(&self, value: f64) -> f64 {
+        match self {
+            Self::Ft => value,
+            Self::In => value / 12.0,
+            Self::M => value * 3.28084,
+            Self::Cm => value / 30.48,
+        }
+    }
+}
+
+/// A number printed on the page, to be located with a text search.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LabelToken {
+    /// Text to search for (the number without its unit)
+    pub text: String,
+    pub value: f64,
+    pub unit: Option<LengthUnit>,
+}
+
+/// A located number.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NumberLabel {
+    pub value: f64,
+    pub unit: Option<LengthUnit>,
+    /// `[x0, y0, x1, y1]`
+    pub bounds: [f64; 4],
+}
+
+/// Calibration from a scale bar; serialises as a frontend `PageCalibration`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScaleBarCalibration {
+    pub pixel_distance: f64,
+    pub real_distance: f64,
+    pub real_unit: LengthUnit,
+    pub calculated_pixels_per_foot: f64,
+}
+
+/// A detected scale bar.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ScaleBar {
+    /// `[x0, y0, x1, y1]` of the bar and its ticks
+    pub bounds: [f64; 4],
+    pub tick_count: usize,
+    /// Labels consistent with the calibration
+    pub label_count: usize,
+    /// No label carried a unit, so feet were assumed
+    pub unit_assumed: bool,
+    pub calibration: ScaleBarCalibration,
+}
+
+/// Collect the distinct numbers of a page's text that could label a scale
+/// bar, with a unit when one is attached (`20'`, `10FT`) or follows
+/// (`20 FEET`). Zero comes first as nearly every bar starts with it.
+pub fn label_tokens(text: &str) -> Vec<LabelToken> {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let mut tokens: Vec<LabelToken> = Vec::new();
+    for (i, word) in words.iter().enumerate() {
+        let split = word
+            .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+            .unwrap_or(word.len());
+        let (number, suffix) = word.split_at(split);
+        let Ok(value) = number.parse::<f64>() else {
+            continue;
+        };
+        if !(0.0..=MAX_LABEL_VALUE).contains(&value) || number.ends_with('.') {
+            continue;
+        }
+        let unit = if suffix.is_empty() {
+            words.get(i + 1).and_then(|next| {
+                // Only spelled-out units: a lone "M" after a number is too common
+                LengthUnit::from_suffix(next).filter(|_| next.len() > 2)
+            })
+        } else {
+            match LengthUnit::from_suffix(suffix) {
+                Some(unit) => Some(unit),
+                None => continue,
+            }
+        };
+
+        match tokens.iter_mut().find(|t| t.text == number) {
+            Some(existing) => existing.unit = existing.unit.or(unit),
+            None => tokens.push(LabelToken {
+                text: number.to_string(),
+                value,
+                unit,
+            }),
+        }
+    }
+
+    tokens.sort_by(|a, b| a.value.total_cmp(&b.value));
+    tokens.truncate(MAX_LABEL_TOKENS);
+    tokens
+}
+
+/// Turn search hits per token into labels. Text search matches substrings,
+/// so a hit for "0" inside a hit for "10" is dropped.
+pub fn resolve_labels(found: &[(LabelToken, Vec<[f64; 4]>)]) -> Vec<NumberLabel> {
+    let contains = |outer: &[f64; 4], inner: &[f64; 4]| {
+        inner[0] >= outer[0] - TOLERANCE
+            && inner[1] >= outer[1] - TOLERANCE
+            && inner[2] <= outer[2] + TOLERANCE
+            && inner[3] <= outer[3] + TOLERANCE
+    };
+
+    let mut labels = Vec::new();
+    for (token, hits) in found {
+        for bounds in hits {
+            let inside_longer = found.iter().any(|(other, other_hits)| {
+                other.text.len() > token.text.len()
+                    && other.text.contains(token.text.as_str())
+                    && other_hits.iter().any(|b| contains(b, bounds))
+            });
+            if !inside_longer {
+                labels.push(NumberLabel {
+                    value: token.value,
+                    unit: token.unit,
+                    bounds: *bounds,
+                });
+            }
+        }
+    }
+    labels
+}
+
+/// Find scale bars among `lines` (straight segments of the page), best first.
+/// `pixels_per_point` converts the PDF-point thresholds to frontend space.
+pub fn detect(lines: &[(Pt, Pt)], labels: &[NumberLabel], pixels_per_point: f64) -> Vec<ScaleBar> {
+    let tol = TOLERANCE * pixels_per_point;
+
+    let verticals: Vec<(f64, f64, f64)> = lines
+        .iter()
+        .filter(|(a, b)| (a[0] - b[0]).abs() <= tol)
+        .map(|(a, b)| ((a[0] + b[0]) / 2.0, a[1].min(b[1]), a[1].max(b[1])))
+        .filter(|&(_, y0, y1)| {
+            let len = y1 - y0;
+            len >= MIN_TICK_LENGTH * pixels_per_point && len <= MAX_TICK_LENGTH * pixels_per_point
+        })
+        .collect();
+
+    let mut bars: Vec<ScaleBar> = baselines(lines, tol, MIN_BAR_LENGTH * pixels_per_point)
+        .into_iter()
+        .filter_map(|baseline| bar_on_baseline(baseline, &verticals, labels, tol))
+        .collect();
+
+    // Best first; the top and bottom edge of a filled bar find the same bar
+    bars.sort_by(|a, b| {
+        (b.label_count, b.tick_count)
+            .cmp(&(a.label_count, a.tick_count))
+            .then(b.calibration.pixel_distance.total_cmp(&a.calibration.pixel_distance))
+    });
+    let mut result: Vec<ScaleBar> = Vec::new();
+    for bar in bars {
+        if !result.iter().any(|kept| overlaps(&kept.bounds, &bar.bounds)) {
+            result.push(bar);
+        }
+    }
+    result
+}
+
+/// Horizontal runs `(y, x0, x1)` made of collinear, touching horizontal
+/// segments, at least `min_length` long.
+fn baselines(lines: &[(Pt, Pt)], tol: f64, min_length: f64) -> Vec<(f64, f64, f64)> {
+    let mut horizontals: Vec<(f64, f64, f64)> = lines
+        .iter()
+        .filter(|(a, b)| (a[1] - b[1]).abs() <= tol && (a[0] - b[0]).abs() > tol)
+        .map(|(a, b)| ((a[1] + b[1]) / 2.0, a[0].min(b[0]), a[0].max(b[0])))
+        .collect();
+    horizontals.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.total_cmp(&b.1)));
+
+    let mut runs: Vec<(f64, f64, f64)> = Vec::new();
+    for (y, x0, x1) in horizontals {
+        match runs
+            .iter_mut()
+            .rev()
+            .take_while(|run| y - run.0 <= tol)
+            .find(|run| x0 <= run.2 + tol && x1 >= run.1 - tol)
+        {
+            Some(run) => {
+                run.1 = run.1.min(x0);
+                run.2 = run.2.max(x1);
+            }
+            None => runs.push((y, x0, x1)),
+        }
+    }
+    runs.retain(|run| run.2 - run.1 >= min_length);
+    runs
+}
+
+/// Try to read a scale bar off one baseline.
+fn bar_on_baseline(
+    (y, x0, x1): (f64, f64, f64),
+    verticals: &[(f64, f64, f64)],
+    labels: &[NumberLabel],
+    tol: f64,
+) -> Option<ScaleBar> {
+    let touching: Vec<&(f64, f64, f64)> = verticals
+        .iter()
+        .filter(|(x, y0, y1)| *x >= x0 - tol && *x <= x1 + tol && *y0 <= y + tol && *y1 >= y - tol)
+        .collect();
+    let mut ticks: Vec<f64> = touching.iter().map(|t| t.0).collect();
+    ticks.sort_by(f64::total_cmp);
+    ticks.dedup_by(|a, b| (*a - *b).abs() <= tol);
+    if ticks.len() < MIN_TICKS
+        || (ticks[0] - x0).abs() > 2.0 * tol
+        || (ticks[ticks.len() - 1] - x1).abs() > 2.0 * tol
+    {
+        return None;
+    }
+    let top = touching.iter().map(|t| t.1).fold(y, f64::min);
+    let bottom = touching.iter().map(|t| t.2).fold(y, f64::max);
+    let tick_height = bottom - top;
+
+    // Nearest label to each tick, above or below the bar
+    let mut at_tick: HashMap<usize, (f64, &NumberLabel)> = HashMap::new();
+    for label in labels {
+        let b = &label.bounds;
+        let (cx, cy) = ((b[0] + b[2]) / 2.0, (b[1] + b[3]) / 2.0);
+        let reach = tick_height.max(b[3] - b[1]) * 2.0 + tol;
+        if cy < top - reach || cy > bottom + reach {
+            continue;
+        }
+        let Some((i, dx)) = ticks
+            .iter()
+            .map(|tx| (cx - tx).abs())
+            .enumerate()
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+        else {
+            continue;
+        };
+        if dx > (b[2] - b[0]) / 2.0 + tol {
+            continue;
+        }
+        let distance = dx + (cy - y).abs();
+        let nearer = match at_tick.get(&i) {
+            Some((d, _)) => distance < *d,
+            None => true,
+        };
+        if nearer {
+            at_tick.insert(i, (distance, label));
+        }
+    }
+    let mut marks: Vec<(f64, &NumberLabel)> = at_tick
+        .into_iter()
+        .map(|(i, (_, label))| (ticks[i], label))
+        .collect();
+    marks.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    // Calibrate on the pair of labels most others agree with, widest first
+    let mut best: Option<(usize, f64, usize, usize)> = None;
+    for i in 0..marks.len() {
+        for j in i + 1..marks.len() {
+            let (xi, vi) = (marks[i].0, marks[i].1.value);
+            let (xj, vj) = (marks[j].0, marks[j].1.value);
+            if vj <= vi {
+                continue;
+            }
+            let slope = (vj - vi) / (xj - xi);
+            let agree = marks
+                .iter()
+                .filter(|(x, l)| (vi + slope * (x - xi) - l.value).abs() <= FIT_TOLERANCE * (vj - vi))
+                .count();
+            let span = xj - xi;
+            let better = match best {
+                Some((count, best_span, _, _)) => (agree, span) > (count, best_span),
+                None => true,
+            };
+            if better {
+                best = Some((agree, span, i, j));
+            }
+        }
+    }
+    let (agree, span, i, j) = best?;
+    let has_zero = marks[i].1.value == 0.0;
+    if agree < 3 && !(agree == 2 && has_zero) {
+        return None;
+    }
+
+    let unit = marks.iter().find_map(|(_, l)| l.unit);
+    let real_unit = unit.unwrap_or(LengthUnit::Ft);
+    let real_distance = marks[j].1.value - marks[i].1.value;
+    Some(ScaleBar {
+        bounds: [x0, top, x1, bottom],
+        tick_count: ticks.len(),
+        label_count: agree,
+        unit_assumed: unit.is_none(),
+        calibration: ScaleBarCalibration {
+            pixel_distance: span,
+            real_distance,
+            real_unit,
+            calculated_pixels_per_foot: span / real_unit.feet(real_distance),
+        },
+    })
+}
+
+fn overlaps(a: &[f64; 4], b: &[f64; 4]) -> bool {
+    a[0] <= b[2] && b[0] <= a[2] && a[1] <= b[3] && b[1] <= a[3]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn label(value: f64, unit: Option<LengthUnit>, cx: f64, cy: f64) -> NumberLabel {
+        NumberLabel {
+            value,
+            unit,
+            bounds: [cx - 4.0, cy - 5.0, cx + 4.0, cy + 5.0],
+        }
+    }
+
+    /// "0 5 10 20" bar at 15 px per foot with ticks up from y = 200, plus a
+    /// filled bottom edge, some unrelated linework and dimension text
+    fn sample() -> (Vec<(Pt, Pt)>, Vec<NumberLabel>) {
+        let mut lines = vec![
+            ([100.0, 200.0], [250.0, 200.0]),
+            ([250.0, 200.0], [400.0, 200.0]),
+            ([100.0, 206.0], [400.0, 206.0]),
+            ([50.0, 500.0], [700.0, 500.0]),
+            ([600.0, 100.0], [600.0, 400.0]),
+        ];
+        for x in [100.0, 175.0, 250.0, 400.0] {
+            lines.push(([x, 194.0], [x, 206.0]));
+        }
+        let labels = vec![
+            label(0.0, None, 100.0, 185.0),
+            label(5.0, None, 175.0, 185.0),
+            label(10.0, None, 250.0, 185.0),
+            label(20.0, Some(LengthUnit::Ft), 400.0, 185.0),
+            label(12.0, None, 300.0, 480.0),
+        ];
+        (lines, labels)
+    }
+
+    #[test]
+    fn test_detects_labelled_bar() {
+        let (lines, labels) = sample();
+        let bars = detect(&lines, &labels, 1.0);
+        assert_eq!(bars.len(), 1);
+        let bar = &bars[0];
+        assert_eq!(bar.tick_count, 4);
+        assert_eq!(bar.label_count, 4);
+        assert!(!bar.unit_assumed);
+        assert_eq!(bar.calibration.pixel_distance, 300.0);
+        assert_eq!(bar.calibration.real_distance, 20.0);
+        assert_eq!(bar.calibration.calculated_pixels_per_foot, 15.0);
+
+        let json = serde_json::to_value(&bar.calibration).unwrap();
+        assert_eq!(json["realUnit"], "ft");
+        assert_eq!(json["calculatedPixelsPerFoot"], 15.0);
+    }
+
+    #[test]
+    fn test_rejects_unlabelled_and_inconsistent_bars() {
+        let (lines, mut labels) = sample();
+        assert!(detect(&lines, &[], 1.0).is_empty());
+
+        // One label alone cannot calibrate
+        labels.truncate(1);
+        assert!(detect(&lines, &labels, 1.0).is_empty());
+
+        // Two labels without a zero are not trusted
+        let (_, labels) = sample();
+        assert!(detect(&lines, &labels[1..3], 1.0).is_empty());
+        assert_eq!(detect(&lines, &labels[..2], 1.0).len(), 1);
+    }
+
+    #[test]
+    fn test_label_tokens_and_substring_hits() {
+        let tokens = label_tokens("10 0 5 20' 2.5FT 12'-6\" 20 FEET R2 1.");
+        let summary: Vec<(&str, Option<LengthUnit>)> =
+            tokens.iter().map(|t| (t.text.as_str(), t.unit)).collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("0", None),
+                ("2.5", Some(LengthUnit::Ft)),
+                ("5", None),
+                ("10", None),
+                ("20", Some(LengthUnit::Ft)),
+            ]
+        );
+
+        let zero = tokens[0].clone();
+        let ten = tokens[3].clone();
+        let labels = resolve_labels(&[
+            (zero, vec![[10.0, 0.0, 14.0, 8.0], [54.0, 0.0, 58.0, 8.0]]),
+            (ten, vec![[50.0, 0.0, 58.0, 8.0]]),
+        ]);
+        let values: Vec<f64> = labels.iter().map(|l| l.value).collect();
+        assert_eq!(values, vec![0.0, 10.0]);
+    }
+}
@@ -0,0 +1,381 @@
+//! Scale Detection Module
+//!
+//! Finds drawing scale notations in a page's extracted text, such as
+//! `1/4" = 1'-0"`, `SCALE: 1" = 20'` or `1:100`, and turns them into the
+//! pixels-per-foot value the frontend's `PageScale` uses. A sheet often carries
+//! several scales (one per detail), so every notation is reported and the
+//! caller can pick the one for a viewport by position.
+
+use serde::Serialize;
+
+/// How far before a notation (in characters) a "SCALE" label is looked for
+const LABEL_LOOKBACK: usize = 16;
+
+/// Largest metric ratio accepted (1:N); larger values are more likely ids
+const MAX_METRIC_RATIO: u32 = 100_000;
+
+/// Family of a scale, matching `ScaleCategory` in the frontend.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ScaleKind {
+    /// `X" = 1'-0"`
+    Architectural,
+    /// `1" = N'` with N a multiple of 10
+    Engineering,
+    /// `1:N`
+    Metric,
+}
+
+/// One scale notation found on a page.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ScaleNotation {
+    /// The notation as printed, whitespace collapsed
+    pub text: String,
+    pub kind: ScaleKind,
+    /// Paper inches that represent one real foot
+    pub paper_inches_per_foot: f64,
+    /// `paper_inches_per_foot` at the page's rendered pixels per inch
+    pub pixels_per_foot: f64,
+    /// Preceded by a "SCALE" label, so unlikely to be a stray note
+    pub labelled: bool,
+    /// `[x0, y0, x1, y1]` in rendered page pixels, when the text was located
+    pub bounds: Option<[f32; 4]>,
+}
+
+/// Scales detected on one page.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PageScaleDetection {
+    pub page_idx: i32,
+    pub notations: Vec<ScaleNotation>,
+    /// The page says "NOT TO SCALE" / "NTS" somewhere
+    pub not_to_scale: bool,
+    /// Index into `notations` of the proposed scale
+    pub proposed: Option<usize>,
+}
+
+/// Find every scale notation in `text`. `pixels_per_inch` is the rendered
+/// resolution of the page (72 points per inch times the render zoom; MuPDF
+/// applies any UserUnit when rendering). Bounds are left for the caller to
+/// fill in.
+pub fn find_notations(text: &str, pixels_per_inch: f64) -> Vec<ScaleNotation> {
+    let original: Vec<char> = text.chars().collect();
+    let chars: Vec<char> = original.iter().map(|&c| normalize_char(c)).collect();
+
+    let mut notations = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let starts_number = chars[i].is_ascii_digit()
+            && (i == 0 || !matches!(chars[i - 1], '0'..='9' | '.' | '/' | ':'));
+        if !starts_number {
+            i += 1;
+            continue;
+        }
+
+        let parsed = parse_imperial(&chars, i).or_else(|| parse_metric(&chars, i));
+        match parsed {
+            Some((end, kind, paper_inches_per_foot)) => {
+                let raw: String = original[i..end].iter().collect();
+                let before: String = chars[i.saturating_sub(LABEL_LOOKBACK)..i].iter().collect();
+                notations.push(ScaleNotation {
+                    text: raw.split_whitespace().collect::<Vec<_>>().join(" "),
+                    kind,
+                    paper_inches_per_foot,
+                    pixels_per_foot: paper_inches_per_foot * pixels_per_inch,
+                    labelled: before.to_ascii_uppercase().contains("SCALE"),
+                    bounds: None,
+                });
+                i = end;
+            }
+            None => i += 1,
+        }
+    }
+    notations
+}
+
+/// Whether the text marks the sheet (or part of it) as not to scale.
+pub fn is_not_to_scale(text: &str) -> bool {
+    let upper = text.to_ascii_uppercase();
+    if upper.contains("NOT TO SCALE") || upper.contains("N.T.S") {
+        return true;
+    }
+    upper
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .any(|word| word == "NTS")
+}
+
+/// Pick the notation to use. With a `region` (`[x0, y0, x1, y1]` in page
+/// pixels), notations located inside it are preferred. Labelled notations win
+/// over unlabelled ones, then the scale that occurs most often, then the
+/// first one.
+pub fn propose(notations: &[ScaleNotation], region: Option<[f32; 4]>) -> Option<usize> {
+    let inside = |n: &ScaleNotation| match (region, n.bounds) {
+        (Some(r), Some(b)) => {
+            let (cx, cy) = ((b[0] + b[2]) / 2.0, (b[1] + b[3]) / 2.0);
+            cx >= r[0] && cx <= r[2] && cy >= r[1] && cy <= r[3]
+        }
+        _ => false,
+    };
+    let mut candidates: Vec<usize> = (0..notations.len())
+        .filter(|&i| inside(&notations[i]))
+        .collect();
+    if candidates.is_empty() {
+        candidates = (0..notations.len()).collect();
+    }
+
+    let same_scale = |a: &ScaleNotation, b: &ScaleNotation| {
+        (a.paper_inches_per_foot - b.paper_inches_per_foot).abs()
+            <= 1e-9 * a.paper_inches_per_foot.max(b.paper_inches_per_foot)
+    };
+    let mut best: Option<(usize, (bool, usize))> = None;
+    for &i in &candidates {
+        let count = candidates
+            .iter()
+            .filter(|&&j| same_scale(&notations[i], &notations[j]))
+            .count();
+        let score = (notations[i].labelled, count);
+        let better = match best {
+            Some((_, best_score)) => score > best_score,
+            None => true,
+        };
+        if better {
+            best = Some((i, score));
+        }
+    }
+    best.map(|(i, _)| i)
+}
+
+/// Map typographic quotes and dashes to their ASCII forms, one char for one.
+fn normalize_char(c: char) -> char {
+    match c {
+        '\u{2018}' | '\u{2019}' | '\u{2032}' | '\u{00B4}' | '`' => '\'',
+        '\u{201C}' | '\u{201D}' | '\u{2033}' => '"',
+        '\u{2010}' | '\u{2011}' | '\u{2012}' | '\u{2013}' | '\u{2014}' | '\u{2212}' => '-',
+        '\u{FF1D}' => '=',
+        c if c.is_whitespace() => ' ',
+        c => c,
+    }
+}
+
+/// Parse `P" = F'[-I"]` at `start`, returning (end, kind, paper inches per foot).
+fn parse_imperial(chars: &[char], start: usize) -> Option<(usize, ScaleKind, f64)> {
+    let mut c = Cursor { chars, pos: start };
+    let paper = c.mixed_number()?;
+    c.skip_spaces();
+    if !c.inch_mark() {
+        return None;
+    }
+    c.skip_spaces();
+    if !c.eat('=') {
+        return None;
+    }
+    c.skip_spaces();
+    let feet = c.number()?;
+    c.skip_spaces();
+    if !c.foot_mark() {
+        return None;
+    }
+
+    // Optional inches: 1'-0", 1' 6", 1'6"
+    let mut inches = 0.0;
+    let save = c.pos;
+    c.skip_spaces();
+    c.eat('-');
+    c.skip_spaces();
+    match c.mixed_number() {
+        Some(value) if c.inch_mark() => inches = value,
+        _ => c.pos = save,
+    }
+
+    let real_feet = feet + inches / 12.0;
+    if paper <= 0.0 || real_feet <= 0.0 {
+        return None;
+    }
+    let kind = if paper == 1.0 && inches == 0.0 && feet >= 10.0 && feet % 10.0 == 0.0 {
+        ScaleKind::Engineering
+    } else {
+        ScaleKind::Architectural
+    };
+    Some((c.pos, kind, paper / real_feet))
+}
+
+/// Parse `1:N` at `start`.
+fn parse_metric(chars: &[char], start: usize) -> Option<(usize, ScaleKind, f64)> {
+    let mut c = Cursor { chars, pos: start };
+    if c.integer()? != 1 {
+        return None;
+    }
+    c.skip_spaces();
+    if !c.eat(':') {
+        return None;
+    }
+    c.skip_spaces();
+    let ratio = c.integer()?;
+    if !(2..=MAX_METRIC_RATIO).contains(&ratio) || matches!(c.peek(), Some('0'..='9' | '.' | ':')) {
+        return None;
+    }
+    // One real foot is 12 real inches, drawn 1/N as large
+    Some((c.pos, ScaleKind::Metric, 12.0 / ratio as f64))
+}
+
+struct Cursor<'a> {
+    chars: &'a [char],
+    pos: usize,
+}
+
+impl Cursor<'_> {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn eat(&mut self, expected: char) -> bool {
+        if self.peek() == Some(expected) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn skip_spaces(&mut self) {
+        while self.peek() == Some(' ') {
+            self.pos += 1;
+        }
+    }
+
+    fn integer(&mut self) -> Option<u32> {
+        let start = self.pos;
+        while matches!(self.peek(), Some('0'..='9')) {
+            self.pos += 1;
+        }
+        let digits: String = self.chars[start..self.pos].iter().collect();
+        digits.parse().ok()
+    }
+
+    /// `12` or `1.5`
+    fn number(&mut self) -> Option<f64> {
+        let whole = self.integer()? as f64;
+        let save = self.pos;
+        if self.eat('.') {
+            let start = self.pos;
+            if let Some(frac) = self.integer() {
+                let digits = (self.pos - start) as i32;
+                return Some(whole + frac as f64 / 10f64.powi(digits));
+            }
+            self.pos = save;
+        }
+        Some(whole)
+    }
+
+    /// `3`, `1.5`, `3/16`, `1 1/2` or `1-1/2`
+    fn mixed_number(&mut self) -> Option<f64> {
+        let whole = self.number()?;
+        let save = self.pos;
+        if self.eat('/') {
+            return match self.integer() {
+                Some(den) if den > 0 && whole.fract() == 0.0 => Some(whole / den as f64),
+                _ => None,
+            };
+        }
+
+        self.skip_spaces();
+        self.eat('-');
+        self.skip_spaces();
+        if let Some(num) = self.integer() {
+            if self.eat('/') {
+                if let Some(den) = self.integer().filter(|&d| d > 0 && num < d) {
+                    return Some(whole + num as f64 / den as f64);
+                }
+            }
+        }
+        self.pos = save;
+        Some(whole)
+    }
+
+    /// `"` or two apostrophes, as some fonts draw it
+    fn inch_mark(&mut self) -> bool {
+        if self.eat('"') {
+            return true;
+        }
+        if self.chars.get(self.pos..self.pos + 2) == Some(&['\'', '\''][..]) {
+            self.pos += 2;
+            return true;
+        }
+        false
+    }
+
+    /// A single apostrophe not starting an inch mark
+    fn foot_mark(&mut self) -> bool {
+        if self.peek() == Some('\'') && self.chars.get(self.pos + 1) != Some(&'\'') {
+            self.pos += 1;
+            return true;
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PPI: f64 = 108.0;
+
+    fn ratios(text: &str) -> Vec<(String, ScaleKind, f64)> {
+        find_notations(text, PPI)
+            .into_iter()
+            .map(|n| (n.text, n.kind, n.paper_inches_per_foot))
+            .collect()
+    }
+
+    #[test]
+    fn test_imperial_notations() {
+        let found = ratios("FLOOR PLAN\nSCALE: 1/4\" = 1'-0\"\nDETAIL 3: 3/4\"=1'0\"");
+        assert_eq!(
+            found,
+            vec![
+                ("1/4\" = 1'-0\"".to_string(), ScaleKind::Architectural, 0.25),
+                ("3/4\"=1'0\"".to_string(), ScaleKind::Architectural, 0.75),
+            ]
+        );
+
+        // Typographic quotes, mixed numbers and engineering scales
+        let found = ratios("1 1/2\u{201D} = 1\u{2019} \u{2013} 0\u{201D}, SCALE: 1\" = 20'");
+        assert_eq!(found[0].2, 1.5);
+        assert_eq!(found[1], ("1\" = 20'".to_string(), ScaleKind::Engineering, 0.05));
+
+        // Real side with inches, and an inch mark drawn as two apostrophes
+        let found = ratios("1'' = 2'-6\"");
+        assert!((found[0].2 - 0.4).abs() < 1e-12);
+
+        // Not scales: dimensions and notes without '='
+        assert!(ratios("12'-6\" x 10'-0\", 5/8\" GYP. BD.").is_empty());
+    }
+
+    #[test]
+    fn test_metric_notations_and_nts() {
+        let notations = find_notations("SCALE 1:100  detail 1 : 20  ref 21:100 11:30", PPI);
+        assert_eq!(notations.len(), 2);
+        assert_eq!(notations[0].kind, ScaleKind::Metric);
+        assert!(notations[0].labelled && !notations[1].labelled);
+        assert!((notations[0].pixels_per_foot - 12.96).abs() < 1e-9);
+        assert!((notations[1].paper_inches_per_foot - 0.6).abs() < 1e-12);
+
+        assert!(is_not_to_scale("SCALE: N.T.S."));
+        assert!(is_not_to_scale("scale: nts"));
+        assert!(!is_not_to_scale("PLANTS AND FIXTURES"));
+    }
+
+    #[test]
+    fn test_propose() {
+        let mut notations = find_notations("1/8\" = 1'-0\" 1/4\" = 1'-0\" 1/8\" = 1'-0\"", PPI);
+        assert_eq!(propose(&notations, None), Some(0));
+        assert_eq!(propose(&[], None), None);
+
+        notations[1].bounds = Some([500.0, 500.0, 600.0, 520.0]);
+        assert_eq!(propose(&notations, Some([400.0, 400.0, 800.0, 800.0])), Some(1));
+        // Nothing in the region: fall back to the whole page
+        assert_eq!(propose(&notations, Some([0.0, 0.0, 10.0, 10.0])), Some(0));
+
+        notations[2].labelled = true;
+        assert_eq!(propose(&notations, None), Some(2));
+    }
+}
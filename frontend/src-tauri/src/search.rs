@@ -0,0 +1,481 @@
+//! Search Module
+//!
+//! Bookkeeping for long-running document searches. A search registers under
+//! the id the frontend picked for it and gets a cancellation flag; page
+//! workers check the flag before each page, so `cancel_search` (or starting
+//! a new search under the same id) stops the remaining pages. Results are
+//! streamed to the frontend as events while the search runs.
+//!
+//! Plain searches use MuPDF's substring search. Case-sensitive, whole-word
+//! and regex searches run a `Matcher` over each line of the page's
+//! structured text instead, building hit quads from the matched characters.
+
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use crate::mupdf_wrapper::SearchHit;
+use crate::structured_text::TextChar;
+
+/// How a query is matched. The default is MuPDF's case-insensitive substring
+/// search.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct SearchOptions {
+    pub case_sensitive: bool,
+    /// Only match where the query is not part of a longer word
+    pub whole_word: bool,
+    /// Treat the query as a regular expression (e.g. `D\d{3}`)
+    pub regex: bool,
+}
+
+/// A compiled query.
+#[derive(Debug, Clone)]
+pub enum Matcher {
+    /// Handed to MuPDF as is
+    Plain(String),
+    Pattern { regex: Regex, whole_word: bool },
+}
+
+impl Matcher {
+    pub fn new(query: &str, options: &SearchOptions) -> Result<Self, String> {
+        if *options == SearchOptions::default() {
+            return Ok(Matcher::Plain(query.to_string()));
+        }
+        let pattern = if options.regex {
+            query.to_string()
+        } else {
+            regex::escape(query)
+        };
+        let regex = RegexBuilder::new(&pattern)
+            .case_insensitive(!options.case_sensitive)
+            .build()
+            .map_err(|e| e.to_string())?;
+        Ok(Matcher::Pattern {
+            regex,
+            whole_word: options.whole_word,
+        })
+    }
+}
+
+/// One search hit: its quads (one per line when it wraps) and the text it
+/// matched.
+#[derive(Debug, Clone)]
+pub struct SearchMatch {
+    pub quads: Vec<SearchHit>,
+    pub text: String,
+}
+
+/// Group MuPDF hit quads into hits, each quad flagged if it starts a new
+/// hit. `text` is the matched text of every hit.
+pub fn group_quads(quads: Vec<(SearchHit, bool)>, text: &str) -> Vec<SearchMatch> {
+    let mut matches: Vec<SearchMatch> = Vec::new();
+    for (quad, starts_hit) in quads {
+        match matches.last_mut() {
+            Some(last) if !starts_hit => last.quads.push(quad),
+            _ => matches.push(SearchMatch {
+                quads: vec![quad],
+                text: text.to_string(),
+            }),
+        }
+    }
+    matches
+}
+
+/// Run a pattern over the lines of a page's characters (as returned by the
+/// bridge, in reading order), returning each hit with its matched text. A
+/// hit's quad is the box of its characters; matches never span lines.
+pub fn find_matches(chars: &[TextChar], regex: &Regex, whole_word: bool) -> Vec<(SearchHit, String)> {
+    let mut hits = Vec::new();
+    let mut start = 0;
+    while start < chars.len() {
+        let key = (chars[start].block, chars[start].line);
+        let end = chars[start..]
+            .iter()
+            .position(|ch| (ch.block, ch.line) != key)
+            .map_or(chars.len(), |len| start + len);
+        find_in_line(&chars[start..end], regex, whole_word, &mut hits);
+        start = end;
+    }
+    hits
+}
+
+fn find_in_line(
+    line: &[TextChar],
+    regex: &Regex,
+    whole_word: bool,
+    hits: &mut Vec<(SearchHit, String)>,
+) {
+    let text: String = line.iter().map(|ch| ch.c).collect();
+    // Byte offset of each character, to map matches back to characters
+    let offsets: Vec<usize> = text.char_indices().map(|(i, _)| i).collect();
+    let char_at = |byte: usize| offsets.partition_point(|&o| o < byte);
+    let is_word = |idx: usize| line.get(idx).is_some_and(|ch| ch.c.is_alphanumeric());
+
+    for found in regex.find_iter(&text) {
+        if found.is_empty() {
+            continue;
+        }
+        let (first, end) = (char_at(found.start()), char_at(found.end()));
+        if whole_word && ((first > 0 && is_word(first - 1)) || is_word(end)) {
+            continue;
+        }
+        let [x0, y0, x1, y1] = line[first..end].iter().fold(
+            [f32::INFINITY, f32::INFINITY, f32::NEG_INFINITY, f32::NEG_INFINITY],
+            |b, ch| {
+                [
+                    b[0].min(ch.bbox[0]),
+                    b[1].min(ch.bbox[1]),
+                    b[2].max(ch.bbox[2]),
+                    b[3].max(ch.bbox[3]),
+                ]
+            },
+        );
+        let hit = SearchHit {
+            ul: (x0, y0),
+            ur: (x1, y0),
+            lr: (x1, y1),
+            ll: (x0, y1),
+        };
+        hits.push((hit, found.as_str().to_string()));
+    }
+}
+
+/// Occurrence counts of a document search, for turning a search straight
+/// into a count takeoff.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SearchTally {
+    pub total: usize,
+    /// Pages with at least one hit, in page order
+    pub pages: Vec<PageTally>,
+    /// One entry per distinct matched string (e.g. `WP-1`, `WP-2`), in
+    /// natural order
+    pub terms: Vec<TermTally>,
+    /// Pages that could not be searched, so are missing from the counts
+    pub failed_pages: Vec<i32>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PageTally {
+    pub page_idx: i32,
+    pub count: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TermTally {
+    /// The matched text as first seen
+    pub text: String,
+    pub count: usize,
+    pub pages: Vec<PageTally>,
+}
+
+/// Key under which a matched string is counted: unless `case_sensitive`,
+/// strings differing only in case share one.
+pub fn term_key(text: &str, case_sensitive: bool) -> String {
+    if case_sensitive {
+        text.to_string()
+    } else {
+        text.to_uppercase()
+    }
+}
+
+/// Count matched strings per page and per distinct string. Unless
+/// `case_sensitive`, strings differing only in case count as one term.
+pub fn tally(mut pages: Vec<(i32, Vec<String>)>, case_sensitive: bool) -> SearchTally {
+    pages.sort_by_key(|(page_idx, _)| *page_idx);
+    let mut page_tallies = Vec::new();
+    let mut terms: Vec<TermTally> = Vec::new();
+    let mut term_index: HashMap<String, usize> = HashMap::new();
+
+    for (page_idx, texts) in pages {
+        if texts.is_empty() {
+            continue;
+        }
+        page_tallies.push(PageTally {
+            page_idx,
+            count: texts.len(),
+        });
+        for text in texts {
+            let idx = *term_index.entry(term_key(&text, case_sensitive)).or_insert_with(|| {
+                terms.push(TermTally {
+                    text,
+                    count: 0,
+                    pages: Vec::new(),
+                });
+                terms.len() - 1
+            });
+            let term = &mut terms[idx];
+            term.count += 1;
+            match term.pages.last_mut() {
+                Some(last) if last.page_idx == page_idx => last.count += 1,
+                _ => term.pages.push(PageTally { page_idx, count: 1 }),
+            }
+        }
+    }
+
+    terms.sort_by(|a, b| natural_cmp(&a.text, &b.text));
+    SearchTally {
+        total: page_tallies.iter().map(|page| page.count).sum(),
+        pages: page_tallies,
+        terms,
+        failed_pages: Vec::new(),
+    }
+}
+
+/// Compare strings with digit runs by value, so `WP-2` sorts before `WP-10`.
+fn natural_cmp(a: &str, b: &str) -> std::cmp::Ordering {
+    let (mut a, mut b) = (a.chars().peekable(), b.chars().peekable());
+    loop {
+        match (a.peek().copied(), b.peek().copied()) {
+            (None, None) => return std::cmp::Ordering::Equal,
+            (None, Some(_)) => return std::cmp::Ordering::Less,
+            (Some(_), None) => return std::cmp::Ordering::Greater,
+            (Some(x), Some(y)) if x.is_ascii_digit() && y.is_ascii_digit() => {
+                let take = |it: &mut std::iter::Peekable<std::str::Chars>| {
+                    let mut digits = String::new();
+                    while let Some(c) = it.next_if(char::is_ascii_digit) {
+                        digits.push(c);
+                    }
+                    digits
+                };
+                let (x, y) = (take(&mut a), take(&mut b));
+                let (x, y) = (x.trim_start_matches('0'), y.trim_start_matches('0'));
+                let order = x.len().cmp(&y.len()).then_with(|| x.cmp(y));
+                if order.is_ne() {
+                    return order;
+                }
+            }
+            (Some(x), Some(y)) => {
+                if x != y {
+                    return x.cmp(&y);
+                }
+                a.next();
+                b.next();
+            }
+        }
+    }
+}
+
+/// Event emitted after each searched page
+pub const PAGE_EVENT: &str = "search-page";
+
+/// Event emitted once a search has finished or was cancelled
+pub const DONE_EVENT: &str = "search-done";
+
+/// Payload of `search-page`.
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchPageEvent {
+    pub search_id: String,
+    pub doc_id: String,
+    pub page_idx: i32,
+    pub hits: Vec<SearchHit>,
+    /// Pages searched so far, for progress display
+    pub pages_searched: usize,
+    pub page_count: usize,
+}
+
+/// Payload of `search-done`.
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchDoneEvent {
+    pub search_id: String,
+    /// `None` for project-wide searches
+    pub doc_id: Option<String>,
+    pub total_hits: usize,
+    pub cancelled: bool,
+}
+
+/// Cancellation flag of one running search.
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    fn same(&self, other: &CancelToken) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+/// Running searches by frontend-chosen id.
+#[derive(Default)]
+pub struct SearchRegistry {
+    running: Mutex<HashMap<String, CancelToken>>,
+}
+
+impl SearchRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a search, cancelling any earlier search under the same id.
+    pub fn start(&self, search_id: &str) -> CancelToken {
+        let token = CancelToken::default();
+        if let Ok(mut running) = self.running.lock() {
+            if let Some(previous) = running.insert(search_id.to_string(), token.clone()) {
+                previous.cancel();
+            }
+        }
+        token
+    }
+
+    /// Cancel a running search. Returns false if no search has that id.
+    pub fn cancel(&self, search_id: &str) -> bool {
+        let token = self
+            .running
+            .lock()
+            .ok()
+            .and_then(|mut running| running.remove(search_id));
+        match token {
+            Some(token) => {
+                token.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Forget a finished search, unless a newer one took over its id.
+    pub fn finish(&self, search_id: &str, token: &CancelToken) {
+        if let Ok(mut running) = self.running.lock() {
+            if running.get(search_id).is_some_and(|current| current.same(token)) {
+                running.remove(search_id);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// One line of characters per string, 5pt wide each.
+    fn page(lines: &[&str]) -> Vec<TextChar> {
+        lines
+            .iter()
+            .enumerate()
+            .flat_map(|(line, text)| {
+                text.chars().enumerate().map(move |(i, c)| TextChar {
+                    bbox: [i as f32 * 5.0, line as f32 * 10.0, i as f32 * 5.0 + 5.0, line as f32 * 10.0 + 8.0],
+                    size: 8.0,
+                    c,
+                    block: 0,
+                    line: line as i32,
+                    dir: (1.0, 0.0),
+                    font: 0,
+                })
+            })
+            .collect()
+    }
+
+    fn count(chars: &[TextChar], query: &str, options: SearchOptions) -> usize {
+        match Matcher::new(query, &options).unwrap() {
+            Matcher::Pattern { regex, whole_word } => find_matches(chars, &regex, whole_word).len(),
+            Matcher::Plain(_) => panic!("expected a pattern"),
+        }
+    }
+
+    #[test]
+    fn test_matcher_options() {
+        let chars = page(&["D101 D102 DD1034 d103", "REC RECEPT rec"]);
+        let regex = SearchOptions {
+            regex: true,
+            case_sensitive: true,
+            ..Default::default()
+        };
+        assert_eq!(count(&chars, r"D\d{3}", regex), 3);
+        assert_eq!(count(&chars, r"D\d{3}", SearchOptions { whole_word: true, ..regex }), 2);
+        assert_eq!(count(&chars, r"D\d{3}", SearchOptions { case_sensitive: false, ..regex }), 4);
+
+        let words = SearchOptions {
+            whole_word: true,
+            ..Default::default()
+        };
+        assert_eq!(count(&chars, "rec", words), 2);
+        assert_eq!(
+            count(&chars, "REC", SearchOptions { case_sensitive: true, ..Default::default() }),
+            2
+        );
+
+        assert!(matches!(Matcher::new("REC", &SearchOptions::default()), Ok(Matcher::Plain(_))));
+        assert!(Matcher::new("D(", &regex).is_err());
+    }
+
+    #[test]
+    fn test_hit_quads() {
+        let chars = page(&["TYPE A", "TYPE B"]);
+        let regex = Regex::new("B").unwrap();
+        let hits = find_matches(&chars, &regex, false);
+        assert_eq!(hits.len(), 1);
+        assert_eq!((hits[0].0.ul, hits[0].0.lr), ((25.0, 10.0), (30.0, 18.0)));
+        assert_eq!(hits[0].1, "B");
+    }
+
+    #[test]
+    fn test_group_quads_counts_wrapped_hit_once() {
+        let quad = |y: f32| SearchHit {
+            ul: (0.0, y),
+            ur: (10.0, y),
+            lr: (10.0, y + 8.0),
+            ll: (0.0, y + 8.0),
+        };
+        // The second hit wraps onto the next line
+        let quads = vec![(quad(0.0), true), (quad(20.0), true), (quad(30.0), false), (quad(50.0), true)];
+        let matches = group_quads(quads, "FIRE DAMPER");
+        assert_eq!(matches.iter().map(|m| m.quads.len()).collect::<Vec<_>>(), vec![1, 2, 1]);
+        assert_eq!(matches[1].quads[1].ul, (0.0, 30.0));
+        assert_eq!(matches[2].text, "FIRE DAMPER");
+        assert!(group_quads(Vec::new(), "x").is_empty());
+    }
+
+    #[test]
+    fn test_tally_groups_terms() {
+        let strings = |texts: &[&str]| texts.iter().map(|t| t.to_string()).collect::<Vec<_>>();
+        let result = tally(
+            vec![
+                (3, strings(&["WP-10", "wp-2"])),
+                (0, strings(&["WP-2", "WP-1", "WP-2"])),
+                (1, Vec::new()),
+            ],
+            false,
+        );
+        assert_eq!(result.total, 5);
+        assert_eq!(
+            result.pages,
+            vec![PageTally { page_idx: 0, count: 3 }, PageTally { page_idx: 3, count: 2 }]
+        );
+        let terms: Vec<(&str, usize)> = result.terms.iter().map(|t| (t.text.as_str(), t.count)).collect();
+        assert_eq!(terms, vec![("WP-1", 1), ("WP-2", 3), ("WP-10", 1)]);
+        assert_eq!(result.terms[1].pages.len(), 2);
+
+        let sensitive = tally(vec![(0, strings(&["WP-2", "wp-2"]))], true);
+        assert_eq!(sensitive.terms.len(), 2);
+    }
+
+    #[test]
+    fn test_cancel_and_restart() {
+        let registry = SearchRegistry::new();
+        let first = registry.start("panel");
+        let second = registry.start("panel");
+        assert!(first.is_cancelled());
+        assert!(!second.is_cancelled());
+
+        // The superseded search finishing must not forget the new one
+        registry.finish("panel", &first);
+        assert!(registry.cancel("panel"));
+        assert!(second.is_cancelled());
+        assert!(!registry.cancel("panel"));
+
+        let third = registry.start("panel");
+        registry.finish("panel", &third);
+        assert!(!registry.cancel("panel"));
+        assert!(!third.is_cancelled());
+    }
+}
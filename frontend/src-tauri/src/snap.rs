@@ -0,0 +1,303 @@
+//! Snap-to-Geometry Module
+//!
+//! Finds the best snap target on a page's vector linework near the cursor.
+//! Candidates are ranked by kind first (endpoint beats intersection beats
+//! midpoint, and so on), then by distance to the cursor.
+//!
+//! Coordinates are in frontend space, the same as the spatial index.
+
+use crate::spatial::{VectorGeometry, VectorObject};
+use rstar::{RTree, AABB};
+use serde::Serialize;
+
+type Pt = [f64; 2];
+
+/// Number of straight pieces a curve is flattened into for nearest/intersection tests
+const CURVE_STEPS: usize = 16;
+
+/// Cap on segments tested pairwise for intersections (O(n^2) on dense
+/// hatching); the pieces nearest the cursor are kept
+const MAX_INTERSECTION_SEGMENTS: usize = 256;
+
+/// Kind of snap target, in priority order (highest first).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SnapKind {
+    Endpoint,
+    Intersection,
+    Midpoint,
+    /// Foot of the perpendicular from the anchor point onto a line
+    Perpendicular,
+    /// Closest point on any segment
+    Nearest,
+}
+
+/// The chosen snap target.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct SnapCandidate {
+    pub x: f64,
+    pub y: f64,
+    pub kind: SnapKind,
+    /// Distance from the cursor to the snap point
+    pub distance: f64,
+}
+
+/// Keeps the best candidate offered so far.
+struct SnapPicker {
+    cursor: Pt,
+    radius_sq: f64,
+    best: Option<(SnapKind, f64, Pt)>,
+}
+
+impl SnapPicker {
+    fn offer(&mut self, kind: SnapKind, p: Pt) {
+        let d = dist_sq(p, self.cursor);
+        if d > self.radius_sq {
+            return;
+        }
+        let better = match self.best {
+            None => true,
+            Some((best_kind, best_d, _)) => (kind, d) < (best_kind, best_d),
+        };
+        if better {
+            self.best = Some((kind, d, p));
+        }
+    }
+}
+
+/// A straight piece of linework; `is_line` is false for flattened curve pieces.
+struct Piece {
+    a: Pt,
+    b: Pt,
+    is_line: bool,
+    /// Id of the object the piece comes from
+    source: usize,
+}
+
+/// Find the best snap point within `radius` of `cursor`.
+///
+/// # Arguments
+/// * `tree` - Spatial index of the page
+/// * `cursor` - Cursor position
+/// * `radius` - Maximum snap distance
+/// * `anchor` - Previously placed point, enables perpendicular snapping
+pub fn find_snap(
+    tree: &RTree<VectorObject>,
+    cursor: Pt,
+    radius: f64,
+    anchor: Option<Pt>,
+) -> Option<SnapCandidate> {
+    let envelope = AABB::from_corners(
+        [cursor[0] - radius, cursor[1] - radius],
+        [cursor[0] + radius, cursor[1] + radius],
+    );
+
+    let mut picker = SnapPicker {
+        cursor,
+        radius_sq: radius * radius,
+        best: None,
+    };
+    let mut pieces = Vec::new();
+
+    for obj in tree.locate_in_envelope_intersecting(&envelope) {
+        match obj.geom {
+            VectorGeometry::Line(a, b) => {
+                picker.offer(SnapKind::Endpoint, a);
+                picker.offer(SnapKind::Endpoint, b);
+                picker.offer(SnapKind::Midpoint, lerp(a, b, 0.5));
+                pieces.push(Piece {
+                    a,
+                    b,
+                    is_line: true,
+                    source: obj.id,
+                });
+            }
+            VectorGeometry::Curve(pts) => {
+                picker.offer(SnapKind::Endpoint, pts[0]);
+                picker.offer(SnapKind::Endpoint, pts[3]);
+                picker.offer(SnapKind::Midpoint, cubic_point(&pts, 0.5));
+                let mut prev = pts[0];
+                for step in 1..=CURVE_STEPS {
+                    let next = cubic_point(&pts, step as f64 / CURVE_STEPS as f64);
+                    pieces.push(Piece {
+                        a: prev,
+                        b: next,
+                        is_line: false,
+                        source: obj.id,
+                    });
+                    prev = next;
+                }
+            }
+        }
+    }
+
+    for piece in &pieces {
+        picker.offer(SnapKind::Nearest, closest_on_segment(cursor, piece.a, piece.b));
+        if let (Some(anchor), true) = (anchor, piece.is_line) {
+            if let Some(foot) = perpendicular_foot(anchor, piece.a, piece.b) {
+                picker.offer(SnapKind::Perpendicular, foot);
+            }
+        }
+    }
+
+    // Pieces of one curve meet at its flattening vertices, which are not
+    // intersections, so only pieces of different objects are paired
+    pieces.sort_by(|p, q| {
+        let dp = dist_sq(cursor, closest_on_segment(cursor, p.a, p.b));
+        let dq = dist_sq(cursor, closest_on_segment(cursor, q.a, q.b));
+        dp.total_cmp(&dq)
+    });
+    pieces.truncate(MAX_INTERSECTION_SEGMENTS);
+    for (i, first) in pieces.iter().enumerate() {
+        for second in &pieces[i + 1..] {
+            if first.source == second.source {
+                continue;
+            }
+            if let Some(p) = intersect(first, second) {
+                picker.offer(SnapKind::Intersection, p);
+            }
+        }
+    }
+
+    picker.best.map(|(kind, d, p)| SnapCandidate {
+        x: p[0],
+        y: p[1],
+        kind,
+        distance: d.sqrt(),
+    })
+}
+
+fn dist_sq(a: Pt, b: Pt) -> f64 {
+    let dx = a[0] - b[0];
+    let dy = a[1] - b[1];
+    dx * dx + dy * dy
+}
+
+fn lerp(a: Pt, b: Pt, t: f64) -> Pt {
+    [a[0] + (b[0] - a[0]) * t, a[1] + (b[1] - a[1]) * t]
+}
+
+/// Evaluate a cubic bezier at `t` (de Casteljau).
+fn cubic_point(p: &[Pt; 4], t: f64) -> Pt {
+    let ab = lerp(p[0], p[1], t);
+    let bc = lerp(p[1], p[2], t);
+    let cd = lerp(p[2], p[3], t);
+    lerp(lerp(ab, bc, t), lerp(bc, cd, t), t)
+}
+
+/// Parameter of the projection of `p` onto the infinite line through `a` and `b`.
+fn project(p: Pt, a: Pt, b: Pt) -> Option<f64> {
+    let len_sq = dist_sq(a, b);
+    if len_sq == 0.0 {
+        return None;
+    }
+    Some(((p[0] - a[0]) * (b[0] - a[0]) + (p[1] - a[1]) * (b[1] - a[1])) / len_sq)
+}
+
+fn closest_on_segment(p: Pt, a: Pt, b: Pt) -> Pt {
+    match project(p, a, b) {
+        Some(t) => lerp(a, b, t.clamp(0.0, 1.0)),
+        None => a,
+    }
+}
+
+/// Foot of the perpendicular from `p`, only if it lands within the segment.
+fn perpendicular_foot(p: Pt, a: Pt, b: Pt) -> Option<Pt> {
+    let t = project(p, a, b)?;
+    (0.0..=1.0).contains(&t).then(|| lerp(a, b, t))
+}
+
+fn intersect(s1: &Piece, s2: &Piece) -> Option<Pt> {
+    let r = [s1.b[0] - s1.a[0], s1.b[1] - s1.a[1]];
+    let s = [s2.b[0] - s2.a[0], s2.b[1] - s2.a[1]];
+    let denom = r[0] * s[1] - r[1] * s[0];
+    if denom.abs() < f64::EPSILON {
+        return None; // Parallel or degenerate
+    }
+    let qp = [s2.a[0] - s1.a[0], s2.a[1] - s1.a[1]];
+    let t = (qp[0] * s[1] - qp[1] * s[0]) / denom;
+    let u = (qp[0] * r[1] - qp[1] * r[0]) / denom;
+    if (0.0..=1.0).contains(&t) && (0.0..=1.0).contains(&u) {
+        Some(lerp(s1.a, s1.b, t))
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tree(lines: &[(Pt, Pt)]) -> RTree<VectorObject> {
+        RTree::bulk_load(
+            lines
+                .iter()
+                .enumerate()
+                .map(|(id, &(a, b))| VectorObject {
+                    id,
+                    geom: VectorGeometry::Line(a, b),
+                })
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_endpoint_wins_over_nearest() {
+        let t = tree(&[([0.0, 0.0], [100.0, 0.0])]);
+        let snap = find_snap(&t, [3.0, 2.0], 10.0, None).unwrap();
+        assert_eq!(snap.kind, SnapKind::Endpoint);
+        assert_eq!((snap.x, snap.y), (0.0, 0.0));
+    }
+
+    #[test]
+    fn test_intersection_of_crossing_lines() {
+        let t = tree(&[([0.0, 30.0], [100.0, 30.0]), ([60.0, 0.0], [60.0, 100.0])]);
+        let snap = find_snap(&t, [58.0, 31.0], 5.0, None).unwrap();
+        assert_eq!(snap.kind, SnapKind::Intersection);
+        assert!((snap.x - 60.0).abs() < 1e-9 && (snap.y - 30.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_perpendicular_and_nearest() {
+        let t = tree(&[([0.0, 0.0], [100.0, 0.0])]);
+        let nearest = find_snap(&t, [30.0, 3.0], 5.0, None).unwrap();
+        assert_eq!(nearest.kind, SnapKind::Nearest);
+        assert_eq!((nearest.x, nearest.y), (30.0, 0.0));
+
+        let perp = find_snap(&t, [30.0, 3.0], 5.0, Some([28.0, 40.0])).unwrap();
+        assert_eq!(perp.kind, SnapKind::Perpendicular);
+        assert!((perp.x - 28.0).abs() < 1e-9 && perp.y.abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_curve_has_no_self_intersections() {
+        let t = RTree::bulk_load(vec![VectorObject {
+            id: 0,
+            geom: VectorGeometry::Curve([[0.0, 0.0], [0.0, 100.0], [100.0, 100.0], [100.0, 0.0]]),
+        }]);
+        // Cursor on the arc, away from its ends and midpoint
+        let on_arc = cubic_point(&[[0.0, 0.0], [0.0, 100.0], [100.0, 100.0], [100.0, 0.0]], 0.25);
+        let snap = find_snap(&t, on_arc, 5.0, None).unwrap();
+        assert_eq!(snap.kind, SnapKind::Nearest);
+    }
+
+    #[test]
+    fn test_intersection_near_cursor_survives_cap() {
+        // Long hatching lines crossing the vertical line away from the cursor
+        let mut lines: Vec<(Pt, Pt)> = vec![([-100.0, 0.0], [100.0, 0.0]), ([0.0, -100.0], [0.0, 100.0])];
+        lines.extend((0..MAX_INTERSECTION_SEGMENTS * 4).map(|i| {
+            let y = if i % 2 == 0 { 40.0 } else { -40.0 } + i as f64 * 0.001;
+            ([-1000.0, y], [1000.0, y])
+        }));
+        let t = tree(&lines);
+        let snap = find_snap(&t, [1.0, 1.0], 50.0, None).unwrap();
+        assert_eq!(snap.kind, SnapKind::Intersection);
+        assert!(snap.x.abs() < 1e-9 && snap.y.abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_nothing_outside_radius() {
+        let t = tree(&[([0.0, 0.0], [100.0, 0.0])]);
+        assert!(find_snap(&t, [50.0, 30.0], 10.0, None).is_none());
+    }
+}
@@ -1,33 +1,219 @@
-use rstar::{RTreeObject, AABB};
-use serde::Serialize;
+//! Spatial Index Module
+//!
+//! Holds the vector linework extracted from PDF pages in an R-Tree so that
+//! viewport queries (and snap-to-line) only touch geometry near the cursor.
+//!
+//! All coordinates stored here are in frontend space, i.e. PDF points scaled
+//! by the render zoom, matching the coordinate system of search hits.
 
-#[derive(Debug, Clone, Serialize)]
-pub struct VectorElement {
+use crate::mupdf_wrapper::PathSegment;
+use rstar::{RTree, RTreeObject, AABB};
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+/// Geometry of a single indexed vector primitive.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum VectorGeometry {
+    /// Straight segment from start to end
+    Line([f64; 2], [f64; 2]),
+    /// Cubic bezier: start, control 1, control 2, end
+    Curve([[f64; 2]; 4]),
+}
+
+/// A vector primitive extracted from a PDF page.
+pub struct VectorObject {
     pub id: usize,
-    pub points: Vec<(f64, f64)>, // Simple line strip
-    pub is_closed: bool,
+    pub geom: VectorGeometry,
 }
 
-impl RTreeObject for VectorElement {
+impl RTreeObject for VectorObject {
     type Envelope = AABB<[f64; 2]>;
 
     fn envelope(&self) -> Self::Envelope {
-        if self.points.is_empty() {
-            return AABB::from_corners([0.0, 0.0], [0.0, 0.0]); // Empty
+        match &self.geom {
+            VectorGeometry::Line(a, b) => AABB::from_corners(*a, *b),
+            // A cubic bezier always lies inside the convex hull of its control points
+            VectorGeometry::Curve(pts) => AABB::from_points(pts.iter()),
         }
+    }
+}
+
+/// Convert raw page segments (PDF points) into indexable objects.
+///
+/// # Arguments
+/// * `segments` - Segments as returned by `MuPdfDoc::extract_vectors`
+/// * `scale` - Factor from PDF points to frontend coordinates
+/// * `first_id` - Id assigned to the first object; subsequent ids increment
+pub fn vectors_from_segments(
+    segments: &[PathSegment],
+    scale: f64,
+    first_id: usize,
+) -> Vec<VectorObject> {
+    let s = |(x, y): (f32, f32)| [x as f64 * scale, y as f64 * scale];
+
+    segments
+        .iter()
+        .enumerate()
+        .map(|(i, seg)| {
+            let geom = match *seg {
+                PathSegment::Line { from, to } => VectorGeometry::Line(s(from), s(to)),
+                PathSegment::Curve { from, ctrl1, ctrl2, to } => {
+                    VectorGeometry::Curve([s(from), s(ctrl1), s(ctrl2), s(to)])
+                }
+            };
+            VectorObject {
+                id: first_id + i,
+                geom,
+            }
+        })
+        .collect()
+}
+
+/// Shared handle to the R-Tree of a single page.
+pub type PageIndex = Arc<RTree<VectorObject>>;
 
-        let mut min_x = f64::MAX;
-        let mut min_y = f64::MAX;
-        let mut max_x = f64::MIN;
-        let mut max_y = f64::MIN;
+/// Spatial indexes keyed by document id and page number.
+///
+/// Pages are indexed lazily on first query, since extracting the linework of
+/// every sheet up front is slow for large plan sets. Trees are handed out as
+/// `Arc`s so queries never hold the store lock while walking a tree.
+///
+/// Each document has a generation that eviction bumps, so a tree built from
+/// a document that was closed or replaced meanwhile is not cached.
+#[derive(Default)]
+pub struct SpatialIndexStore {
+    inner: RwLock<StoreInner>,
+}
+
+#[derive(Default)]
+struct StoreInner {
+    documents: HashMap<String, HashMap<i32, PageIndex>>,
+    /// Evictions per document
+    generations: HashMap<String, u64>,
+}
+
+impl StoreInner {
+    fn generation(&self, doc_id: &str) -> u64 {
+        self.generations.get(doc_id).copied().unwrap_or(0)
+    }
+}
+
+impl SpatialIndexStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
 
-        for (x, y) in &self.points {
-            if *x < min_x { min_x = *x; }
-            if *x > max_x { max_x = *x; }
-            if *y < min_y { min_y = *y; }
-            if *y > max_y { max_y = *y; }
+    /// Return the index for a page, building it with `build` if it is not cached.
+    ///
+    /// `build` runs without the store lock held; if two callers race to build
+    /// the same page, the first tree inserted wins. A tree whose document was
+    /// evicted while it was being built is returned but not cached.
+    pub fn get_or_build<F>(&self, doc_id: &str, page: i32, build: F) -> Result<PageIndex, String>
+    where
+        F: FnOnce() -> Result<Vec<VectorObject>, String>,
+    {
+        let generation = {
+            let inner = self.inner.read().map_err(|_| "Failed to lock spatial index")?;
+            if let Some(index) = inner.documents.get(doc_id).and_then(|pages| pages.get(&page)) {
+                return Ok(Arc::clone(index));
+            }
+            inner.generation(doc_id)
+        };
+
+        let tree = Arc::new(RTree::bulk_load(build()?));
+
+        let mut inner = self.inner.write().map_err(|_| "Failed to lock spatial index")?;
+        if inner.generation(doc_id) != generation {
+            return Ok(tree);
+        }
+        let index = inner
+            .documents
+            .entry(doc_id.to_string())
+            .or_default()
+            .entry(page)
+            .or_insert(tree);
+        Ok(Arc::clone(index))
+    }
+
+    /// Drop every cached page index of a document.
+    pub fn evict_document(&self, doc_id: &str) {
+        if let Ok(mut inner) = self.inner.write() {
+            inner.documents.remove(doc_id);
+            *inner.generations.entry(doc_id.to_string()).or_insert(0) += 1;
         }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vectors_from_segments_scales_points() {
+        let segments = vec![
+            PathSegment::Line { from: (0.0, 0.0), to: (10.0, 20.0) },
+            PathSegment::Curve {
+                from: (0.0, 0.0),
+                ctrl1: (0.0, 10.0),
+                ctrl2: (10.0, 10.0),
+                to: (10.0, 0.0),
+            },
+        ];
+        let objects = vectors_from_segments(&segments, 1.5, 100);
+        assert_eq!(objects.len(), 2);
+        assert_eq!(objects[0].id, 100);
+        assert_eq!(objects[1].id, 101);
+        assert_eq!(objects[0].geom, VectorGeometry::Line([0.0, 0.0], [15.0, 30.0]));
+    }
+
+    #[test]
+    fn test_curve_envelope_covers_control_points() {
+        let obj = VectorObject {
+            id: 0,
+            geom: VectorGeometry::Curve([[0.0, 0.0], [0.0, 10.0], [10.0, 10.0], [10.0, 0.0]]),
+        };
+        let env = obj.envelope();
+        assert_eq!(env.lower(), [0.0, 0.0]);
+        assert_eq!(env.upper(), [10.0, 10.0]);
+    }
+
+    #[test]
+    fn test_index_store_builds_once_and_evicts() {
+        let store = SpatialIndexStore::new();
+        let line = || {
+            Ok(vec![VectorObject {
+                id: 0,
+                geom: VectorGeometry::Line([0.0, 0.0], [1.0, 1.0]),
+            }])
+        };
+
+        let first = store.get_or_build("doc", 0, line).unwrap();
+        let cached = store
+            .get_or_build("doc", 0, || Err("should not rebuild".to_string()))
+            .unwrap();
+        assert!(Arc::ptr_eq(&first, &cached));
+
+        store.evict_document("doc");
+        assert!(store
+            .get_or_build("doc", 0, || Err("rebuilt".to_string()))
+            .is_err());
+    }
 
-        AABB::from_corners([min_x, min_y], [max_x, max_y])
+    #[test]
+    fn test_build_racing_eviction_is_not_cached() {
+        let store = SpatialIndexStore::new();
+        let stale = store
+            .get_or_build("doc", 0, || {
+                // The document is closed while its page is being indexed
+                store.evict_document("doc");
+                Ok(Vec::new())
+            })
+            .unwrap();
+        let fresh = store.get_or_build("doc", 0, || Ok(Vec::new())).unwrap();
+        assert!(!Arc::ptr_eq(&stale, &fresh));
+        let cached = store
+            .get_or_build("doc", 0, || Err("should not rebuild".to_string()))
+            .unwrap();
+        assert!(Arc::ptr_eq(&fresh, &cached));
     }
 }
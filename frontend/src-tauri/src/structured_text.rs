@@ -0,0 +1,228 @@
+//! Structured Text Module
+//!
+//! Groups the characters MuPDF extracts from a page into blocks, lines and
+//! words with bounding boxes, font name, size and writing direction. This is
+//! what text selection, schedule parsing and word-level search work on; the
+//! flat string from `extract_page_text` loses all positions.
+
+use serde::Serialize;
+
+/// A character as returned by the bridge, in PDF points (top-left origin).
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextChar {
+    /// `[x0, y0, x1, y1]`
+    pub bbox: [f32; 4],
+    pub size: f32,
+    pub c: char,
+    /// Index of the MuPDF text block
+    pub block: i32,
+    /// Index of the line within its block
+    pub line: i32,
+    /// Writing direction of the line
+    pub dir: (f32, f32),
+    /// Index into the page's font names
+    pub font: usize,
+}
+
+/// Structured text of one page.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StructuredPage {
+    pub page_idx: i32,
+    pub blocks: Vec<TextBlock>,
+}
+
+/// A paragraph-like group of lines.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TextBlock {
+    pub bbox: [f32; 4],
+    pub lines: Vec<StructuredLine>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StructuredLine {
+    pub bbox: [f32; 4],
+    pub text: String,
+    /// Writing direction in degrees clockwise from horizontal (0, 90, 180,
+    /// 270 for the usual orientations)
+    pub rotation: f32,
+    pub words: Vec<TextWord>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TextWord {
+    pub bbox: [f32; 4],
+    pub text: String,
+    /// Font of the word's first character
+    pub font_name: String,
+    /// Largest font size in the word
+    pub font_size: f32,
+}
+
+impl StructuredPage {
+    /// Multiply all boxes and font sizes by `factor`.
+    pub fn scale(&mut self, factor: f32) {
+        let scale_box = |bbox: &mut [f32; 4]| bbox.iter_mut().for_each(|v| *v *= factor);
+        for block in &mut self.blocks {
+            scale_box(&mut block.bbox);
+            for line in &mut block.lines {
+                scale_box(&mut line.bbox);
+                for word in &mut line.words {
+                    scale_box(&mut word.bbox);
+                    word.font_size *= factor;
+                }
+            }
+        }
+    }
+}
+
+/// Group characters (in reading order) into blocks, lines and words. Words
+/// are split on whitespace; `fonts` holds the names `TextChar::font` indexes.
+pub fn build(page_idx: i32, chars: &[TextChar], fonts: &[String]) -> StructuredPage {
+    let mut blocks: Vec<TextBlock> = Vec::new();
+    let mut current: Option<(i32, i32)> = None;
+    let mut word: Vec<&TextChar> = Vec::new();
+
+    for ch in chars {
+        if current != Some((ch.block, ch.line)) {
+            finish_word(&mut word, &mut blocks, fonts);
+            if current.map(|(block, _)| block) != Some(ch.block) {
+                blocks.push(TextBlock {
+                    bbox: EMPTY_BOX,
+                    lines: Vec::new(),
+                });
+            }
+            if let Some(block) = blocks.last_mut() {
+                block.lines.push(StructuredLine {
+                    bbox: EMPTY_BOX,
+                    text: String::new(),
+                    rotation: rotation(ch.dir),
+                    words: Vec::new(),
+                });
+            }
+            current = Some((ch.block, ch.line));
+        }
+
+        if let Some(line) = blocks.last_mut().and_then(|b| b.lines.last_mut()) {
+            line.text.push(ch.c);
+        }
+        if ch.c.is_whitespace() {
+            finish_word(&mut word, &mut blocks, fonts);
+        } else {
+            word.push(ch);
+        }
+    }
+    finish_word(&mut word, &mut blocks, fonts);
+
+    // Lines made only of whitespace carry nothing to select
+    for block in &mut blocks {
+        block.lines.retain(|line| !line.words.is_empty());
+        for line in &mut block.lines {
+            line.bbox = union_all(line.words.iter().map(|w| w.bbox));
+            line.text = line.text.trim().to_string();
+        }
+        block.bbox = union_all(block.lines.iter().map(|l| l.bbox));
+    }
+    blocks.retain(|block| !block.lines.is_empty());
+
+    StructuredPage { page_idx, blocks }
+}
+
+/// Box that any union replaces.
+const EMPTY_BOX: [f32; 4] = [f32::INFINITY, f32::INFINITY, f32::NEG_INFINITY, f32::NEG_INFINITY];
+
+fn union(a: [f32; 4], b: [f32; 4]) -> [f32; 4] {
+    [a[0].min(b[0]), a[1].min(b[1]), a[2].max(b[2]), a[3].max(b[3])]
+}
+
+fn union_all(boxes: impl Iterator<Item = [f32; 4]>) -> [f32; 4] {
+    boxes.fold(EMPTY_BOX, union)
+}
+
+/// Writing direction in degrees, snapped to whole degrees.
+fn rotation((dx, dy): (f32, f32)) -> f32 {
+    let degrees = dy.atan2(dx).to_degrees().round();
+    if degrees < 0.0 {
+        degrees + 360.0
+    } else {
+        degrees
+    }
+}
+
+/// Append the pending characters as a word of the last line.
+fn finish_word(word: &mut Vec<&TextChar>, blocks: &mut [TextBlock], fonts: &[String]) {
+    let Some(first) = word.first() else {
+        return;
+    };
+    let done = TextWord {
+        bbox: union_all(word.iter().map(|ch| ch.bbox)),
+        text: word.iter().map(|ch| ch.c).collect(),
+        font_name: fonts.get(first.font).cloned().unwrap_or_default(),
+        font_size: word.iter().map(|ch| ch.size).fold(0.0, f32::max),
+    };
+    if let Some(line) = blocks.last_mut().and_then(|b| b.lines.last_mut()) {
+        line.words.push(done);
+    }
+    word.clear();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Characters of `text` laid out left to right from `x`, 6pt apart.
+    fn run(text: &str, x: f32, y: f32, block: i32, line: i32, font: usize) -> Vec<TextChar> {
+        text.chars()
+            .enumerate()
+            .map(|(i, c)| {
+                let x0 = x + i as f32 * 6.0;
+                TextChar {
+                    bbox: [x0, y, x0 + 6.0, y + 10.0],
+                    size: 10.0,
+                    c,
+                    block,
+                    line,
+                    dir: (1.0, 0.0),
+                    font,
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_build_groups_words_lines_and_blocks() {
+        let fonts = vec!["Helvetica".to_string(), "Helvetica-Bold".to_string()];
+        let mut chars = run("DOOR  SCHEDULE ", 100.0, 50.0, 0, 0, 1);
+        chars.extend(run("D1 3070 HM", 100.0, 70.0, 0, 1, 0));
+        chars.extend(run("   ", 100.0, 90.0, 0, 2, 0));
+        chars.extend(run("NOTES", 400.0, 50.0, 3, 0, 0));
+
+        let page = build(2, &chars, &fonts);
+        assert_eq!(page.page_idx, 2);
+        assert_eq!(page.blocks.len(), 2);
+
+        let table = &page.blocks[0];
+        assert_eq!(table.lines.len(), 2);
+        assert_eq!(table.lines[0].text, "DOOR  SCHEDULE");
+        let words: Vec<&str> = table.lines[1].words.iter().map(|w| w.text.as_str()).collect();
+        assert_eq!(words, vec!["D1", "3070", "HM"]);
+        assert_eq!(table.lines[0].words[1].font_name, "Helvetica-Bold");
+        assert_eq!(table.lines[0].words[1].bbox, [136.0, 50.0, 184.0, 60.0]);
+        assert_eq!(table.bbox, [100.0, 50.0, 184.0, 80.0]);
+    }
+
+    #[test]
+    fn test_rotation_and_scale() {
+        let mut chars = run("N", 10.0, 10.0, 0, 0, 0);
+        chars[0].dir = (0.0, -1.0);
+        let mut page = build(0, &chars, &[]);
+        let line = &page.blocks[0].lines[0];
+        assert_eq!(line.rotation, 270.0);
+        assert_eq!(line.words[0].font_name, "");
+
+        page.scale(1.5);
+        let word = &page.blocks[0].lines[0].words[0];
+        assert_eq!(word.bbox, [15.0, 15.0, 24.0, 30.0]);
+        assert_eq!(word.font_size, 15.0);
+        assert!(build(0, &[], &[]).blocks.is_empty());
+    }
+}
@@ -0,0 +1,553 @@
+//! Text Index Module
+//!
+//! Per-document inverted index over page text, persisted next to the
+//! ingestion result in `indices/`. Words are lowercased and lightly stemmed
+//! ("outlets" and "outlet" match), and every occurrence keeps its page and
+//! token position, so queries can mix plain terms, "quoted phrases" and
+//! misspellings (fuzzy matching against the vocabulary). Pages are ranked
+//! with BM25 and each hit carries a snippet and its page number.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// BM25 term frequency saturation
+const K1: f32 = 1.2;
+
+/// BM25 page length normalisation
+const B: f32 = 0.75;
+
+/// Score factor for terms matched only through fuzzy expansion
+const FUZZY_WEIGHT: f32 = 0.6;
+
+/// Characters of context on each side of a snippet's match
+const SNIPPET_CONTEXT: usize = 60;
+
+/// Version of `stem`, bumped whenever it changes the terms it produces so
+/// saved indices get rebuilt (indices without one are version 0)
+const STEMMER_VERSION: u32 = 1;
+
+/// Occurrences of a term on one page.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Posting {
+    page: u32,
+    /// Token positions on the page, ascending
+    positions: Vec<u32>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TextIndex {
+    pub doc_id: String,
+    /// Text of each page, for snippets
+    pages: Vec<String>,
+    /// Token count of each page
+    page_lengths: Vec<u32>,
+    /// Stemmed term -> postings in page order
+    postings: HashMap<String, Vec<Posting>>,
+    /// `STEMMER_VERSION` the terms were built with
+    #[serde(default)]
+    stemmer: u32,
+}
+
+/// A ranked page match.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct IndexHit {
+    pub page_idx: i32,
+    pub score: f32,
+    pub snippet: String,
+    /// Indexed terms that matched (after stemming and fuzzy expansion)
+    pub terms: Vec<String>,
+}
+
+/// A word of page text: its index term and byte range.
+#[derive(Debug, Clone, PartialEq)]
+struct Token {
+    term: String,
+    start: usize,
+    end: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum QueryPart {
+    Term(String),
+    Phrase(Vec<String>),
+}
+
+impl TextIndex {
+    /// Index the text of each page of a document.
+    pub fn build(doc_id: &str, pages: Vec<String>) -> Self {
+        let mut postings: HashMap<String, Vec<Posting>> = HashMap::new();
+        let mut page_lengths = Vec::with_capacity(pages.len());
+
+        for (page, text) in pages.iter().enumerate() {
+            let tokens = tokenize(text);
+            page_lengths.push(tokens.len() as u32);
+            for (position, token) in tokens.into_iter().enumerate() {
+                let list = postings.entry(token.term).or_default();
+                match list.last_mut() {
+                    Some(last) if last.page == page as u32 => last.positions.push(position as u32),
+                    _ => list.push(Posting {
+                        page: page as u32,
+                        positions: vec![position as u32],
+                    }),
+                }
+            }
+        }
+
+        Self {
+            doc_id: doc_id.to_string(),
+            pages,
+            page_lengths,
+            postings,
+            stemmer: STEMMER_VERSION,
+        }
+    }
+
+    /// Whether the index was built by the current stemmer. Terms of older
+    /// ones may not match queries; `current` rebuilds the index then.
+    pub fn is_current(&self) -> bool {
+        self.stemmer == STEMMER_VERSION
+    }
+
+    /// The index rebuilt from its page text if it was saved by an older
+    /// stemmer.
+    pub fn current(self) -> Self {
+        if self.is_current() {
+            self
+        } else {
+            Self::build(&self.doc_id, self.pages)
+        }
+    }
+
+    pub fn page_count(&self) -> usize {
+        self.pages.len()
+    }
+
+    /// Ranked pages matching `query`, best first. Plain terms are optional
+    /// (more matches rank higher); quoted phrases are required.
+    pub fn search(&self, query: &str, limit: usize) -> Vec<IndexHit> {
+        let parts = parse_query(query);
+        if parts.is_empty() || self.pages.is_empty() {
+            return Vec::new();
+        }
+
+        let avg_len = self.page_lengths.iter().sum::<u32>() as f32 / self.pages.len() as f32;
+        let mut scores: HashMap<u32, (f32, Vec<String>, u32)> = HashMap::new();
+        let mut required: Option<HashMap<u32, u32>> = None;
+
+        for part in &parts {
+            match part {
+                QueryPart::Term(term) => {
+                    // Best expansion per page, so a term and its fuzzy
+                    // neighbours do not add up
+                    let mut best: HashMap<u32, (f32, &str, u32)> = HashMap::new();
+                    for (candidate, weight) in self.expand(term) {
+                        for posting in &self.postings[candidate] {
+                            let score = weight
+                                * self.bm25(posting.positions.len(), self.postings[candidate].len(), posting.page, avg_len);
+                            let entry = best.entry(posting.page).or_insert((0.0, candidate, posting.positions[0]));
+                            if score > entry.0 {
+                                *entry = (score, candidate, posting.positions[0]);
+                            }
+                        }
+                    }
+                    for (page, (score, candidate, position)) in best {
+                        let entry = scores.entry(page).or_insert((0.0, Vec::new(), position));
+                        entry.0 += score;
+                        entry.1.push(candidate.to_string());
+                        entry.2 = entry.2.min(position);
+                    }
+                }
+                QueryPart::Phrase(terms) => {
+                    let matches = self.phrase_matches(terms);
+                    let df = matches.len();
+                    let mut found = HashMap::new();
+                    for (page, positions) in matches {
+                        let score = terms.len() as f32 * self.bm25(positions.len(), df, page, avg_len);
+                        let entry = scores.entry(page).or_insert((0.0, Vec::new(), positions[0]));
+                        entry.0 += score;
+                        entry.1.extend(terms.iter().cloned());
+                        found.insert(page, positions[0]);
+                    }
+                    required = Some(match required {
+                        Some(previous) => previous
+                            .into_iter()
+                            .filter(|(page, _)| found.contains_key(page))
+                            .collect(),
+                        None => found,
+                    });
+                }
+            }
+        }
+
+        let mut hits: Vec<IndexHit> = scores
+            .into_iter()
+            .filter(|(_, (score, _, _))| *score > 0.0)
+            .filter_map(|(page, (score, mut terms, position))| {
+                // Snippets of phrase queries show the phrase
+                let position = match &required {
+                    Some(pages) => *pages.get(&page)?,
+                    None => position,
+                };
+                terms.sort();
+                terms.dedup();
+                Some(IndexHit {
+                    page_idx: page as i32,
+                    score,
+                    snippet: self.snippet(page, position),
+                    terms,
+                })
+            })
+            .collect();
+
+        hits.sort_by(|a, b| {
+            b.score
+                .total_cmp(&a.score)
+                .then_with(|| a.page_idx.cmp(&b.page_idx))
+        });
+        hits.truncate(limit);
+        hits
+    }
+
+    fn bm25(&self, tf: usize, df: usize, page: u32, avg_len: f32) -> f32 {
+        let n = self.pages.len() as f32;
+        let idf = (1.0 + (n - df as f32 + 0.5) / (df as f32 + 0.5)).ln();
+        let len = self.page_lengths[page as usize] as f32;
+        let tf = tf as f32;
+        idf * tf * (K1 + 1.0) / (tf + K1 * (1.0 - B + B * len / avg_len.max(1.0)))
+    }
+
+    /// Indexed terms a query term matches, with their score weight: the term
+    /// itself, or vocabulary terms within a small edit distance.
+    fn expand<'a>(&'a self, term: &str) -> Vec<(&'a String, f32)> {
+        if let Some((key, _)) = self.postings.get_key_value(term) {
+            return vec![(key, 1.0)];
+        }
+        let max_distance = match term.chars().count() {
+            0..=3 => return Vec::new(),
+            4..=7 => 1,
+            _ => 2,
+        };
+        self.postings
+            .keys()
+            .filter(|candidate| {
+                candidate.len().abs_diff(term.len()) <= max_distance
+                    && edit_distance(term, candidate) <= max_distance
+            })
+            .map(|candidate| (candidate, FUZZY_WEIGHT))
+            .collect()
+    }
+
+    /// Pages containing the terms consecutively, with the positions where
+    /// the phrase starts.
+    fn phrase_matches(&self, terms: &[String]) -> Vec<(u32, Vec<u32>)> {
+        let Some(lists) = terms
+            .iter()
+            .map(|term| self.postings.get(term))
+            .collect::<Option<Vec<_>>>()
+        else {
+            return Vec::new();
+        };
+
+        lists[0]
+            .iter()
+            .filter_map(|first| {
+                let pages: Vec<&Posting> = lists[1..]
+                    .iter()
+                    .map(|list| list.iter().find(|p| p.page == first.page))
+                    .collect::<Option<_>>()?;
+                let starts: Vec<u32> = first
+                    .positions
+                    .iter()
+                    .copied()
+                    .filter(|&start| {
+                        pages
+                            .iter()
+                            .enumerate()
+                            .all(|(i, p)| p.positions.binary_search(&(start + i as u32 + 1)).is_ok())
+                    })
+                    .collect();
+                (!starts.is_empty()).then_some((first.page, starts))
+            })
+            .collect()
+    }
+
+    /// Text around the token at `position`, whitespace collapsed.
+    fn snippet(&self, page: u32, position: u32) -> String {
+        let text = &self.pages[page as usize];
+        let Some(token) = tokenize(text).into_iter().nth(position as usize) else {
+            return String::new();
+        };
+
+        let before: Vec<(usize, char)> = text[..token.start].char_indices().collect();
+        let start = before
+            .len()
+            .checked_sub(SNIPPET_CONTEXT)
+            .map_or(0, |i| before[i].0);
+        let end = text[token.end..]
+            .char_indices()
+            .nth(SNIPPET_CONTEXT)
+            .map_or(text.len(), |(i, _)| token.end + i);
+
+        let body = text[start..end].split_whitespace().collect::<Vec<_>>().join(" ");
+        let prefix = if start > 0 { "…" } else { "" };
+        let suffix = if end < text.len() { "…" } else { "" };
+        format!("{}{}{}", prefix, body, suffix)
+    }
+}
+
+/// Split text into lowercase, stemmed words.
+fn tokenize(text: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut start = None;
+    for (i, c) in text.char_indices().chain(std::iter::once((text.len(), ' '))) {
+        match (c.is_alphanumeric(), start) {
+            (true, None) => start = Some(i),
+            (false, Some(s)) => {
+                tokens.push(Token {
+                    term: stem(&text[s..i].to_lowercase()),
+                    start: s,
+                    end: i,
+                });
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    tokens
+}
+
+/// Split a query into stemmed terms and "quoted phrases".
+fn parse_query(query: &str) -> Vec<QueryPart> {
+    let mut parts = Vec::new();
+    for (i, chunk) in query.split('"').enumerate() {
+        let terms: Vec<String> = tokenize(chunk).into_iter().map(|t| t.term).collect();
+        // Odd chunks are inside quotes
+        if i % 2 == 1 && terms.len() > 1 {
+            parts.push(QueryPart::Phrase(terms));
+        } else {
+            parts.extend(terms.into_iter().map(QueryPart::Term));
+        }
+    }
+    parts
+}
+
+/// Light English suffix stripping for lowercase words: plurals first, then
+/// "-ing" and "-ed", so "fittings" and "fitting" share a stem. Words with
+/// digits and short words are kept as they are.
+fn stem(word: &str) -> String {
+    if word.len() <= 3 || !word.chars().all(|c| c.is_ascii_lowercase()) {
+        return word.to_string();
+    }
+    let word = strip_plural(word);
+    let has_vowel = |s: &str| s.chars().any(|c| "aeiouy".contains(c));
+
+    for suffix in ["ing", "ed"] {
+        // "speed" and "exceed" are not past tenses
+        if suffix == "ed" && word.ends_with("eed") {
+            continue;
+        }
+        if let Some(base) = word.strip_suffix(suffix) {
+            if base.len() >= 3 && has_vowel(base) {
+                // running -> run, but keep "fall" and "dress"
+                let b = base.as_bytes();
+                let doubled = b[b.len() - 1] == b[b.len() - 2] && !b"lsz".contains(&b[b.len() - 1]);
+                return if doubled {
+                    base[..base.len() - 1].to_string()
+                } else {
+                    base.to_string()
+                };
+            }
+        }
+    }
+    word
+}
+
+/// Singular of a plural: "batteries" -> "battery", "finishes" -> "finish",
+/// "outlets" -> "outlet". Other words are returned unchanged.
+fn strip_plural(word: &str) -> String {
+    if let Some(base) = word.strip_suffix("ies") {
+        return format!("{}y", base);
+    }
+    if word.ends_with("sses") {
+        return word[..word.len() - 2].to_string();
+    }
+    if let Some(base) = word.strip_suffix("es") {
+        if ["sh", "ch", "x", "z"].iter().any(|end| base.ends_with(end)) {
+            return base.to_string();
+        }
+    }
+    if word.ends_with('s') && !word.ends_with("ss") && !word.ends_with("us") && !word.ends_with("is") {
+        return word[..word.len() - 1].to_string();
+    }
+    word.to_string()
+}
+
+/// The `limit` best hits over several documents' ranked hits, e.g. for a
+/// project-wide search. Documents keep their order and hits their ranking;
+/// documents left without hits are dropped. Equal scores favour the earlier
+/// document.
+pub fn top_hits(documents: Vec<(String, Vec<IndexHit>)>, limit: usize) -> Vec<(String, Vec<IndexHit>)> {
+    let mut scores: Vec<(usize, f32)> = documents
+        .iter()
+        .enumerate()
+        .flat_map(|(doc, (_, hits))| hits.iter().map(move |hit| (doc, hit.score)))
+        .collect();
+    scores.sort_by(|a, b| b.1.total_cmp(&a.1));
+    let mut kept = vec![0; documents.len()];
+    for (doc, _) in scores.into_iter().take(limit) {
+        kept[doc] += 1;
+    }
+
+    documents
+        .into_iter()
+        .zip(kept)
+        .filter(|(_, kept)| *kept > 0)
+        .map(|((doc_id, mut hits), kept)| {
+            hits.sort_by(|a, b| b.score.total_cmp(&a.score));
+            hits.truncate(kept);
+            (doc_id, hits)
+        })
+        .collect()
+}
+
+/// Levenshtein distance between two strings.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut previous = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let current = row[j + 1];
+            row[j + 1] = if ca == *cb {
+                previous
+            } else {
+                1 + previous.min(row[j]).min(current)
+            };
+            previous = current;
+        }
+    }
+    row[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> TextIndex {
+        TextIndex::build(
+            "doc",
+            vec![
+                "GENERAL NOTES. All receptacles shall be tamper resistant.".to_string(),
+                "Provide duplex receptacle at each counter. Duplex receptacle mounted at 18 in.".to_string(),
+                "Fire alarm pull stations at exits. Running conduit exposed.".to_string(),
+            ],
+        )
+    }
+
+    #[test]
+    fn test_stem_and_tokenize() {
+        assert_eq!(stem("receptacles"), "receptacle");
+        assert_eq!(stem("batteries"), "battery");
+        assert_eq!(stem("running"), "run");
+        assert_eq!(stem("mounted"), "mount");
+        assert_eq!(stem("glass"), "glass");
+        assert_eq!(stem("wp1s"), "wp1s");
+        assert_eq!(stem("speed"), "speed");
+        assert_eq!(stem("speeds"), "speed");
+        for (singular, plural) in
+            [("ceiling", "ceilings"), ("fitting", "fittings"), ("drawing", "drawings"), ("finish", "finishes")]
+        {
+            assert_eq!(stem(singular), stem(plural), "{} / {}", singular, plural);
+        }
+        assert_eq!(stem("fittings"), "fit");
+        assert_eq!(stem("boxes"), "box");
+        let terms: Vec<String> = tokenize("WP-1, Outlets").into_iter().map(|t| t.term).collect();
+        assert_eq!(terms, vec!["wp", "1", "outlet"]);
+        assert_eq!(edit_distance("recptacle", "receptacle"), 1);
+    }
+
+    #[test]
+    fn test_ranked_terms_and_fuzzy() {
+        let index = sample();
+        let hits = index.search("receptacles", 10);
+        let pages: Vec<i32> = hits.iter().map(|h| h.page_idx).collect();
+        // Page 1 mentions it twice
+        assert_eq!(pages, vec![1, 0]);
+        assert!(hits[0].snippet.contains("duplex receptacle"));
+
+        let fuzzy = index.search("recptacle", 10);
+        assert_eq!(fuzzy.len(), 2);
+        assert!(fuzzy[0].score < hits[0].score);
+
+        assert_eq!(index.search("run", 10)[0].page_idx, 2);
+        assert!(index.search("xyz", 10).is_empty());
+    }
+
+    #[test]
+    fn test_plural_and_singular_find_each_other() {
+        let index = TextIndex::build(
+            "doc",
+            vec![
+                "Acoustic ceilings in corridors.".to_string(),
+                "Plumbing fitting schedule. Wall finish per legend.".to_string(),
+            ],
+        );
+        let pages = |query: &str| -> Vec<i32> { index.search(query, 10).iter().map(|h| h.page_idx).collect() };
+        assert_eq!(pages("ceiling"), vec![0]);
+        assert_eq!(pages("fittings"), vec![1]);
+        assert_eq!(pages("finishes"), vec![1]);
+    }
+
+    #[test]
+    fn test_phrase_required() {
+        let index = sample();
+        let hits = index.search("\"duplex receptacle\" tamper", 10);
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].page_idx, 1);
+        assert!(index.search("\"receptacle duplex\"", 10).is_empty());
+
+        let json = serde_json::to_string(&index).unwrap();
+        let restored: TextIndex = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, index);
+    }
+
+    #[test]
+    fn test_top_hits_limit_whole_project() {
+        let hit = |page_idx: i32, score: f32| IndexHit {
+            page_idx,
+            score,
+            snippet: String::new(),
+            terms: Vec::new(),
+        };
+        let documents = vec![
+            ("specs".to_string(), vec![hit(0, 5.0), hit(3, 1.0)]),
+            ("drawings".to_string(), vec![hit(2, 4.0), hit(1, 3.0), hit(7, 2.0)]),
+            ("empty".to_string(), Vec::new()),
+        ];
+        let top: Vec<(String, Vec<i32>)> = top_hits(documents.clone(), 3)
+            .into_iter()
+            .map(|(doc_id, hits)| (doc_id, hits.iter().map(|h| h.page_idx).collect()))
+            .collect();
+        assert_eq!(top, vec![("specs".to_string(), vec![0]), ("drawings".to_string(), vec![2, 1])]);
+
+        assert_eq!(top_hits(documents.clone(), 10).len(), 2);
+        assert_eq!(top_hits(documents.clone(), 10)[1].1.len(), 3);
+        assert!(top_hits(documents, 0).is_empty());
+    }
+
+    #[test]
+    fn test_old_stemmer_index_is_rebuilt() {
+        let index = TextIndex::build("doc", vec!["Acoustic ceilings in corridors.".to_string()]);
+        let mut old = serde_json::to_value(&index).unwrap();
+        let postings = old["postings"].as_object_mut().unwrap();
+        let ceil = postings.remove("ceil").unwrap();
+        postings.insert("ceiling".to_string(), ceil);
+        old.as_object_mut().unwrap().remove("stemmer");
+
+        let old: TextIndex = serde_json::from_value(old).unwrap();
+        assert!(old.search("ceilings", 10).is_empty());
+        assert!(!old.is_current() && index.is_current());
+        assert_eq!(old.current(), index);
+    }
+}
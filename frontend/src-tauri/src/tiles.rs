@@ -0,0 +1,98 @@
+//! Page Tile Module
+//!
+//! Map-style tile addressing for the `/tile/{id}/{page}/{z}/{x}/{y}` route of
+//! the `protakeoff://` protocol. Each level doubles the render zoom; tiles are
+//! `TILE_SIZE` pixels square, counted from the top-left of the scaled page.
+
+/// Edge length of a tile in pixels
+pub const TILE_SIZE: i32 = 256;
+
+/// Render zoom of level 0 (18 DPI: a whole 36x48 sheet fits in 3x4 tiles)
+const BASE_ZOOM: f32 = 0.25;
+
+/// Deepest level served (zoom 16, i.e. 1152 DPI)
+pub const MAX_LEVEL: u32 = 6;
+
+/// A parsed tile address.
+#[derive(Debug, PartialEq)]
+pub struct TileRequest<'a> {
+    pub doc_id: &'a str,
+    pub page: i32,
+    pub level: u32,
+    pub x: i32,
+    pub y: i32,
+}
+
+impl TileRequest<'_> {
+    /// MuPDF zoom factor for this tile's level.
+    pub fn zoom(&self) -> f32 {
+        BASE_ZOOM * (1u32 << self.level) as f32
+    }
+
+    /// Top-left pixel of the tile within the scaled page.
+    pub fn origin(&self) -> (i32, i32) {
+        (self.x * TILE_SIZE, self.y * TILE_SIZE)
+    }
+}
+
+/// Parse `/tile/{id}/{page}/{z}/{x}/{y}` (`{y}` may end in `.png`).
+///
+/// Returns `None` for malformed paths, negative coordinates or levels above
+/// `MAX_LEVEL`.
+pub fn parse_tile_path(path: &str) -> Option<TileRequest<'_>> {
+    let mut segments = path.strip_prefix("/tile/")?.split('/');
+    let doc_id = segments.next().filter(|id| !id.is_empty())?;
+    let page = segments.next()?.parse::<i32>().ok()?;
+    let level = segments.next()?.parse::<u32>().ok()?;
+    let x = segments.next()?.parse::<i32>().ok()?;
+    let y = segments.next()?.trim_end_matches(".png").parse::<i32>().ok()?;
+    if segments.next().is_some() || page < 0 || level > MAX_LEVEL || x < 0 || y < 0 {
+        return None;
+    }
+
+    // Reject tiles that could not start inside any page without overflowing
+    x.checked_mul(TILE_SIZE)?;
+    y.checked_mul(TILE_SIZE)?;
+
+    Some(TileRequest {
+        doc_id,
+        page,
+        level,
+        x,
+        y,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_tile_path() {
+        let tile = parse_tile_path("/tile/doc-1/3/2/5/7.png").unwrap();
+        assert_eq!(
+            tile,
+            TileRequest {
+                doc_id: "doc-1",
+                page: 3,
+                level: 2,
+                x: 5,
+                y: 7,
+            }
+        );
+        assert_eq!(tile.zoom(), 1.0);
+        assert_eq!(tile.origin(), (1280, 1792));
+        assert!(parse_tile_path("/tile/doc-1/0/0/0/0").is_some());
+    }
+
+    #[test]
+    fn test_parse_tile_path_rejects_bad_input() {
+        assert!(parse_tile_path("/page/doc/0").is_none());
+        assert!(parse_tile_path("/tile/doc/0/0/0").is_none());
+        assert!(parse_tile_path("/tile//0/0/0/0").is_none());
+        assert!(parse_tile_path("/tile/doc/0/0/-1/0").is_none());
+        assert!(parse_tile_path("/tile/doc/0/7/0/0").is_none());
+        assert!(parse_tile_path("/tile/doc/0/0/0/0/extra").is_none());
+        assert!(parse_tile_path("/tile/doc/0/0/99999999/0").is_none());
+    }
+}
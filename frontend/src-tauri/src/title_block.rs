@@ -0,0 +1,493 @@
+//! Title Block Module
+//!
+//! Reads the sheet number (A-101, E2.3) and sheet title off a drawing's title
+//! block so imported pages can be named after their sheets instead of
+//! "Page N". Title blocks sit along the right or bottom edge of a sheet; the
+//! number is usually the largest text there, often under a "SHEET NO." label.
+//! When no title block is found the PDF page label is used, if it is not just
+//! a page number, and `apply_bookmark` fills in what is still missing from
+//! the page's outline bookmark.
+
+use crate::mupdf_wrapper::TextLine;
+use crate::navigation::OutlineEntry;
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Lines whose centre is right of this share of the width are in the title block
+const RIGHT_STRIP: f32 = 0.75;
+
+/// Lines whose centre is below this share of the height are in the title block
+const BOTTOM_STRIP: f32 = 0.8;
+
+/// Longest sheet number accepted, in characters
+const MAX_NUMBER_LEN: usize = 10;
+
+/// Longest line taken as (part of) a sheet title
+const MAX_TITLE_LEN: usize = 60;
+
+/// Lines joined into one multi-line title at most
+const MAX_TITLE_LINES: usize = 3;
+
+const NUMBER_LABELS: &[&str] = &[
+    "SHEET NO",
+    "SHEET NUMBER",
+    "SHEET #",
+    "SHEET:",
+    "DWG NO",
+    "DWG. NO",
+    "DRAWING NO",
+    "DRAWING NUMBER",
+];
+
+const TITLE_LABELS: &[&str] = &["SHEET TITLE", "DRAWING TITLE", "DWG TITLE", "TITLE"];
+
+/// Where a sheet name came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SheetNameSource {
+    TitleBlock,
+    PageLabel,
+    Bookmark,
+}
+
+/// Sheet number and title of one page.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SheetInfo {
+    pub page_idx: i32,
+    pub sheet_number: Option<String>,
+    pub sheet_title: Option<String>,
+    /// Display name for the page list, e.g. "A-101 - FIRST FLOOR PLAN"
+    pub name: Option<String>,
+    pub source: Option<SheetNameSource>,
+}
+
+impl SheetInfo {
+    fn new(
+        page_idx: i32,
+        sheet_number: Option<String>,
+        sheet_title: Option<String>,
+        source: Option<SheetNameSource>,
+    ) -> Self {
+        let name = match (&sheet_number, &sheet_title) {
+            (Some(number), Some(title)) => Some(format!("{} - {}", number, title)),
+            (Some(only), None) | (None, Some(only)) => Some(only.clone()),
+            (None, None) => None,
+        };
+        Self {
+            page_idx,
+            sheet_number,
+            sheet_title,
+            source: source.filter(|_| name.is_some()),
+            name,
+        }
+    }
+}
+
+/// Whether `token` looks like a sheet number: a 1-3 letter discipline prefix,
+/// an optional `-` or `.`, 1-4 digits, an optional `.`/`-` sub-number and an
+/// optional letter suffix (A-101, E2.3, S-1.01, FP-2, A101B).
+pub fn is_sheet_number(token: &str) -> bool {
+    let b = token.as_bytes();
+    if b.len() > MAX_NUMBER_LEN {
+        return false;
+    }
+    let mut i = 0;
+    let letters = |i: &mut usize| {
+        let start = *i;
+        while *i < b.len() && b[*i].is_ascii_uppercase() {
+            *i += 1;
+        }
+        *i - start
+    };
+    let digits = |i: &mut usize| {
+        let start = *i;
+        while *i < b.len() && b[*i].is_ascii_digit() {
+            *i += 1;
+        }
+        *i - start
+    };
+
+    if !(1..=3).contains(&letters(&mut i)) {
+        return false;
+    }
+    if i < b.len() && (b[i] == b'-' || b[i] == b'.') {
+        i += 1;
+    }
+    if !(1..=4).contains(&digits(&mut i)) {
+        return false;
+    }
+    if i + 1 < b.len() && (b[i] == b'.' || b[i] == b'-') && b[i + 1].is_ascii_digit() {
+        i += 1;
+        if digits(&mut i) > 3 {
+            return false;
+        }
+    }
+    if i < b.len() && b[i].is_ascii_uppercase() {
+        i += 1;
+    }
+    i == b.len()
+}
+
+/// Find the sheet number and title of a page from its text lines (page
+/// points, top-left origin) and page size, falling back to the page label.
+pub fn analyse(
+    page_idx: i32,
+    lines: &[TextLine],
+    width: f32,
+    height: f32,
+    page_label: Option<&str>,
+) -> SheetInfo {
+    let in_block: Vec<&TextLine> = lines
+        .iter()
+        .filter(|line| {
+            let (cx, cy) = centre(line);
+            cx >= width * RIGHT_STRIP || cy >= height * BOTTOM_STRIP
+        })
+        .collect();
+
+    let number = find_number(&in_block, width, height);
+    let title = find_title(&in_block, number.as_ref().map(|(line, _)| *line), height);
+
+    if number.is_some() || title.is_some() {
+        return SheetInfo::new(
+            page_idx,
+            number.map(|(_, token)| token),
+            title,
+            Some(SheetNameSource::TitleBlock),
+        );
+    }
+
+    // A purely numeric label is just the page number
+    let label = page_label
+        .map(str::trim)
+        .filter(|label| !label.is_empty() && !label.chars().all(|c| c.is_ascii_digit()));
+    SheetInfo::new(
+        page_idx,
+        label.map(str::to_string),
+        None,
+        Some(SheetNameSource::PageLabel),
+    )
+}
+
+/// Name a page the title block left unnamed from the title of the outline
+/// bookmark pointing at it: the title, and the sheet number when the page
+/// label did not supply one.
+pub fn apply_bookmark(info: SheetInfo, bookmark: Option<&str>) -> SheetInfo {
+    if info.source == Some(SheetNameSource::TitleBlock) {
+        return info;
+    }
+    let (bookmark_number, bookmark_title) = split_bookmark(bookmark);
+    if bookmark_number.is_none() && bookmark_title.is_none() {
+        return info;
+    }
+    let source = if info.sheet_number.is_some() {
+        SheetNameSource::PageLabel
+    } else {
+        SheetNameSource::Bookmark
+    };
+    SheetInfo::new(
+        info.page_idx,
+        info.sheet_number.or(bookmark_number),
+        bookmark_title,
+        Some(source),
+    )
+}
+
+/// Bookmark title naming each page: the shallowest bookmark pointing at it
+/// whose title starts with a sheet number, otherwise the shallowest one
+/// (the first one when several are equally deep). Deeper bookmarks tend to
+/// be details on the sheet, e.g. "Detail 4" under "A-201 Elevations".
+pub fn page_bookmarks(outline: &[OutlineEntry]) -> HashMap<i32, String> {
+    fn walk(entries: &[OutlineEntry], depth: usize, best: &mut HashMap<i32, (bool, usize, String)>) {
+        for entry in entries {
+            let title = entry.title.trim();
+            if let (Some(page), false) = (entry.page, title.is_empty()) {
+                let sheet = split_bookmark(Some(title)).0.is_some();
+                let better = match best.get(&page) {
+                    Some((best_sheet, best_depth, _)) => {
+                        (sheet && !best_sheet) || (sheet == *best_sheet && depth < *best_depth)
+                    }
+                    None => true,
+                };
+                if better {
+                    best.insert(page, (sheet, depth, title.to_string()));
+                }
+            }
+            walk(&entry.children, depth + 1, best);
+        }
+    }
+
+    let mut best = HashMap::new();
+    walk(outline, 0, &mut best);
+    best.into_iter().map(|(page, (_, _, title))| (page, title)).collect()
+}
+
+/// Split a bookmark like "A-101 Floor Plan" into its sheet number and title.
+fn split_bookmark(bookmark: Option<&str>) -> (Option<String>, Option<String>) {
+    let Some(bookmark) = bookmark.map(str::trim).filter(|b| !b.is_empty()) else {
+        return (None, None);
+    };
+    let (first, rest) = bookmark.split_once(char::is_whitespace).unwrap_or((bookmark, ""));
+    let first = first.trim_end_matches(':');
+    if !is_sheet_number(first) {
+        return (None, Some(bookmark.to_string()));
+    }
+    let rest = rest.trim_start_matches(|c: char| c.is_whitespace() || c == '-' || c == ':');
+    (Some(first.to_string()), Some(rest.to_string()).filter(|r| !r.is_empty()))
+}
+
+/// Best sheet number candidate in the title block: large text, standalone on
+/// its line, near a "SHEET NO." label and towards the bottom-right corner.
+fn find_number<'a>(block: &[&'a TextLine], width: f32, height: f32) -> Option<(&'a TextLine, String)> {
+    let labels: Vec<&TextLine> = block
+        .iter()
+        .copied()
+        .filter(|line| has_label(&line.text, NUMBER_LABELS))
+        .collect();
+    let max_font = block.iter().map(|l| l.font_size).fold(0.0, f32::max);
+
+    let mut best: Option<(f32, &TextLine, String)> = None;
+    for &line in block {
+        for raw in line.text.split(|c: char| c.is_whitespace() || c == ':' || c == '#') {
+            let token = raw.trim_matches(|c: char| matches!(c, '.' | ',' | ';' | '(' | ')'));
+            if !is_sheet_number(token) {
+                continue;
+            }
+            let (cx, cy) = centre(line);
+            let mut score = 2.0 * line.font_size / max_font.max(1.0);
+            score += 0.5 * (cx / width + cy / height);
+            if line.text.trim() == token {
+                score += 3.0;
+            }
+            let near_label = labels.iter().any(|label| {
+                std::ptr::eq(*label, line)
+                    || distance(label, line) <= 4.0 * label.font_size.max(line.font_size)
+            });
+            if near_label {
+                score += 4.0;
+            }
+            let better = match &best {
+                Some((best_score, _, _)) => score > *best_score,
+                None => true,
+            };
+            if better {
+                best = Some((score, line, token.to_string()));
+            }
+        }
+    }
+    best.map(|(_, line, token)| (line, token))
+}
+
+/// The title: the lines under a "SHEET TITLE" label, or else the largest
+/// wordy text in the title block, preferring text near the sheet number.
+fn find_title(block: &[&TextLine], number: Option<&TextLine>, height: f32) -> Option<String> {
+    let candidates: Vec<&TextLine> = block
+        .iter()
+        .copied()
+        .filter(|line| !matches!(number, Some(n) if std::ptr::eq(n, *line)) && is_title_text(&line.text))
+        .collect();
+
+    // Text under an explicit title label
+    for label in block.iter().filter(|l| has_label(&l.text, TITLE_LABELS) && !has_label(&l.text, NUMBER_LABELS)) {
+        let below = candidates
+            .iter()
+            .copied()
+            .filter(|line| {
+                line.bbox[1] >= label.bbox[1] + label.font_size * 0.5
+                    && line.bbox[1] - label.bbox[3] <= 3.0 * line.font_size.max(label.font_size)
+                    && line.bbox[0] < label.bbox[2] + label.font_size * 10.0
+                    && line.bbox[2] > label.bbox[0] - label.font_size * 10.0
+            })
+            .min_by(|a, b| a.bbox[1].total_cmp(&b.bbox[1]));
+        if let Some(first) = below {
+            return Some(stack(&candidates, first));
+        }
+    }
+
+    let score = |line: &TextLine| match number {
+        Some(n) => line.font_size * (1.0 - 0.5 * (distance(n, line) / (0.25 * height)).min(1.0)),
+        None => line.font_size,
+    };
+    candidates
+        .iter()
+        .copied()
+        .max_by(|a, b| score(a).total_cmp(&score(b)))
+        .map(|line| stack(&candidates, line))
+}
+
+/// Join `start` with the lines stacked directly above and below it in the
+/// same font size, top to bottom.
+fn stack(candidates: &[&TextLine], start: &TextLine) -> String {
+    let continues = |upper: &TextLine, lower: &TextLine| {
+        let size = upper.font_size.max(lower.font_size);
+        (upper.font_size - lower.font_size).abs() <= 0.15 * size
+            && lower.bbox[1] >= upper.bbox[3] - 0.3 * size
+            && lower.bbox[1] - upper.bbox[3] <= 0.8 * size
+            && lower.bbox[0] < upper.bbox[2]
+            && lower.bbox[2] > upper.bbox[0]
+    };
+
+    let mut top = start;
+    for _ in 1..MAX_TITLE_LINES {
+        match candidates.iter().find(|line| continues(line, top)) {
+            Some(above) => top = above,
+            None => break,
+        }
+    }
+    let mut parts = vec![top.text.trim().to_string()];
+    let mut current = top;
+    while parts.len() < MAX_TITLE_LINES {
+        match candidates.iter().find(|line| continues(current, line)) {
+            Some(below) => {
+                parts.push(below.text.trim().to_string());
+                current = below;
+            }
+            None => break,
+        }
+    }
+    parts.join(" ")
+}
+
+/// Mostly letters, a few words long, and not a label.
+fn is_title_text(text: &str) -> bool {
+    let text = text.trim();
+    let letters = text.chars().filter(|c| c.is_alphabetic()).count();
+    let visible = text.chars().filter(|c| !c.is_whitespace()).count();
+    letters >= 3
+        && letters * 10 >= visible * 6
+        && text.len() <= MAX_TITLE_LEN
+        && !has_label(text, NUMBER_LABELS)
+        && !has_label(text, TITLE_LABELS)
+}
+
+fn has_label(text: &str, labels: &[&str]) -> bool {
+    let upper = text.to_uppercase();
+    labels.iter().any(|label| upper.contains(label))
+}
+
+fn centre(line: &TextLine) -> (f32, f32) {
+    (
+        (line.bbox[0] + line.bbox[2]) / 2.0,
+        (line.bbox[1] + line.bbox[3]) / 2.0,
+    )
+}
+
+/// Gap between two line boxes (0 when they overlap).
+fn distance(a: &TextLine, b: &TextLine) -> f32 {
+    let dx = (a.bbox[0] - b.bbox[2]).max(b.bbox[0] - a.bbox[2]).max(0.0);
+    let dy = (a.bbox[1] - b.bbox[3]).max(b.bbox[1] - a.bbox[3]).max(0.0);
+    dx.hypot(dy)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const W: f32 = 2592.0;
+    const H: f32 = 1728.0;
+
+    fn line(text: &str, x: f32, y: f32, size: f32) -> TextLine {
+        TextLine {
+            bbox: [x, y, x + size * 0.6 * text.len() as f32, y + size],
+            font_size: size,
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_sheet_number_pattern() {
+        for ok in ["A-101", "E2.3", "S-1.01", "FP-2", "A101B", "M1", "A.201"] {
+            assert!(is_sheet_number(ok), "{}", ok);
+        }
+        for bad in ["A", "101", "A-", "ABCD-1", "a-101", "A-12345", "A-1.2345", "NOTE"] {
+            assert!(!is_sheet_number(bad), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_reads_title_block() {
+        let lines = vec![
+            line("GENERAL NOTES", 100.0, 100.0, 14.0),
+            line("SEE DETAIL 3/A-501 FOR FLASHING", 100.0, 130.0, 8.0),
+            line("ACME OFFICE BUILDING", 2300.0, 1300.0, 16.0),
+            line("SHEET TITLE", 2300.0, 1480.0, 6.0),
+            line("FIRST FLOOR", 2300.0, 1490.0, 14.0),
+            line("PLAN", 2300.0, 1506.0, 14.0),
+            line("DATE: 2024-05-01", 2300.0, 1560.0, 6.0),
+            line("SHEET NO.", 2300.0, 1600.0, 6.0),
+            line("A-101", 2300.0, 1610.0, 28.0),
+        ];
+        let info = analyse(0, &lines, W, H, Some("1"));
+        assert_eq!(info.sheet_number.as_deref(), Some("A-101"));
+        assert_eq!(info.sheet_title.as_deref(), Some("FIRST FLOOR PLAN"));
+        assert_eq!(info.name.as_deref(), Some("A-101 - FIRST FLOOR PLAN"));
+        assert_eq!(info.source, Some(SheetNameSource::TitleBlock));
+
+        // Without the title label the largest text near the number wins
+        let unlabelled: Vec<TextLine> = lines.into_iter().filter(|l| l.text != "SHEET TITLE").collect();
+        let info = analyse(0, &unlabelled, W, H, None);
+        assert_eq!(info.sheet_title.as_deref(), Some("FIRST FLOOR PLAN"));
+    }
+
+    #[test]
+    fn test_falls_back_to_page_label() {
+        let lines = vec![line("SPECIFICATIONS 1.01 GENERAL", 100.0, 100.0, 10.0)];
+        let info = analyse(4, &lines, W, H, Some("E-201"));
+        assert_eq!(info.sheet_number.as_deref(), Some("E-201"));
+        assert_eq!(info.source, Some(SheetNameSource::PageLabel));
+
+        let info = analyse(4, &lines, W, H, Some("5"));
+        assert_eq!(info.name, None);
+        assert_eq!(info.source, None);
+    }
+
+    #[test]
+    fn test_falls_back_to_bookmark() {
+        let lines = vec![line("SPECIFICATIONS 1.01 GENERAL", 100.0, 100.0, 10.0)];
+        let labelled = analyse(4, &lines, W, H, Some("E-201"));
+        let info = apply_bookmark(labelled.clone(), Some("E-201 Lighting Plan"));
+        assert_eq!(info.name.as_deref(), Some("E-201 - Lighting Plan"));
+        assert_eq!(info.source, Some(SheetNameSource::PageLabel));
+        assert_eq!(apply_bookmark(labelled.clone(), None), labelled);
+
+        let unnamed = analyse(4, &lines, W, H, Some("5"));
+        let info = apply_bookmark(unnamed.clone(), Some("Specifications"));
+        assert_eq!(info.name.as_deref(), Some("Specifications"));
+        assert_eq!(info.source, Some(SheetNameSource::Bookmark));
+        let info = apply_bookmark(unnamed, Some("A-101: Floor Plan"));
+        assert_eq!(info.sheet_number.as_deref(), Some("A-101"));
+        assert_eq!(info.sheet_title.as_deref(), Some("Floor Plan"));
+
+        // A title block always wins
+        let block = vec![line("SHEET NO.", 2300.0, 1600.0, 6.0), line("A-101", 2300.0, 1610.0, 28.0)];
+        let info = analyse(0, &block, W, H, None);
+        assert_eq!(apply_bookmark(info.clone(), Some("Plans")), info);
+    }
+
+    #[test]
+    fn test_page_bookmarks_prefers_sheet_bookmarks() {
+        let entry = |title: &str, page: Option<i32>| OutlineEntry {
+            title: title.to_string(),
+            page,
+            x: 0.0,
+            y: 0.0,
+            uri: None,
+            open: false,
+            children: Vec::new(),
+        };
+        let outline = crate::navigation::build_tree(vec![
+            (0, entry("Architectural", Some(0))),
+            (1, entry("A-101 Floor Plan", Some(0))),
+            (1, entry("A-201 Elevations", Some(1))),
+            (3, entry("Detail 4", Some(1))),
+            (0, entry("Electrical", Some(2))),
+            (1, entry("Lighting", Some(2))),
+            (0, entry("Website", None)),
+        ]);
+        let pages = page_bookmarks(&outline);
+        assert_eq!(pages[&0], "A-101 Floor Plan");
+        assert_eq!(pages[&1], "A-201 Elevations");
+        assert_eq!(pages[&2], "Electrical");
+        assert_eq!(pages.len(), 3);
+    }
+}
@@ -0,0 +1,86 @@
+//! Viewport Buffer Module
+//!
+//! Serializes page linework into the `ViewportResponse` FlatBuffer defined in
+//! `schemas/schema.fbs`. The wasm crate reads the same bytes directly to build
+//! its snapping quadtree, so large sheets never go through JSON.
+
+use crate::schema_generated::pro_takeoff::{
+    finish_viewport_response_buffer, Bezier, Point, Segment, ViewportResponse, ViewportResponseArgs,
+};
+use crate::spatial::{VectorGeometry, VectorObject};
+use flatbuffers::FlatBufferBuilder;
+
+fn point(p: [f64; 2]) -> Point {
+    Point::new(p[0] as f32, p[1] as f32)
+}
+
+/// Encode indexed vector objects of a page into a finished `ViewportResponse`.
+pub fn encode_viewport<'a>(
+    page: i32,
+    objects: impl IntoIterator<Item = &'a VectorObject>,
+) -> Vec<u8> {
+    let mut segments = Vec::new();
+    let mut curves = Vec::new();
+    for obj in objects {
+        match obj.geom {
+            VectorGeometry::Line(a, b) => segments.push(Segment::new(&point(a), &point(b))),
+            VectorGeometry::Curve([p0, p1, p2, p3]) => {
+                curves.push(Bezier::new(&point(p0), &point(p1), &point(p2), &point(p3)))
+            }
+        }
+    }
+
+    let mut fbb = FlatBufferBuilder::with_capacity(
+        64 + segments.len() * std::mem::size_of::<Segment>()
+            + curves.len() * std::mem::size_of::<Bezier>(),
+    );
+    let segments = fbb.create_vector(&segments);
+    let curves = fbb.create_vector(&curves);
+    let root = ViewportResponse::create(
+        &mut fbb,
+        &ViewportResponseArgs {
+            entities: None,
+            page,
+            segments: Some(segments),
+            curves: Some(curves),
+        },
+    );
+    finish_viewport_response_buffer(&mut fbb, root);
+    fbb.finished_data().to_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema_generated::pro_takeoff::root_as_viewport_response;
+
+    #[test]
+    fn test_encode_viewport_roundtrip() {
+        let objects = vec![
+            VectorObject {
+                id: 0,
+                geom: VectorGeometry::Line([0.0, 0.0], [150.0, 75.0]),
+            },
+            VectorObject {
+                id: 1,
+                geom: VectorGeometry::Curve([[0.0, 0.0], [0.0, 10.0], [10.0, 10.0], [10.0, 0.0]]),
+            },
+        ];
+        let bytes = encode_viewport(4, &objects);
+
+        let viewport = root_as_viewport_response(&bytes).unwrap();
+        assert_eq!(viewport.page(), 4);
+        assert!(viewport.entities().is_none());
+
+        let segments = viewport.segments().unwrap();
+        assert_eq!(segments.len(), 1);
+        assert_eq!(
+            (segments.get(0).b().x(), segments.get(0).b().y()),
+            (150.0, 75.0)
+        );
+
+        let curves = viewport.curves().unwrap();
+        assert_eq!(curves.len(), 1);
+        assert_eq!(curves.get(0).p2().x(), 10.0);
+    }
+}
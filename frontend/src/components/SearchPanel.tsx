@@ -1,10 +1,11 @@
 import React, { useState, useCallback, useRef, useEffect } from 'react';
-import { Search, X, ChevronUp, ChevronDown, Loader2, File, Files, BrainCircuit } from 'lucide-react';
+import { Search, X, ChevronUp, ChevronDown, Loader2, File, Files, BrainCircuit, CaseSensitive, WholeWord, Regex, Hash } from 'lucide-react';
 import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
 import { Button } from '@/components/ui/button';
 import { Input } from '@/components/ui/input';
 import { useProjectStore } from '@/stores/useProjectStore';
-import type { PageSearchResult, SearchHitQuad, IndexSearchResult } from '@/types/search';
+import type { PageSearchResult, SearchHitQuad, IndexHit, IndexSearchResult, SearchOptions, SearchPageEvent, SearchCounts } from '@/types/search';
 import { cn } from '@/lib/utils';
 import { Tooltip, TooltipContent, TooltipTrigger } from "@/components/ui/tooltip";
 import { toast } from "sonner";
@@ -18,16 +19,27 @@ export const SearchPanel: React.FC<SearchPanelProps> = ({ isOpen, onClose }) =>
     const [query, setQuery] = useState('');
     const [scope, setScope] = useState<'page' | 'document'>('document');
     const [useSmartSearch, setUseSmartSearch] = useState(false);
+    const [options, setOptions] = useState<SearchOptions>({ case_sensitive: false, whole_word: false, regex: false });
 
     // Standard results
     const [results, setResults] = useState<PageSearchResult[]>([]);
     // Smart results
-    const [smartResults, setSmartResults] = useState<string[]>([]);
+    const [smartResults, setSmartResults] = useState<IndexHit[]>([]);
 
     const [currentIndex, setCurrentIndex] = useState(0);
     const [isSearching, setIsSearching] = useState(false);
     const [totalHits, setTotalHits] = useState(0);
     const inputRef = useRef<HTMLInputElement>(null);
+    // Id of the running document search, so it can be cancelled when superseded
+    const activeSearchRef = useRef<string | null>(null);
+    const searchCounterRef = useRef(0);
+
+    const cancelActiveSearch = useCallback(() => {
+        if (activeSearchRef.current) {
+            invoke('cancel_search', { searchId: activeSearchRef.current }).catch(() => { });
+            activeSearchRef.current = null;
+        }
+    }, []);
 
     const {
         currentProject,
@@ -35,6 +47,8 @@ export const SearchPanel: React.FC<SearchPanelProps> = ({ isOpen, onClose }) =>
         setCurrentPageId,
         setSearchHighlights,
         clearSearchHighlights,
+        toolDefaults,
+        addMarkups,
     } = useProjectStore();
 
     // Focus input when panel opens
@@ -47,13 +61,14 @@ export const SearchPanel: React.FC<SearchPanelProps> = ({ isOpen, onClose }) =>
     // Clear highlights when closing
     useEffect(() => {
         if (!isOpen) {
+            cancelActiveSearch();
             clearSearchHighlights();
             setQuery('');
             setResults([]);
             setSmartResults([]);
             setTotalHits(0);
         }
-    }, [isOpen, clearSearchHighlights]);
+    }, [isOpen, clearSearchHighlights, cancelActiveSearch]);
 
     const calculateTotalHits = (searchResults: PageSearchResult[]) => {
         return searchResults.reduce((sum, r) => sum + r.hits.length, 0);
@@ -61,6 +76,7 @@ export const SearchPanel: React.FC<SearchPanelProps> = ({ isOpen, onClose }) =>
 
     const handleSearch = useCallback(async () => {
         const pdf = currentProject?.pdfs?.[0];
+        cancelActiveSearch();
         if (!query.trim() || !pdf) {
             setResults([]);
             setSmartResults([]);
@@ -77,8 +93,8 @@ export const SearchPanel: React.FC<SearchPanelProps> = ({ isOpen, onClose }) =>
                     docId: pdf.id,
                     query: query.trim()
                 });
-                setSmartResults(result.matches);
-                setTotalHits(result.matches.length);
+                setSmartResults(result.hits);
+                setTotalHits(result.hits.length);
                 setResults([]); // Clear standard results
                 clearSearchHighlights();
             } else {
@@ -87,10 +103,31 @@ export const SearchPanel: React.FC<SearchPanelProps> = ({ isOpen, onClose }) =>
                 let searchResults: PageSearchResult[] = [];
 
                 if (scope === 'document') {
-                    searchResults = await invoke<PageSearchResult[]>('search_document', {
-                        id: pdf.id,
-                        query: query.trim(),
+                    // Show hits as pages finish instead of waiting for the whole document
+                    const searchId = `search-panel-${++searchCounterRef.current}`;
+                    activeSearchRef.current = searchId;
+                    const streamed: PageSearchResult[] = [];
+                    const unlisten = await listen<SearchPageEvent>('search-page', (event) => {
+                        const { search_id, page_idx, hits } = event.payload;
+                        if (search_id !== searchId || hits.length === 0) return;
+                        streamed.push({ page_idx, hits });
+                        streamed.sort((a, b) => a.page_idx - b.page_idx);
+                        setResults([...streamed]);
+                        setTotalHits(calculateTotalHits(streamed));
                     });
+                    try {
+                        searchResults = await invoke<PageSearchResult[]>('search_document', {
+                            id: pdf.id,
+                            query: query.trim(),
+                            options,
+                            searchId,
+                        });
+                    } finally {
+                        unlisten();
+                    }
+                    // A newer search cancelled this one
+                    if (activeSearchRef.current !== searchId) return;
+                    activeSearchRef.current = null;
                 } else {
                     if (currentPageId) {
                         const parts = currentPageId.split(':');
@@ -101,6 +138,7 @@ export const SearchPanel: React.FC<SearchPanelProps> = ({ isOpen, onClose }) =>
                                     id: pdf.id,
                                     pageIdx: pageIdx,
                                     query: query.trim(),
+                                    options,
                                 });
                                 if (hits.length > 0) {
                                     searchResults = [{ page_idx: pageIdx, hits }];
@@ -129,14 +167,16 @@ export const SearchPanel: React.FC<SearchPanelProps> = ({ isOpen, onClose }) =>
 
         } catch (error) {
             console.error('Search failed:', error);
+            activeSearchRef.current = null;
             setResults([]);
             setSmartResults([]);
             setTotalHits(0);
             clearSearchHighlights();
         } finally {
-            setIsSearching(false);
+            // Still searching if a newer search took over
+            if (!activeSearchRef.current) setIsSearching(false);
         }
-    }, [query, scope, useSmartSearch, currentProject, currentPageId, clearSearchHighlights]);
+    }, [query, scope, useSmartSearch, options, currentProject, currentPageId, clearSearchHighlights, cancelActiveSearch]);
 
     // Debounce Search
     useEffect(() => {
@@ -153,7 +193,7 @@ export const SearchPanel: React.FC<SearchPanelProps> = ({ isOpen, onClose }) =>
         }, 400);
 
         return () => clearTimeout(timer);
-    }, [query, scope, useSmartSearch]);
+    }, [query, scope, useSmartSearch, options]);
 
     const navigateToResult = useCallback((searchResults: PageSearchResult[], globalIndex: number) => {
         if (searchResults.length === 0) return;
@@ -215,6 +255,7 @@ export const SearchPanel: React.FC<SearchPanelProps> = ({ isOpen, onClose }) =>
     }, [handleSearch, goNext, goPrev, onClose, totalHits, results.length, smartResults.length]);
 
     const handleClose = useCallback(() => {
+        cancelActiveSearch();
         setQuery('');
         setResults([]);
         setSmartResults([]);
@@ -222,10 +263,60 @@ export const SearchPanel: React.FC<SearchPanelProps> = ({ isOpen, onClose }) =>
         setCurrentIndex(0);
         clearSearchHighlights();
         onClose();
-    }, [onClose, clearSearchHighlights]);
+    }, [onClose, clearSearchHighlights, cancelActiveSearch]);
+
+    // Turn every hit in the document into count markups (one per page; per tag for regex searches)
+    const handleCountAll = useCallback(async () => {
+        const pdf = currentProject?.pdfs?.[0];
+        if (!pdf || !query.trim()) return;
+        try {
+            const { markups, failed_pages } = await invoke<SearchCounts>('search_to_count_markups', {
+                id: pdf.id,
+                query: query.trim(),
+                options,
+                properties: toolDefaults.count,
+                splitTerms: options.regex,
+            });
+            const points = Object.values(markups).flat().reduce((sum, m) => sum + m.paths[0].length, 0);
+            if (points === 0) {
+                toast.error('No hits to count');
+                return;
+            }
+            addMarkups(markups);
+            toast.success(`Counted ${points} hits on ${Object.keys(markups).length} pages`);
+            if (failed_pages.length > 0) {
+                toast.error(`${failed_pages.length} pages could not be searched and were not counted`);
+            }
+        } catch (e) {
+            console.error('Count from search failed', e);
+            toast.error('Could not create counts from search');
+        }
+    }, [currentProject, query, options, toolDefaults, addMarkups]);
 
     const handleSmartResultClick = async (idx: number) => {
         setCurrentIndex(idx);
+
+        // Index hits know their page: go there and highlight the query on it
+        const hit = smartResults[idx];
+        const pdf = currentProject?.pdfs?.[0];
+        if (hit && pdf) {
+            setCurrentPageId(`${pdf.id}:${hit.page_idx}`);
+            try {
+                const hits = await invoke<SearchHitQuad[]>('search_page', {
+                    id: pdf.id,
+                    pageIdx: hit.page_idx,
+                    query: query.trim(),
+                });
+                if (hits.length > 0) {
+                    setSearchHighlights(hits);
+                } else {
+                    clearSearchHighlights();
+                }
+            } catch (e) {
+                console.error("Smart navigation failed", e);
+            }
+            return;
+        }
         // Strip validation formatting or HTML if any (simple approach: use raw query if match is snippet)
         // Actually, match is the text snippet. We can try searching for it.
         // Clean HTML tags from match string
@@ -343,6 +434,30 @@ export const SearchPanel: React.FC<SearchPanelProps> = ({ isOpen, onClose }) =>
                     className="h-7 w-48 border-0 bg-transparent focus-visible:ring-0 px-1 text-sm placeholder:text-muted-foreground/50"
                 />
 
+                {!useSmartSearch && (
+                    <div className="flex gap-0.5">
+                        {([
+                            ['case_sensitive', CaseSensitive, 'Match Case'],
+                            ['whole_word', WholeWord, 'Whole Word'],
+                            ['regex', Regex, 'Regular Expression'],
+                        ] as const).map(([key, Icon, label]) => (
+                            <Tooltip key={key}>
+                                <TooltipTrigger asChild>
+                                    <Button
+                                        variant={options[key] ? 'secondary' : 'ghost'}
+                                        size="icon"
+                                        className="h-6 w-6 rounded-sm"
+                                        onClick={() => setOptions(prev => ({ ...prev, [key]: !prev[key] }))}
+                                    >
+                                        <Icon className="h-3 w-3" />
+                                    </Button>
+                                </TooltipTrigger>
+                                <TooltipContent>{label}</TooltipContent>
+                            </Tooltip>
+                        ))}
+                    </div>
+                )}
+
                 {isSearching ? (
                     <Loader2 className="h-4 w-4 animate-spin text-muted-foreground" />
                 ) : (
@@ -369,6 +484,22 @@ export const SearchPanel: React.FC<SearchPanelProps> = ({ isOpen, onClose }) =>
                     </span>
                 )}
 
+                {!useSmartSearch && totalHits > 0 && (
+                    <Tooltip>
+                        <TooltipTrigger asChild>
+                            <Button
+                                variant="ghost"
+                                size="icon"
+                                className="h-6 w-6"
+                                onClick={handleCountAll}
+                            >
+                                <Hash className="h-3 w-3" />
+                            </Button>
+                        </TooltipTrigger>
+                        <TooltipContent>Count All Hits</TooltipContent>
+                    </Tooltip>
+                )}
+
                 <div className="flex gap-0.5 border-l border-border pl-1 ml-1">
                     <Button
                         variant="ghost"
@@ -403,7 +534,7 @@ export const SearchPanel: React.FC<SearchPanelProps> = ({ isOpen, onClose }) =>
             {/* Smart Results Dropdown */}
             {useSmartSearch && smartResults.length > 0 && (
                 <div className="absolute top-full left-0 mt-1 bg-card border border-border rounded-lg shadow-lg max-h-[300px] overflow-y-auto w-full p-2 space-y-1 z-50">
-                    {smartResults.map((match, idx) => (
+                    {smartResults.map((hit, idx) => (
                         <div
                             key={idx}
                             className={cn(
@@ -412,9 +543,9 @@ export const SearchPanel: React.FC<SearchPanelProps> = ({ isOpen, onClose }) =>
                             )}
                             onClick={() => handleSmartResultClick(idx)}
                         >
-                            <span className="font-mono text-muted-foreground mr-2">#{idx + 1}</span>
+                            <span className="font-mono text-muted-foreground mr-2">p.{hit.page_idx + 1}</span>
                             <span dangerouslySetInnerHTML={{
-                                __html: match.replace(
+                                __html: hit.snippet.replace(
                                     new RegExp(`(${query})`, 'gi'),
                                     '<span class="bg-yellow-200/40 text-foreground font-semibold">$1</span>'
                                 )
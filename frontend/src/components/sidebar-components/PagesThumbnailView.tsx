@@ -75,7 +75,6 @@ export const PagesThumbnailView: React.FC<PagesThumbnailViewProps> = ({
                                 {Array.from({ length: pdf.pageCount || 0 }, (_, i) => {
                                     const pageId = `${pdf.id}:${i}`;
                                     const isActive = currentPageId === pageId;
-                                    const thumbnail = pdf.thumbnails?.[i];
                                     const pageName = currentProject.pageMetadata?.[pageId]?.name || `Page ${i + 1}`;
 
                                     return (
@@ -90,7 +89,7 @@ export const PagesThumbnailView: React.FC<PagesThumbnailViewProps> = ({
                                                     onClick={() => setCurrentPageId(pageId)}
                                                 >
                                                     <img
-                                                        src={thumbnail ? `data:image/png;base64,${thumbnail}` : buildProtocolUrl(`/page/${pdf.id}/${i}.png?zoom=0.15`)}
+                                                        src={buildProtocolUrl(`/page/${pdf.id}/${i}.png?zoom=0.15`)}
                                                         alt={pageName}
                                                         className="absolute inset-0 w-full h-full object-contain"
                                                         decoding="async"
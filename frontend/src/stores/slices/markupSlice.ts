@@ -26,6 +26,7 @@ export interface MarkupSlice {
 
     // Actions
     addMarkup: (markup: Markup) => void;
+    addMarkups: (markupsByPage: Record<string, Markup[]>) => void;
     updateMarkup: (id: string, updates: Partial<Markup> | Partial<Markup['properties']>) => void;
     deleteMarkup: (id: string) => void;
     toggleMarkupVisibility: (id: string) => void;
@@ -107,6 +108,28 @@ export const createMarkupSlice: StateCreator<
         return { currentProject: newProject, toolDefaults: newToolDefaults };
     }),
 
+    // Bulk add (e.g. count markups built from search hits) as one undo step
+    addMarkups: (markupsByPage) => set((state) => {
+        if (!state.currentProject) return {};
+        const newMarkupsMap = { ...state.currentProject.markups };
+        for (const [pageId, markups] of Object.entries(markupsByPage)) {
+            if (markups.length === 0) continue;
+            newMarkupsMap[pageId] = [...(newMarkupsMap[pageId] || []), ...markups];
+            markups.forEach(m => markupManager.markAdded(m.id));
+        }
+
+        const newProject: Project = {
+            ...state.currentProject,
+            updatedAt: new Date().toISOString(),
+            markups: newMarkupsMap
+        };
+
+        historyManager.push(state.currentProject, newProject, 'addMarkups');
+        persistenceManager.queueSave(newProject);
+
+        return { currentProject: newProject };
+    }),
+
     updateMarkup: (id, updates) => set((state) => {
         if (!state.currentProject) return {};
 
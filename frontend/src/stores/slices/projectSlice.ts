@@ -1,6 +1,7 @@
 
 import { type StateCreator } from 'zustand';
 import { type ProjectState, type Project, type PdfFile } from '../../types/store';
+import type { SheetInfo } from '../../types/page';
 import { persistenceManager } from '../../utils/PersistenceManager';
 import { serializeProject, deserializeProject } from '../../utils/projectFileUtils';
 import { save, open } from '@tauri-apps/plugin-dialog';
@@ -47,6 +48,16 @@ const hydrateBackend = async (project: Project, updatePdf: (id: string, updates:
     }
 };
 
+// Helper to release backend documents (MuPDF handles, spatial indexes, extracted
+// temp files) before another project's PDFs are opened.
+const releaseBackend = async () => {
+    try {
+        await invoke('close_all_documents');
+    } catch (e) {
+        console.error('[Store] Failed to close backend documents:', e);
+    }
+};
+
 export interface ProjectSlice {
     // State
     currentProject: Project | null;
@@ -72,6 +83,7 @@ export interface ProjectSlice {
     renamePdf: (pdfId: string, name: string) => void;
     updatePdf: (pdfId: string, updates: Partial<PdfFile>) => void; // General update action
     renamePage: (pageId: string, name: string) => void;
+    applySheetNames: (pdfId: string, sheets: SheetInfo[]) => void;
     refreshProjects: () => void;
     saveProjectToFile: () => Promise<boolean>;
     saveProjectAs: () => Promise<boolean>;
@@ -105,7 +117,7 @@ export const createProjectSlice: StateCreator<
         let initialPageId = null;
         if (project) {
             // Re-hydrate backend state asynchronously
-            hydrateBackend(project, get().updatePdf);
+            releaseBackend().then(() => hydrateBackend(project, get().updatePdf));
 
             // Always default to first page of the project (0-index)
             if (project.pdfs && project.pdfs.length > 0) {
@@ -165,6 +177,7 @@ export const createProjectSlice: StateCreator<
             if (project) {
                 historyManager.clear();
                 // Re-hydrate backend state
+                await releaseBackend();
                 await hydrateBackend(project, get().updatePdf);
 
                 set({
@@ -191,6 +204,7 @@ export const createProjectSlice: StateCreator<
         await persistenceManager.deleteProject(id);
         const projects = get().projects.filter(p => p.id !== id);
         if (get().currentProject?.id === id) {
+            await releaseBackend();
             set({ currentProject: null, projects });
         } else {
             set({ projects });
@@ -279,33 +293,9 @@ export const createProjectSlice: StateCreator<
                     fileSize: fileSize,
 
                     path: filePath, // Persist path
-                    thumbnails: [], // Will be populated below
                     ingestionStatus: 'processing' // Start as processing
                 });
 
-                // Generate thumbnails for the newly added PDF
-                const thumbnails: string[] = [];
-                const CHUNK_SIZE = 5;
-                for (let i = 0; i < pageCount; i += CHUNK_SIZE) {
-                    const chunk = Array.from(
-                        { length: Math.min(CHUNK_SIZE, pageCount - i) },
-                        (_, j) => i + j
-                    );
-
-                    const chunkThumbs = await Promise.all(
-                        chunk.map(pageNum =>
-                            invoke<string>('generate_page_thumbnail', {
-                                id: fileId,
-                                pageNumber: pageNum
-                            })
-                        )
-                    );
-                    thumbnails.push(...chunkThumbs);
-                }
-
-                // Update the last added PDF with its thumbnails
-                newPdfs[newPdfs.length - 1].thumbnails = thumbnails;
-
                 // Fire and forget ingestion
                 // We do this AFTER adding the PDF to state so the UI shows it immediately
                 invoke('ingest_file', { id: fileId, path: filePath })
@@ -319,9 +309,12 @@ export const createProjectSlice: StateCreator<
                     });
             }
 
+            // Start from the latest project: updates that landed while files
+            // were opening (e.g. ingestion status) must not be overwritten
+            const latestProject = get().currentProject ?? currentProject;
             const updatedProject = {
-                ...currentProject,
-                pdfs: [...currentProject.pdfs, ...newPdfs],
+                ...latestProject,
+                pdfs: [...latestProject.pdfs, ...newPdfs],
                 updatedAt: new Date().toISOString()
             };
 
@@ -332,6 +325,14 @@ export const createProjectSlice: StateCreator<
                 currentPageId: (!get().currentPageId && newPdfs.length > 0) ? `${newPdfs[0].id}:0` : get().currentPageId
             });
 
+            // Name pages after their title block sheet numbers, once the PDFs
+            // are in the project the names are written to
+            for (const pdf of newPdfs) {
+                invoke<SheetInfo[]>('extract_sheet_names', { id: pdf.id })
+                    .then((sheets) => get().applySheetNames(pdf.id, sheets))
+                    .catch((err) => console.error(`[SheetNames] Failed for ${pdf.name}`, err));
+            }
+
         } catch (e) {
             console.error('Upload failed', e);
             set({ isUploadingPdfs: false });
@@ -389,6 +390,27 @@ export const createProjectSlice: StateCreator<
         return { currentProject: newProject };
     }),
 
+    applySheetNames: (pdfId, sheets) => set((state) => {
+        if (!state.currentProject) return {};
+        const newMeta = { ...state.currentProject.pageMetadata };
+        let named = 0;
+        for (const sheet of sheets) {
+            const pageId = `${pdfId}:${sheet.page_idx}`;
+            // Never overwrite a name the user chose
+            if (!sheet.name || newMeta[pageId]?.name) continue;
+            newMeta[pageId] = { ...newMeta[pageId], name: sheet.name };
+            named++;
+        }
+        if (named === 0) return {};
+        const newProject = {
+            ...state.currentProject,
+            pageMetadata: newMeta,
+            updatedAt: new Date().toISOString()
+        };
+        persistenceManager.queueSave(newProject);
+        return { currentProject: newProject };
+    }),
+
     refreshProjects: async () => {
         const index = await persistenceManager.loadAll();
         // Index items are already projects (metadata subset) or we can just map them
@@ -470,6 +492,8 @@ export const createProjectSlice: StateCreator<
 
             if (path && typeof path === 'string') {
                 set({ isLoadingProject: true });
+                // Close the previous project first: loading extracts new temp files
+                await releaseBackend();
                 const content = await invoke<string>('load_project_file', { path });
                 const project = await deserializeProject(content); // Utils to implement
 
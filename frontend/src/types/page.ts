@@ -0,0 +1,51 @@
+/** Page geometry returned by the `get_page_info` command */
+export interface PageInfo {
+    page_idx: number;
+    /** [x0, y0, x1, y1] in PDF user space (bottom-left origin, unrotated) */
+    media_box: [number, number, number, number];
+    /** Crop box clipped to the media box */
+    crop_box: [number, number, number, number];
+    /** Clockwise display rotation: 0, 90, 180 or 270 */
+    rotation: number;
+    /** Size of one user-space unit in 1/72 inch (1 unless the sheet is oversized) */
+    user_unit: number;
+    /** Displayed size in points, UserUnit applied (multiply by the render zoom for pixels) */
+    width: number;
+    height: number;
+    /** Page label, e.g. "A-101", when the document defines one */
+    label: string | null;
+}
+
+/** Sheet number and title of a page, from the `extract_sheet_names` command */
+export interface SheetInfo {
+    page_idx: number;
+    sheet_number: string | null;
+    sheet_title: string | null;
+    /** Display name, e.g. "A-101 - FIRST FLOOR PLAN" */
+    name: string | null;
+    source: 'title_block' | 'page_label' | 'bookmark' | null;
+}
+
+/** One bookmark of the `get_outline` tree; positions in frontend coordinates */
+export interface OutlineEntry {
+    title: string;
+    /** Target page, null for bookmarks without one */
+    page: number | null;
+    x: number;
+    y: number;
+    uri: string | null;
+    /** Expanded by default */
+    open: boolean;
+    children: OutlineEntry[];
+}
+
+/** Link annotation from `get_page_links`; areas in frontend coordinates */
+export interface PageLink {
+    /** [x0, y0, x1, y1] of the clickable area */
+    bbox: [number, number, number, number];
+    /** Target page of internal links */
+    target_page: number | null;
+    target_x: number;
+    target_y: number;
+    uri: string | null;
+}
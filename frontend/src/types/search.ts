@@ -1,3 +1,5 @@
+import type { Markup } from './store';
+
 /** A quad representing a hit's bounding area on the page */
 export interface SearchHitQuad {
     ul: [number, number];
@@ -6,14 +8,117 @@ export interface SearchHitQuad {
     ll: [number, number];
 }
 
+/** Matching options for `search_page` / `search_document` (all off = MuPDF substring search) */
+export interface SearchOptions {
+    case_sensitive: boolean;
+    whole_word: boolean;
+    /** Treat the query as a regular expression, e.g. `D\d{3}` */
+    regex: boolean;
+}
+
 /** Search hits for a single page */
 export interface PageSearchResult {
     page_idx: number;
     hits: SearchHitQuad[];
 }
 
+/** Payload of the `search-page` event streamed by `search_document` and `search_project` */
+export interface SearchPageEvent {
+    search_id: string;
+    doc_id: string;
+    page_idx: number;
+    hits: SearchHitQuad[];
+    /** Pages searched so far, over every document of a project search */
+    pages_searched: number;
+    /** Pages to search, over every document of a project search */
+    page_count: number;
+}
+
+/** Payload of the `search-done` event */
+export interface SearchDoneEvent {
+    search_id: string;
+    /** null for project-wide searches */
+    doc_id: string | null;
+    total_hits: number;
+    cancelled: boolean;
+}
+
+/** A ranked page from the full-text index */
+export interface IndexHit {
+    page_idx: number;
+    score: number;
+    snippet: string;
+    /** Indexed (stemmed / fuzzy-matched) terms that matched */
+    terms: string[];
+}
+
 /** Result from smart index search (Kreuzberg) */
 export interface IndexSearchResult {
     doc_id: string;
     matches: string[]; // Text snippets
+    hits: IndexHit[]; // Ranked, best first
+}
+
+/** A word of structured text; boxes use the same coordinates as search hits */
+export interface TextWord {
+    bbox: [number, number, number, number];
+    text: string;
+    font_name: string;
+    font_size: number;
+}
+
+export interface StructuredLine {
+    bbox: [number, number, number, number];
+    text: string;
+    /** Writing direction in degrees clockwise from horizontal */
+    rotation: number;
+    words: TextWord[];
+}
+
+export interface TextBlock {
+    bbox: [number, number, number, number];
+    lines: StructuredLine[];
+}
+
+/** Result of the `extract_structured_text` command */
+export interface StructuredPage {
+    page_idx: number;
+    blocks: TextBlock[];
+}
+
+export interface PageTally {
+    page_idx: number;
+    count: number;
+}
+
+/** Result of `search_document` with `tally: true`: hit counts per page and per matched string */
+export interface SearchTally {
+    total: number;
+    pages: PageTally[];
+    /** Distinct matched strings (e.g. WP-1, WP-2) in natural order */
+    terms: {
+        text: string;
+        count: number;
+        pages: PageTally[];
+    }[];
+    /** Pages that could not be searched, missing from the counts */
+    failed_pages: number[];
+}
+
+/** Count markups made by `search_to_count_markups` */
+export interface SearchCounts {
+    /** Count markups keyed by page id (`{docId}:{pageIdx}`) */
+    markups: Record<string, Markup[]>;
+    /** Pages that could not be searched, so have no counts */
+    failed_pages: number[];
+}
+
+/** One document's hits from `search_project` */
+export interface DocumentSearchResult {
+    doc_id: string;
+    total_hits: number;
+    /** Hit quads per page (text search) */
+    pages: PageSearchResult[];
+    /** Ranked pages with snippets (index search, `useIndex: true`) */
+    index_hits: IndexHit[];
 }
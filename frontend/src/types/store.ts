@@ -1,5 +1,6 @@
 import type { PageScale, PageCalibration } from '../utils/scales';
 import type { SearchHitQuad } from './search';
+import type { SheetInfo } from './page';
 export type { PageScale, PageCalibration };
 
 export type ToolType = 'select' | 'segment' | 'linear' | 'area' | 'count' | 'note' | 'draw' | 'highlight' | 'legend' | 'ruler';
@@ -134,7 +135,6 @@ export interface PdfFile {
     url: string;          // Blob URL for displaying in canvas
     path?: string;        // Local file path (required for backend re-hydration)
     fileSize?: number;    // File size in bytes
-    thumbnails?: string[]; // Base64 PNG thumbnails for each page (cached for sidebar)
     ingestionStatus?: 'pending' | 'processing' | 'completed' | 'failed';
 }
 
@@ -263,6 +263,7 @@ export interface ProjectState {
 
     // Markup Actions
     addMarkup: (markup: Markup) => void;
+    addMarkups: (markupsByPage: Record<string, Markup[]>) => void; // Keyed by page id, one undo step
     updateMarkup: (id: string, updates: Partial<Markup> | Partial<Markup['properties']>) => void; // Support both
     deleteMarkup: (id: string) => void;
 
@@ -290,6 +291,7 @@ export interface ProjectState {
     renamePdf: (pdfId: string, name: string) => void; // New Action
     updatePdf: (pdfId: string, updates: Partial<PdfFile>) => void; // General update action
     renamePage: (pageId: string, name: string) => void; // New Action
+    applySheetNames: (pdfId: string, sheets: SheetInfo[]) => void; // Names pages that have no custom name
 
     // Project File Actions (.ptf files)
     saveProjectToFile: () => Promise<boolean>;       // Save to existing path or prompt for new
@@ -59,3 +59,94 @@ export function buildProtocolUrl(path: string): string {
 export function isWindows(): boolean {
     return cachedPlatform === 'windows';
 }
+
+/** Edge length of a page tile in pixels (TILE_SIZE in src-tauri/src/tiles.rs) */
+export const TILE_SIZE = 256;
+
+/**
+ * Encoding of a protocol render. 'rgba' returns raw pixels; the size is in the
+ * X-Image-Width / X-Image-Height response headers.
+ */
+export type RenderFormat = 'png' | 'jpeg' | 'webp' | 'rgba';
+
+export interface RenderFormatOptions {
+    format?: RenderFormat;
+    /** 1-100, only used by jpeg and webp (default 85) */
+    quality?: number;
+}
+
+/**
+ * Colour treatment applied by the backend while rendering, so muted sheets
+ * match in exported images too.
+ */
+export type RenderMode = 'normal' | 'grayscale' | 'faded' | 'inverted' | 'monochrome';
+
+export interface RenderModeOptions {
+    mode?: RenderMode;
+    /** Fade percentage 0-100 for 'faded' (default 60) */
+    intensity?: number;
+    /** Luminance cut-off 0-255 for 'monochrome' (default 160) */
+    threshold?: number;
+}
+
+/** An optional content group, as returned by the `list_layers` command */
+export interface PdfLayer {
+    /** Index to pass in `hiddenLayers` */
+    index: number;
+    name: string;
+    /** Visibility in the document's default configuration */
+    visible: boolean;
+}
+
+export interface RenderLayerOptions {
+    /** Layer indices to hide for this render */
+    hiddenLayers?: number[];
+}
+
+export type RenderQueryOptions = RenderFormatOptions & RenderModeOptions & RenderLayerOptions;
+
+/**
+ * Build the `format=&quality=&mode=&hide=` query for a page or tile render.
+ * Returns an empty string for the default (normal PNG).
+ */
+export function buildRenderQuery(options?: RenderQueryOptions): string {
+    const params: string[] = [];
+    if (options?.format && options.format !== 'png') {
+        params.push(`format=${options.format}`);
+    }
+    if (options?.quality !== undefined) {
+        params.push(`quality=${Math.round(options.quality)}`);
+    }
+    if (options?.mode && options.mode !== 'normal') {
+        params.push(`mode=${options.mode}`);
+        if (options.mode === 'faded' && options.intensity !== undefined) {
+            params.push(`intensity=${Math.round(options.intensity)}`);
+        }
+        if (options.mode === 'monochrome' && options.threshold !== undefined) {
+            params.push(`threshold=${Math.round(options.threshold)}`);
+        }
+    }
+    if (options?.hiddenLayers && options.hiddenLayers.length > 0) {
+        const hidden = [...new Set(options.hiddenLayers)].sort((a, b) => a - b);
+        params.push(`hide=${hidden.join(',')}`);
+    }
+    return params.join('&');
+}
+
+/**
+ * Build the URL of a single page tile.
+ * Level 0 renders the page at zoom 0.25 and each level doubles it (max level 6).
+ * Tiles are counted from the top-left of the page.
+ */
+export function buildTileUrl(
+    pdfId: string,
+    pageIndex: number,
+    level: number,
+    x: number,
+    y: number,
+    options?: RenderQueryOptions
+): string {
+    const query = buildRenderQuery(options);
+    const path = `/tile/${pdfId}/${pageIndex}/${level}/${x}/${y}.png`;
+    return buildProtocolUrl(query ? `${path}?${query}` : path);
+}
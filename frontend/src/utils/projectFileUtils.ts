@@ -53,9 +53,8 @@ export async function serializeProject(project: Project): Promise<string> {
         path: pdf.path,
         // Provide the path as 'url' so the backend optimization logic can find the file to embed
         // If it's a blob url (not a path), we can't embed it easily via backend
-        url: pdf.path || (pdf.url.startsWith('blob:') ? '' : pdf.url),
-        // Persist thumbnails
-        thumbnails: pdf.thumbnails
+        url: pdf.path || (pdf.url.startsWith('blob:') ? '' : pdf.url)
+        // Thumbnails are not persisted; the backend caches renders by file hash
     }));
 
     const serialized: SerializedProject = {
@@ -120,8 +119,7 @@ export async function deserializeProject(json: string): Promise<Project> {
             pageCount: pdfData.pageCount,
             url: virtualUrl,
             fileSize: pdfData.fileSize,
-            path: rehydratePath,
-            thumbnails: (pdfData as any).thumbnails
+            path: rehydratePath
         });
     }
 
@@ -141,6 +141,60 @@ export const getScaleById = (id: string): PageScale | undefined => {
     return ALL_SCALES.find(s => s.id === id);
 };
 
+/** A scale notation found by the `detect_page_scales` command */
+export interface DetectedScaleNotation {
+    text: string;
+    kind: Exclude<ScaleCategory, 'custom'>;
+    paper_inches_per_foot: number;
+    pixels_per_foot: number;
+    /** Preceded by a "SCALE" label */
+    labelled: boolean;
+    /** [x0, y0, x1, y1] in page pixels, when located */
+    bounds: [number, number, number, number] | null;
+}
+
+/** Result of `detect_page_scales` for one page */
+export interface PageScaleDetection {
+    page_idx: number;
+    notations: DetectedScaleNotation[];
+    not_to_scale: boolean;
+    /** Index into notations of the proposed scale */
+    proposed: number | null;
+}
+
+/** A graphic scale bar found by the `detect_scale_bars` command */
+export interface DetectedScaleBar {
+    /** [x0, y0, x1, y1] in page pixels */
+    bounds: [number, number, number, number];
+    tick_count: number;
+    /** Tick labels consistent with the calibration */
+    label_count: number;
+    /** No unit was printed, so feet were assumed */
+    unit_assumed: boolean;
+    /** Ready to pass to createCustomScale */
+    calibration: PageCalibration;
+}
+
+/**
+ * Turn a detected notation into a PageScale, reusing the predefined scale
+ * when one matches so the scale picker shows it selected.
+ */
+export const scaleFromDetection = (notation: DetectedScaleNotation): PageScale => {
+    const preset = ALL_SCALES.find(s =>
+        s.category === notation.kind &&
+        Math.abs(s.pixelsPerFoot - notation.pixels_per_foot) < 1e-6 * s.pixelsPerFoot
+    );
+    if (preset) return preset;
+
+    return {
+        id: 'detected',
+        name: notation.text,
+        category: notation.kind,
+        pixelsPerFoot: notation.pixels_per_foot,
+        displayUnit: notation.kind === 'metric' ? 'm' : 'ft',
+    };
+};
+
 /**
  * Create a custom scale from calibration data
  */
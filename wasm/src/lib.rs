@@ -1,8 +1,15 @@
 // wasm/src/lib.rs
 use wasm_bindgen::prelude::*;
 
-// Mocking the imported schema module structure for demonstration
-// use crate::schema_generated::pro_takeoff::{ViewportResponse, Point};
+mod quadtree;
+#[allow(dead_code, unused_imports, clippy::all)]
+mod schema_generated;
+
+use quadtree::QuadTree;
+use schema_generated::pro_takeoff::{root_as_viewport_response, Geometry, Point};
+
+/// Default snap distance, in frontend units
+const SNAP_RADIUS: f64 = 20.0;
 
 #[wasm_bindgen]
 extern "C" {
@@ -35,7 +42,7 @@ pub fn calculate_shoelace_area(points: &[f64]) -> f64 {
         area += (x_i * y_next) - (x_next * y_i);
     }
 
-    (area.abs() / 2.0)
+    area.abs() / 2.0
 }
 
 /// Snap lookup result
@@ -45,55 +52,162 @@ pub struct SnapResult {
     pub y: f64,
 }
 
+/// Collect every snappable vertex of a `ViewportResponse` buffer: linework
+/// endpoints plus the vertices of any layer entities.
+fn collect_vertices(
+    geometry_buffer: &[u8],
+) -> Result<Vec<[f64; 2]>, flatbuffers::InvalidFlatbuffer> {
+    let root = root_as_viewport_response(geometry_buffer)?;
+    let xy = |p: &Point| [p.x() as f64, p.y() as f64];
+    let mut vertices = Vec::new();
+
+    if let Some(segments) = root.segments() {
+        for s in segments {
+            vertices.push(xy(s.a()));
+            vertices.push(xy(s.b()));
+        }
+    }
+    // Control points are off-curve, only the ends of a bezier are real vertices
+    if let Some(curves) = root.curves() {
+        for c in curves {
+            vertices.push(xy(c.p0()));
+            vertices.push(xy(c.p3()));
+        }
+    }
+    if let Some(entities) = root.entities() {
+        for entity in entities {
+            let points = match entity.geometry_type() {
+                Geometry::Polyline => entity.geometry_as_polyline().and_then(|g| g.points()),
+                Geometry::Polygon => entity.geometry_as_polygon().and_then(|g| g.points()),
+                Geometry::Annotation => {
+                    if let Some(p) = entity.geometry_as_annotation().and_then(|g| g.position()) {
+                        vertices.push(xy(p));
+                    }
+                    None
+                }
+                _ => None,
+            };
+            vertices.extend(points.iter().flat_map(|pts| pts.iter()).map(xy));
+        }
+    }
+
+    Ok(vertices)
+}
+
+/// Vertex quadtree built from a viewport buffer.
+///
+/// Build once when `get_viewport_buffer` returns, then call `snap` on every
+/// pointer move without crossing back into the backend.
+#[wasm_bindgen]
+pub struct SnapIndex {
+    tree: QuadTree,
+}
+
+#[wasm_bindgen]
+impl SnapIndex {
+    #[wasm_bindgen(constructor)]
+    pub fn new(geometry_buffer: &[u8]) -> Result<SnapIndex, JsError> {
+        let vertices =
+            collect_vertices(geometry_buffer).map_err(|e| JsError::new(&e.to_string()))?;
+        Ok(SnapIndex {
+            tree: QuadTree::from_points(&vertices),
+        })
+    }
+
+    #[wasm_bindgen(getter, js_name = vertexCount)]
+    pub fn vertex_count(&self) -> usize {
+        self.tree.len()
+    }
+
+    /// Nearest vertex within `radius` of the cursor.
+    pub fn snap(&self, cursor_x: f64, cursor_y: f64, radius: f64) -> Option<SnapResult> {
+        self.tree
+            .nearest_within([cursor_x, cursor_y], radius)
+            .map(|[x, y]| SnapResult { x, y })
+    }
+}
+
 /// Find nearest vertex in the FlatBuffer blob to the cursor.
 /// `geometry_buffer` is the raw bytes from the API response.
+///
+/// One-off lookup; use `SnapIndex` when querying the same buffer repeatedly.
 #[wasm_bindgen]
 pub fn snap_to_vertex(cursor_x: f64, cursor_y: f64, geometry_buffer: &[u8]) -> Option<SnapResult> {
-    // 1. Initialize FlatBuffer verifier/reader
-    // let root = flatbuffers::root::<ViewportResponse>(geometry_buffer).ok()?;
-    
-    // 2. Iterate entities
-    // let entities = root.entities()?;
-    
-    // Optimization: In a real "Formula 1" engine, we wouldn't scan all points linearly.
-    // The FlatBuffer structure itself isn't a spatial index.
-    // Ideally, we would have built a cheap QuadTree in Wasm memory upon data load.
-    // But per requirements, we read the buffer. We'll do a linear scan for simplicity or assume structure allows it.
-    
-    // For demonstration of "Reading Binary Directly":
-    // We'll mock the iteration.
-    
-    let mut min_dist_sq = f64::MAX;
-    let mut nearest = None;
-    let snap_radius = 20.0; // pixels/units
-    let snap_radius_sq = snap_radius * snap_radius;
-
-    // Hypothetical loop over points in the buffer
-    // for entity in entities {
-    //    match entity.geometry_type() {
-    //       Geometry::Polyline => {
-    //           let polyline = entity.geometry_as_polyline().unwrap();
-    //           for p in polyline.points() { ... check dist ... }
-    //       }
-    //       ...
-    //    }
-    // }
-
-    // Since we don't have the generated code, here is the logic:
-    // Iterate all points, check distance squared.
-    // return closest if < snap_radius
-    
-    // Pseudo-implementation to satisfy type checker:
-    if geometry_buffer.len() > 0 && cursor_x > 0.0 {
-         // Dummy logic -> if cursor is close to (100, 100), snap to it.
-         let target_x = 100.0;
-         let target_y = 100.0;
-         let dx = cursor_x - target_x;
-         let dy = cursor_y - target_y;
-         if dx*dx + dy*dy < snap_radius_sq {
-             return Some(SnapResult { x: target_x, y: target_y });
-         }
+    let vertices = collect_vertices(geometry_buffer).ok()?;
+    let radius_sq = SNAP_RADIUS * SNAP_RADIUS;
+
+    vertices
+        .into_iter()
+        .map(|[x, y]| (x, y, (x - cursor_x).powi(2) + (y - cursor_y).powi(2)))
+        .filter(|&(_, _, d)| d <= radius_sq)
+        .min_by(|a, b| a.2.total_cmp(&b.2))
+        .map(|(x, y, _)| SnapResult { x, y })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use schema_generated::pro_takeoff::{
+        finish_viewport_response_buffer, LayerEntity, LayerEntityArgs, Polygon, PolygonArgs,
+        Segment, ViewportResponse, ViewportResponseArgs,
+    };
+
+    fn viewport_buffer() -> Vec<u8> {
+        let mut fbb = flatbuffers::FlatBufferBuilder::new();
+        let segments = fbb.create_vector(&[
+            Segment::new(&Point::new(0.0, 0.0), &Point::new(100.0, 0.0)),
+            Segment::new(&Point::new(100.0, 0.0), &Point::new(100.0, 100.0)),
+        ]);
+        let ring = fbb.create_vector(&[Point::new(300.0, 300.0), Point::new(350.0, 300.0)]);
+        let polygon = Polygon::create(
+            &mut fbb,
+            &PolygonArgs {
+                points: Some(ring),
+                ..Default::default()
+            },
+        );
+        let entity = LayerEntity::create(
+            &mut fbb,
+            &LayerEntityArgs {
+                id: 1,
+                geometry_type: Geometry::Polygon,
+                geometry: Some(polygon.as_union_value()),
+            },
+        );
+        let entities = fbb.create_vector(&[entity]);
+        let root = ViewportResponse::create(
+            &mut fbb,
+            &ViewportResponseArgs {
+                entities: Some(entities),
+                page: 0,
+                segments: Some(segments),
+                curves: None,
+            },
+        );
+        finish_viewport_response_buffer(&mut fbb, root);
+        fbb.finished_data().to_vec()
     }
 
-    nearest
+    #[test]
+    fn test_snap_index_finds_segment_and_entity_vertices() {
+        let index = SnapIndex::new(&viewport_buffer()).unwrap();
+        assert_eq!(index.vertex_count(), 6);
+
+        let hit = index.snap(97.0, 4.0, 10.0).unwrap();
+        assert_eq!((hit.x, hit.y), (100.0, 0.0));
+
+        let hit = index.snap(348.0, 302.0, 10.0).unwrap();
+        assert_eq!((hit.x, hit.y), (350.0, 300.0));
+
+        assert!(index.snap(200.0, 200.0, 10.0).is_none());
+    }
+
+    #[test]
+    fn test_snap_to_vertex_one_shot() {
+        let buffer = viewport_buffer();
+        let hit = snap_to_vertex(5.0, 5.0, &buffer).unwrap();
+        assert_eq!((hit.x, hit.y), (0.0, 0.0));
+        assert!(snap_to_vertex(50.0, 50.0, &buffer).is_none());
+        assert!(snap_to_vertex(5.0, 5.0, &[1, 2, 3]).is_none());
+    }
 }
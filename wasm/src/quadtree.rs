@@ -0,0 +1,196 @@
+// wasm/src/quadtree.rs
+//
+// Point quadtree over snap vertices. Built once per viewport buffer, then
+// queried on every pointer move, so lookups only visit nodes near the cursor.
+
+/// Points held by a leaf before it splits
+const NODE_CAPACITY: usize = 16;
+
+/// Stops splitting when many vertices share (almost) the same position
+const MAX_DEPTH: usize = 16;
+
+#[derive(Clone, Copy)]
+struct Bounds {
+    min: [f64; 2],
+    max: [f64; 2],
+}
+
+impl Bounds {
+    fn center(&self) -> [f64; 2] {
+        [
+            (self.min[0] + self.max[0]) / 2.0,
+            (self.min[1] + self.max[1]) / 2.0,
+        ]
+    }
+
+    /// Squared distance from `p` to the closest point of the box (0 inside).
+    fn dist_sq(&self, p: [f64; 2]) -> f64 {
+        let dx = (self.min[0] - p[0]).max(0.0).max(p[0] - self.max[0]);
+        let dy = (self.min[1] - p[1]).max(0.0).max(p[1] - self.max[1]);
+        dx * dx + dy * dy
+    }
+
+    /// Index of the quadrant containing `p`: bit 0 = right half, bit 1 = bottom half.
+    fn quadrant_of(&self, p: [f64; 2]) -> usize {
+        let c = self.center();
+        (p[0] >= c[0]) as usize | (((p[1] >= c[1]) as usize) << 1)
+    }
+
+    fn quadrant(&self, q: usize) -> Bounds {
+        let c = self.center();
+        let (min_x, max_x) = if q & 1 == 0 {
+            (self.min[0], c[0])
+        } else {
+            (c[0], self.max[0])
+        };
+        let (min_y, max_y) = if q & 2 == 0 {
+            (self.min[1], c[1])
+        } else {
+            (c[1], self.max[1])
+        };
+        Bounds {
+            min: [min_x, min_y],
+            max: [max_x, max_y],
+        }
+    }
+}
+
+struct Node {
+    bounds: Bounds,
+    points: Vec<[f64; 2]>,
+    children: Option<Box<[Node; 4]>>,
+}
+
+impl Node {
+    fn new(bounds: Bounds) -> Self {
+        Node {
+            bounds,
+            points: Vec::new(),
+            children: None,
+        }
+    }
+
+    fn insert(&mut self, p: [f64; 2], depth: usize) {
+        if let Some(children) = self.children.as_mut() {
+            children[self.bounds.quadrant_of(p)].insert(p, depth + 1);
+            return;
+        }
+
+        self.points.push(p);
+        if self.points.len() > NODE_CAPACITY && depth < MAX_DEPTH {
+            let b = self.bounds;
+            let mut children = Box::new([0, 1, 2, 3].map(|q| Node::new(b.quadrant(q))));
+            for p in self.points.drain(..) {
+                children[b.quadrant_of(p)].insert(p, depth + 1);
+            }
+            self.children = Some(children);
+        }
+    }
+
+    fn nearest(&self, p: [f64; 2], best: &mut Option<([f64; 2], f64)>, limit_sq: f64) {
+        let bound = best.map_or(limit_sq, |(_, d)| d);
+        if self.bounds.dist_sq(p) > bound {
+            return;
+        }
+
+        for &q in &self.points {
+            let dx = q[0] - p[0];
+            let dy = q[1] - p[1];
+            let d = dx * dx + dy * dy;
+            if d <= best.map_or(limit_sq, |(_, d)| d) {
+                *best = Some((q, d));
+            }
+        }
+
+        if let Some(children) = &self.children {
+            // Visit the quadrant containing the cursor first to tighten the bound early
+            let first = self.bounds.quadrant_of(p);
+            children[first].nearest(p, best, limit_sq);
+            for (i, child) in children.iter().enumerate() {
+                if i != first {
+                    child.nearest(p, best, limit_sq);
+                }
+            }
+        }
+    }
+}
+
+pub struct QuadTree {
+    root: Option<Node>,
+    len: usize,
+}
+
+impl QuadTree {
+    pub fn from_points(points: &[[f64; 2]]) -> Self {
+        let Some(&first) = points.first() else {
+            return QuadTree { root: None, len: 0 };
+        };
+
+        let mut bounds = Bounds {
+            min: first,
+            max: first,
+        };
+        for p in points {
+            bounds.min = [bounds.min[0].min(p[0]), bounds.min[1].min(p[1])];
+            bounds.max = [bounds.max[0].max(p[0]), bounds.max[1].max(p[1])];
+        }
+
+        let mut root = Node::new(bounds);
+        for &p in points {
+            root.insert(p, 0);
+        }
+        QuadTree {
+            root: Some(root),
+            len: points.len(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Closest point to `p` no further than `radius`.
+    pub fn nearest_within(&self, p: [f64; 2], radius: f64) -> Option<[f64; 2]> {
+        let mut best = None;
+        self.root.as_ref()?.nearest(p, &mut best, radius * radius);
+        best.map(|(q, _)| q)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nearest_matches_linear_scan() {
+        let points: Vec<[f64; 2]> = (0..500)
+            .map(|i| [((i * 37) % 211) as f64 * 3.0, ((i * 91) % 173) as f64 * 2.0])
+            .collect();
+        let tree = QuadTree::from_points(&points);
+        assert_eq!(tree.len(), 500);
+
+        for cursor in [[10.0, 10.0], [300.0, 120.0], [633.0, 346.0], [-5.0, 50.0]] {
+            let expected = points
+                .iter()
+                .copied()
+                .map(|q| (q, (q[0] - cursor[0]).powi(2) + (q[1] - cursor[1]).powi(2)))
+                .filter(|&(_, d)| d <= 400.0)
+                .min_by(|a, b| a.1.total_cmp(&b.1))
+                .map(|(_, d)| d);
+            let found = tree
+                .nearest_within(cursor, 20.0)
+                .map(|q| (q[0] - cursor[0]).powi(2) + (q[1] - cursor[1]).powi(2));
+            assert_eq!(found, expected);
+        }
+    }
+
+    #[test]
+    fn test_duplicate_points_and_empty_tree() {
+        let tree = QuadTree::from_points(&[[5.0, 5.0]; 100]);
+        assert_eq!(tree.nearest_within([6.0, 6.0], 2.0), Some([5.0, 5.0]));
+        assert_eq!(tree.nearest_within([60.0, 60.0], 2.0), None);
+
+        let empty = QuadTree::from_points(&[]);
+        assert_eq!(empty.nearest_within([0.0, 0.0], 100.0), None);
+    }
+}
@@ -0,0 +1,1519 @@
+// automatically generated by the FlatBuffers compiler, do not modify
+
+
+// @generated
+
+use core::mem;
+use core::cmp::Ordering;
+
+extern crate flatbuffers;
+use self::flatbuffers::{EndianScalar, Follow};
+
+#[allow(unused_imports, dead_code)]
+pub mod pro_takeoff {
+
+  use core::mem;
+  use core::cmp::Ordering;
+
+  extern crate flatbuffers;
+  use self::flatbuffers::{EndianScalar, Follow};
+#[deprecated(since = "2.0.0", note = "Use associated constants instead. This will no longer be generated in 2021.")]
+pub const ENUM_MIN_GEOMETRY: u8 = 0;
+#[deprecated(since = "2.0.0", note = "Use associated constants instead. This will no longer be generated in 2021.")]
+pub const ENUM_MAX_GEOMETRY: u8 = 3;
+#[deprecated(since = "2.0.0", note = "Use associated constants instead. This will no longer be generated in 2021.")]
+#[allow(non_camel_case_types)]
+pub const ENUM_VALUES_GEOMETRY: [Geometry; 4] = [
+  Geometry::NONE,
+  Geometry::Polyline,
+  Geometry::Polygon,
+  Geometry::Annotation,
+];
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[repr(transparent)]
+pub struct Geometry(pub u8);
+#[allow(non_upper_case_globals)]
+impl Geometry {
+  pub const NONE: Self = Self(0);
+  pub const Polyline: Self = Self(1);
+  pub const Polygon: Self = Self(2);
+  pub const Annotation: Self = Self(3);
+
+  pub const ENUM_MIN: u8 = 0;
+  pub const ENUM_MAX: u8 = 3;
+  pub const ENUM_VALUES: &'static [Self] = &[
+    Self::NONE,
+    Self::Polyline,
+    Self::Polygon,
+    Self::Annotation,
+  ];
+  /// Returns the variant's name or "" if unknown.
+  pub fn variant_name(self) -> Option<&'static str> {
+    match self {
+      Self::NONE => Some("NONE"),
+      Self::Polyline => Some("Polyline"),
+      Self::Polygon => Some("Polygon"),
+      Self::Annotation => Some("Annotation"),
+      _ => None,
+    }
+  }
+}
+impl core::fmt::Debug for Geometry {
+  fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+    if let Some(name) = self.variant_name() {
+      f.write_str(name)
+    } else {
+      f.write_fmt(format_args!("<UNKNOWN {:?}>", self.0))
+    }
+  }
+}
+impl<'a> flatbuffers::Follow<'a> for Geometry {
+  type Inner = Self;
+  #[inline]
+  unsafe fn follow(buf: &'a [u8], loc: usize) -> Self::Inner {
+    let b = unsafe { flatbuffers::read_scalar_at::<u8>(buf, loc) };
+    Self(b)
+  }
+}
+
+impl flatbuffers::Push for Geometry {
+    type Output = Geometry;
+    #[inline]
+    unsafe fn push(&self, dst: &mut [u8], _written_len: usize) {
+        unsafe { flatbuffers::emplace_scalar::<u8>(dst, self.0) };
+    }
+}
+
+impl flatbuffers::EndianScalar for Geometry {
+  type Scalar = u8;
+  #[inline]
+  fn to_little_endian(self) -> u8 {
+    self.0.to_le()
+  }
+  #[inline]
+  #[allow(clippy::wrong_self_convention)]
+  fn from_little_endian(v: u8) -> Self {
+    let b = u8::from_le(v);
+    Self(b)
+  }
+}
+
+impl<'a> flatbuffers::Verifiable for Geometry {
+  #[inline]
+  fn run_verifier(
+    v: &mut flatbuffers::Verifier, pos: usize
+  ) -> Result<(), flatbuffers::InvalidFlatbuffer> {
+    use self::flatbuffers::Verifiable;
+    u8::run_verifier(v, pos)
+  }
+}
+
+impl flatbuffers::SimpleToVerifyInSlice for Geometry {}
+pub struct GeometryUnionTableOffset {}
+
+// struct Point, aligned to 4
+#[repr(transparent)]
+#[derive(Clone, Copy, PartialEq)]
+pub struct Point(pub [u8; 8]);
+impl Default for Point {
+  fn default() -> Self {
+    Self([0; 8])
+  }
+}
+impl core::fmt::Debug for Point {
+  fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+    f.debug_struct("Point")
+      .field("x", &self.x())
+      .field("y", &self.y())
+      .finish()
+  }
+}
+
+impl flatbuffers::SimpleToVerifyInSlice for Point {}
+impl<'a> flatbuffers::Follow<'a> for Point {
+  type Inner = &'a Point;
+  #[inline]
+  unsafe fn follow(buf: &'a [u8], loc: usize) -> Self::Inner {
+    unsafe { <&'a Point>::follow(buf, loc) }
+  }
+}
+impl<'a> flatbuffers::Follow<'a> for &'a Point {
+  type Inner = &'a Point;
+  #[inline]
+  unsafe fn follow(buf: &'a [u8], loc: usize) -> Self::Inner {
+    unsafe { flatbuffers::follow_cast_ref::<Point>(buf, loc) }
+  }
+}
+impl<'b> flatbuffers::Push for Point {
+    type Output = Point;
+    #[inline]
+    unsafe fn push(&self, dst: &mut [u8], _written_len: usize) {
+        let src = unsafe { ::core::slice::from_raw_parts(self as *const Point as *const u8, <Self as flatbuffers::Push>::size()) };
+        dst.copy_from_slice(src);
+    }
+    #[inline]
+    fn alignment() -> flatbuffers::PushAlignment {
+        flatbuffers::PushAlignment::new(4)
+    }
+}
+
+impl<'a> flatbuffers::Verifiable for Point {
+  #[inline]
+  fn run_verifier(
+    v: &mut flatbuffers::Verifier, pos: usize
+  ) -> Result<(), flatbuffers::InvalidFlatbuffer> {
+    use self::flatbuffers::Verifiable;
+    v.in_buffer::<Self>(pos)
+  }
+}
+
+impl<'a> Point {
+  #[allow(clippy::too_many_arguments)]
+  pub fn new(
+    x: f32,
+    y: f32,
+  ) -> Self {
+    let mut s = Self([0; 8]);
+    s.set_x(x);
+    s.set_y(y);
+    s
+  }
+
+  pub fn x(&self) -> f32 {
+    let mut mem = core::mem::MaybeUninit::<<f32 as EndianScalar>::Scalar>::uninit();
+    // Safety:
+    // Created from a valid Table for this object
+    // Which contains a valid value in this slot
+    EndianScalar::from_little_endian(unsafe {
+      core::ptr::copy_nonoverlapping(
+        self.0[0..].as_ptr(),
+        mem.as_mut_ptr() as *mut u8,
+        core::mem::size_of::<<f32 as EndianScalar>::Scalar>(),
+      );
+      mem.assume_init()
+    })
+  }
+
+  pub fn set_x(&mut self, x: f32) {
+    let x_le = x.to_little_endian();
+    // Safety:
+    // Created from a valid Table for this object
+    // Which contains a valid value in this slot
+    unsafe {
+      core::ptr::copy_nonoverlapping(
+        &x_le as *const _ as *const u8,
+        self.0[0..].as_mut_ptr(),
+        core::mem::size_of::<<f32 as EndianScalar>::Scalar>(),
+      );
+    }
+  }
+
+  pub fn y(&self) -> f32 {
+    let mut mem = core::mem::MaybeUninit::<<f32 as EndianScalar>::Scalar>::uninit();
+    // Safety:
+    // Created from a valid Table for this object
+    // Which contains a valid value in this slot
+    EndianScalar::from_little_endian(unsafe {
+      core::ptr::copy_nonoverlapping(
+        self.0[4..].as_ptr(),
+        mem.as_mut_ptr() as *mut u8,
+        core::mem::size_of::<<f32 as EndianScalar>::Scalar>(),
+      );
+      mem.assume_init()
+    })
+  }
+
+  pub fn set_y(&mut self, x: f32) {
+    let x_le = x.to_little_endian();
+    // Safety:
+    // Created from a valid Table for this object
+    // Which contains a valid value in this slot
+    unsafe {
+      core::ptr::copy_nonoverlapping(
+        &x_le as *const _ as *const u8,
+        self.0[4..].as_mut_ptr(),
+        core::mem::size_of::<<f32 as EndianScalar>::Scalar>(),
+      );
+    }
+  }
+
+}
+
+// struct Color, aligned to 1
+#[repr(transparent)]
+#[derive(Clone, Copy, PartialEq)]
+pub struct Color(pub [u8; 4]);
+impl Default for Color {
+  fn default() -> Self {
+    Self([0; 4])
+  }
+}
+impl core::fmt::Debug for Color {
+  fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+    f.debug_struct("Color")
+      .field("r", &self.r())
+      .field("g", &self.g())
+      .field("b", &self.b())
+      .field("a", &self.a())
+      .finish()
+  }
+}
+
+impl flatbuffers::SimpleToVerifyInSlice for Color {}
+impl<'a> flatbuffers::Follow<'a> for Color {
+  type Inner = &'a Color;
+  #[inline]
+  unsafe fn follow(buf: &'a [u8], loc: usize) -> Self::Inner {
+    unsafe { <&'a Color>::follow(buf, loc) }
+  }
+}
+impl<'a> flatbuffers::Follow<'a> for &'a Color {
+  type Inner = &'a Color;
+  #[inline]
+  unsafe fn follow(buf: &'a [u8], loc: usize) -> Self::Inner {
+    unsafe { flatbuffers::follow_cast_ref::<Color>(buf, loc) }
+  }
+}
+impl<'b> flatbuffers::Push for Color {
+    type Output = Color;
+    #[inline]
+    unsafe fn push(&self, dst: &mut [u8], _written_len: usize) {
+        let src = unsafe { ::core::slice::from_raw_parts(self as *const Color as *const u8, <Self as flatbuffers::Push>::size()) };
+        dst.copy_from_slice(src);
+    }
+    #[inline]
+    fn alignment() -> flatbuffers::PushAlignment {
+        flatbuffers::PushAlignment::new(1)
+    }
+}
+
+impl<'a> flatbuffers::Verifiable for Color {
+  #[inline]
+  fn run_verifier(
+    v: &mut flatbuffers::Verifier, pos: usize
+  ) -> Result<(), flatbuffers::InvalidFlatbuffer> {
+    use self::flatbuffers::Verifiable;
+    v.in_buffer::<Self>(pos)
+  }
+}
+
+impl<'a> Color {
+  #[allow(clippy::too_many_arguments)]
+  pub fn new(
+    r: u8,
+    g: u8,
+    b: u8,
+    a: u8,
+  ) -> Self {
+    let mut s = Self([0; 4]);
+    s.set_r(r);
+    s.set_g(g);
+    s.set_b(b);
+    s.set_a(a);
+    s
+  }
+
+  pub fn r(&self) -> u8 {
+    let mut mem = core::mem::MaybeUninit::<<u8 as EndianScalar>::Scalar>::uninit();
+    // Safety:
+    // Created from a valid Table for this object
+    // Which contains a valid value in this slot
+    EndianScalar::from_little_endian(unsafe {
+      core::ptr::copy_nonoverlapping(
+        self.0[0..].as_ptr(),
+        mem.as_mut_ptr() as *mut u8,
+        core::mem::size_of::<<u8 as EndianScalar>::Scalar>(),
+      );
+      mem.assume_init()
+    })
+  }
+
+  pub fn set_r(&mut self, x: u8) {
+    let x_le = x.to_little_endian();
+    // Safety:
+    // Created from a valid Table for this object
+    // Which contains a valid value in this slot
+    unsafe {
+      core::ptr::copy_nonoverlapping(
+        &x_le as *const _ as *const u8,
+        self.0[0..].as_mut_ptr(),
+        core::mem::size_of::<<u8 as EndianScalar>::Scalar>(),
+      );
+    }
+  }
+
+  pub fn g(&self) -> u8 {
+    let mut mem = core::mem::MaybeUninit::<<u8 as EndianScalar>::Scalar>::uninit();
+    // Safety:
+    // Created from a valid Table for this object
+    // Which contains a valid value in this slot
+    EndianScalar::from_little_endian(unsafe {
+      core::ptr::copy_nonoverlapping(
+        self.0[1..].as_ptr(),
+        mem.as_mut_ptr() as *mut u8,
+        core::mem::size_of::<<u8 as EndianScalar>::Scalar>(),
+      );
+      mem.assume_init()
+    })
+  }
+
+  pub fn set_g(&mut self, x: u8) {
+    let x_le = x.to_little_endian();
+    // Safety:
+    // Created from a valid Table for this object
+    // Which contains a valid value in this slot
+    unsafe {
+      core::ptr::copy_nonoverlapping(
+        &x_le as *const _ as *const u8,
+        self.0[1..].as_mut_ptr(),
+        core::mem::size_of::<<u8 as EndianScalar>::Scalar>(),
+      );
+    }
+  }
+
+  pub fn b(&self) -> u8 {
+    let mut mem = core::mem::MaybeUninit::<<u8 as EndianScalar>::Scalar>::uninit();
+    // Safety:
+    // Created from a valid Table for this object
+    // Which contains a valid value in this slot
+    EndianScalar::from_little_endian(unsafe {
+      core::ptr::copy_nonoverlapping(
+        self.0[2..].as_ptr(),
+        mem.as_mut_ptr() as *mut u8,
+        core::mem::size_of::<<u8 as EndianScalar>::Scalar>(),
+      );
+      mem.assume_init()
+    })
+  }
+
+  pub fn set_b(&mut self, x: u8) {
+    let x_le = x.to_little_endian();
+    // Safety:
+    // Created from a valid Table for this object
+    // Which contains a valid value in this slot
+    unsafe {
+      core::ptr::copy_nonoverlapping(
+        &x_le as *const _ as *const u8,
+        self.0[2..].as_mut_ptr(),
+        core::mem::size_of::<<u8 as EndianScalar>::Scalar>(),
+      );
+    }
+  }
+
+  pub fn a(&self) -> u8 {
+    let mut mem = core::mem::MaybeUninit::<<u8 as EndianScalar>::Scalar>::uninit();
+    // Safety:
+    // Created from a valid Table for this object
+    // Which contains a valid value in this slot
+    EndianScalar::from_little_endian(unsafe {
+      core::ptr::copy_nonoverlapping(
+        self.0[3..].as_ptr(),
+        mem.as_mut_ptr() as *mut u8,
+        core::mem::size_of::<<u8 as EndianScalar>::Scalar>(),
+      );
+      mem.assume_init()
+    })
+  }
+
+  pub fn set_a(&mut self, x: u8) {
+    let x_le = x.to_little_endian();
+    // Safety:
+    // Created from a valid Table for this object
+    // Which contains a valid value in this slot
+    unsafe {
+      core::ptr::copy_nonoverlapping(
+        &x_le as *const _ as *const u8,
+        self.0[3..].as_mut_ptr(),
+        core::mem::size_of::<<u8 as EndianScalar>::Scalar>(),
+      );
+    }
+  }
+
+}
+
+// struct Segment, aligned to 4
+#[repr(transparent)]
+#[derive(Clone, Copy, PartialEq)]
+pub struct Segment(pub [u8; 16]);
+impl Default for Segment {
+  fn default() -> Self {
+    Self([0; 16])
+  }
+}
+impl core::fmt::Debug for Segment {
+  fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+    f.debug_struct("Segment")
+      .field("a", &self.a())
+      .field("b", &self.b())
+      .finish()
+  }
+}
+
+impl flatbuffers::SimpleToVerifyInSlice for Segment {}
+impl<'a> flatbuffers::Follow<'a> for Segment {
+  type Inner = &'a Segment;
+  #[inline]
+  unsafe fn follow(buf: &'a [u8], loc: usize) -> Self::Inner {
+    unsafe { <&'a Segment>::follow(buf, loc) }
+  }
+}
+impl<'a> flatbuffers::Follow<'a> for &'a Segment {
+  type Inner = &'a Segment;
+  #[inline]
+  unsafe fn follow(buf: &'a [u8], loc: usize) -> Self::Inner {
+    unsafe { flatbuffers::follow_cast_ref::<Segment>(buf, loc) }
+  }
+}
+impl<'b> flatbuffers::Push for Segment {
+    type Output = Segment;
+    #[inline]
+    unsafe fn push(&self, dst: &mut [u8], _written_len: usize) {
+        let src = unsafe { ::core::slice::from_raw_parts(self as *const Segment as *const u8, <Self as flatbuffers::Push>::size()) };
+        dst.copy_from_slice(src);
+    }
+    #[inline]
+    fn alignment() -> flatbuffers::PushAlignment {
+        flatbuffers::PushAlignment::new(4)
+    }
+}
+
+impl<'a> flatbuffers::Verifiable for Segment {
+  #[inline]
+  fn run_verifier(
+    v: &mut flatbuffers::Verifier, pos: usize
+  ) -> Result<(), flatbuffers::InvalidFlatbuffer> {
+    use self::flatbuffers::Verifiable;
+    v.in_buffer::<Self>(pos)
+  }
+}
+
+impl<'a> Segment {
+  #[allow(clippy::too_many_arguments)]
+  pub fn new(
+    a: &Point,
+    b: &Point,
+  ) -> Self {
+    let mut s = Self([0; 16]);
+    s.set_a(a);
+    s.set_b(b);
+    s
+  }
+
+  pub fn a(&self) -> &Point {
+    // Safety:
+    // Created from a valid Table for this object
+    // Which contains a valid struct in this slot
+    unsafe { &*(self.0[0..].as_ptr() as *const Point) }
+  }
+
+  #[allow(clippy::identity_op)]
+  pub fn set_a(&mut self, x: &Point) {
+    self.0[0..0 + 8].copy_from_slice(&x.0)
+  }
+
+  pub fn b(&self) -> &Point {
+    // Safety:
+    // Created from a valid Table for this object
+    // Which contains a valid struct in this slot
+    unsafe { &*(self.0[8..].as_ptr() as *const Point) }
+  }
+
+  #[allow(clippy::identity_op)]
+  pub fn set_b(&mut self, x: &Point) {
+    self.0[8..8 + 8].copy_from_slice(&x.0)
+  }
+
+}
+
+// struct Bezier, aligned to 4
+#[repr(transparent)]
+#[derive(Clone, Copy, PartialEq)]
+pub struct Bezier(pub [u8; 32]);
+impl Default for Bezier {
+  fn default() -> Self {
+    Self([0; 32])
+  }
+}
+impl core::fmt::Debug for Bezier {
+  fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+    f.debug_struct("Bezier")
+      .field("p0", &self.p0())
+      .field("p1", &self.p1())
+      .field("p2", &self.p2())
+      .field("p3", &self.p3())
+      .finish()
+  }
+}
+
+impl flatbuffers::SimpleToVerifyInSlice for Bezier {}
+impl<'a> flatbuffers::Follow<'a> for Bezier {
+  type Inner = &'a Bezier;
+  #[inline]
+  unsafe fn follow(buf: &'a [u8], loc: usize) -> Self::Inner {
+    unsafe { <&'a Bezier>::follow(buf, loc) }
+  }
+}
+impl<'a> flatbuffers::Follow<'a> for &'a Bezier {
+  type Inner = &'a Bezier;
+  #[inline]
+  unsafe fn follow(buf: &'a [u8], loc: usize) -> Self::Inner {
+    unsafe { flatbuffers::follow_cast_ref::<Bezier>(buf, loc) }
+  }
+}
+impl<'b> flatbuffers::Push for Bezier {
+    type Output = Bezier;
+    #[inline]
+    unsafe fn push(&self, dst: &mut [u8], _written_len: usize) {
+        let src = unsafe { ::core::slice::from_raw_parts(self as *const Bezier as *const u8, <Self as flatbuffers::Push>::size()) };
+        dst.copy_from_slice(src);
+    }
+    #[inline]
+    fn alignment() -> flatbuffers::PushAlignment {
+        flatbuffers::PushAlignment::new(4)
+    }
+}
+
+impl<'a> flatbuffers::Verifiable for Bezier {
+  #[inline]
+  fn run_verifier(
+    v: &mut flatbuffers::Verifier, pos: usize
+  ) -> Result<(), flatbuffers::InvalidFlatbuffer> {
+    use self::flatbuffers::Verifiable;
+    v.in_buffer::<Self>(pos)
+  }
+}
+
+impl<'a> Bezier {
+  #[allow(clippy::too_many_arguments)]
+  pub fn new(
+    p0: &Point,
+    p1: &Point,
+    p2: &Point,
+    p3: &Point,
+  ) -> Self {
+    let mut s = Self([0; 32]);
+    s.set_p0(p0);
+    s.set_p1(p1);
+    s.set_p2(p2);
+    s.set_p3(p3);
+    s
+  }
+
+  pub fn p0(&self) -> &Point {
+    // Safety:
+    // Created from a valid Table for this object
+    // Which contains a valid struct in this slot
+    unsafe { &*(self.0[0..].as_ptr() as *const Point) }
+  }
+
+  #[allow(clippy::identity_op)]
+  pub fn set_p0(&mut self, x: &Point) {
+    self.0[0..0 + 8].copy_from_slice(&x.0)
+  }
+
+  pub fn p1(&self) -> &Point {
+    // Safety:
+    // Created from a valid Table for this object
+    // Which contains a valid struct in this slot
+    unsafe { &*(self.0[8..].as_ptr() as *const Point) }
+  }
+
+  #[allow(clippy::identity_op)]
+  pub fn set_p1(&mut self, x: &Point) {
+    self.0[8..8 + 8].copy_from_slice(&x.0)
+  }
+
+  pub fn p2(&self) -> &Point {
+    // Safety:
+    // Created from a valid Table for this object
+    // Which contains a valid struct in this slot
+    unsafe { &*(self.0[16..].as_ptr() as *const Point) }
+  }
+
+  #[allow(clippy::identity_op)]
+  pub fn set_p2(&mut self, x: &Point) {
+    self.0[16..16 + 8].copy_from_slice(&x.0)
+  }
+
+  pub fn p3(&self) -> &Point {
+    // Safety:
+    // Created from a valid Table for this object
+    // Which contains a valid struct in this slot
+    unsafe { &*(self.0[24..].as_ptr() as *const Point) }
+  }
+
+  #[allow(clippy::identity_op)]
+  pub fn set_p3(&mut self, x: &Point) {
+    self.0[24..24 + 8].copy_from_slice(&x.0)
+  }
+
+}
+pub enum PolylineOffset {}
+#[derive(Copy, Clone, PartialEq)]
+
+pub struct Polyline<'a> {
+  pub _tab: flatbuffers::Table<'a>,
+}
+
+impl<'a> flatbuffers::Follow<'a> for Polyline<'a> {
+  type Inner = Polyline<'a>;
+  #[inline]
+  unsafe fn follow(buf: &'a [u8], loc: usize) -> Self::Inner {
+    Self { _tab: unsafe { flatbuffers::Table::new(buf, loc) } }
+  }
+}
+
+impl<'a> Polyline<'a> {
+  pub const VT_POINTS: flatbuffers::VOffsetT = 4;
+  pub const VT_COLOR: flatbuffers::VOffsetT = 6;
+  pub const VT_THICKNESS: flatbuffers::VOffsetT = 8;
+
+  #[inline]
+  pub unsafe fn init_from_table(table: flatbuffers::Table<'a>) -> Self {
+    Polyline { _tab: table }
+  }
+  #[allow(unused_mut)]
+  pub fn create<'bldr: 'args, 'args: 'mut_bldr, 'mut_bldr, A: flatbuffers::Allocator + 'bldr>(
+    _fbb: &'mut_bldr mut flatbuffers::FlatBufferBuilder<'bldr, A>,
+    args: &'args PolylineArgs<'args>
+  ) -> flatbuffers::WIPOffset<Polyline<'bldr>> {
+    let mut builder = PolylineBuilder::new(_fbb);
+    builder.add_thickness(args.thickness);
+    if let Some(x) = args.color { builder.add_color(x); }
+    if let Some(x) = args.points { builder.add_points(x); }
+    builder.finish()
+  }
+
+
+  #[inline]
+  pub fn points(&self) -> Option<flatbuffers::Vector<'a, Point>> {
+    // Safety:
+    // Created from valid Table for this object
+    // which contains a valid value in this slot
+    unsafe { self._tab.get::<flatbuffers::ForwardsUOffset<flatbuffers::Vector<'a, Point>>>(Polyline::VT_POINTS, None)}
+  }
+
+  #[inline]
+  pub fn color(&self) -> Option<&'a Color> {
+    // Safety:
+    // Created from valid Table for this object
+    // which contains a valid value in this slot
+    unsafe { self._tab.get::<Color>(Polyline::VT_COLOR, None)}
+  }
+
+  #[inline]
+  pub fn thickness(&self) -> f32 {
+    // Safety:
+    // Created from valid Table for this object
+    // which contains a valid value in this slot
+    unsafe { self._tab.get::<f32>(Polyline::VT_THICKNESS, Some(0.0)).unwrap()}
+  }
+}
+
+impl flatbuffers::Verifiable for Polyline<'_> {
+  #[inline]
+  fn run_verifier(
+    v: &mut flatbuffers::Verifier, pos: usize
+  ) -> Result<(), flatbuffers::InvalidFlatbuffer> {
+    use self::flatbuffers::Verifiable;
+    v.visit_table(pos)?
+     .visit_field::<flatbuffers::ForwardsUOffset<flatbuffers::Vector<'_, Point>>>("points", Self::VT_POINTS, false)?
+     .visit_field::<Color>("color", Self::VT_COLOR, false)?
+     .visit_field::<f32>("thickness", Self::VT_THICKNESS, false)?
+     .finish();
+    Ok(())
+  }
+}
+pub struct PolylineArgs<'a> {
+    pub points: Option<flatbuffers::WIPOffset<flatbuffers::Vector<'a, Point>>>,
+    pub color: Option<&'a Color>,
+    pub thickness: f32,
+}
+impl<'a> Default for PolylineArgs<'a> {
+  #[inline]
+  fn default() -> Self {
+    PolylineArgs {
+      points: None,
+      color: None,
+      thickness: 0.0,
+    }
+  }
+}
+
+pub struct PolylineBuilder<'a: 'b, 'b, A: flatbuffers::Allocator + 'a> {
+  fbb_: &'b mut flatbuffers::FlatBufferBuilder<'a, A>,
+  start_: flatbuffers::WIPOffset<flatbuffers::TableUnfinishedWIPOffset>,
+}
+impl<'a: 'b, 'b, A: flatbuffers::Allocator + 'a> PolylineBuilder<'a, 'b, A> {
+  #[inline]
+  pub fn add_points(&mut self, points: flatbuffers::WIPOffset<flatbuffers::Vector<'b , Point>>) {
+    self.fbb_.push_slot_always::<flatbuffers::WIPOffset<_>>(Polyline::VT_POINTS, points);
+  }
+  #[inline]
+  pub fn add_color(&mut self, color: &Color) {
+    self.fbb_.push_slot_always::<&Color>(Polyline::VT_COLOR, color);
+  }
+  #[inline]
+  pub fn add_thickness(&mut self, thickness: f32) {
+    self.fbb_.push_slot::<f32>(Polyline::VT_THICKNESS, thickness, 0.0);
+  }
+  #[inline]
+  pub fn new(_fbb: &'b mut flatbuffers::FlatBufferBuilder<'a, A>) -> PolylineBuilder<'a, 'b, A> {
+    let start = _fbb.start_table();
+    PolylineBuilder {
+      fbb_: _fbb,
+      start_: start,
+    }
+  }
+  #[inline]
+  pub fn finish(self) -> flatbuffers::WIPOffset<Polyline<'a>> {
+    let o = self.fbb_.end_table(self.start_);
+    flatbuffers::WIPOffset::new(o.value())
+  }
+}
+
+impl core::fmt::Debug for Polyline<'_> {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    let mut ds = f.debug_struct("Polyline");
+      ds.field("points", &self.points());
+      ds.field("color", &self.color());
+      ds.field("thickness", &self.thickness());
+      ds.finish()
+  }
+}
+pub enum PolygonOffset {}
+#[derive(Copy, Clone, PartialEq)]
+
+pub struct Polygon<'a> {
+  pub _tab: flatbuffers::Table<'a>,
+}
+
+impl<'a> flatbuffers::Follow<'a> for Polygon<'a> {
+  type Inner = Polygon<'a>;
+  #[inline]
+  unsafe fn follow(buf: &'a [u8], loc: usize) -> Self::Inner {
+    Self { _tab: unsafe { flatbuffers::Table::new(buf, loc) } }
+  }
+}
+
+impl<'a> Polygon<'a> {
+  pub const VT_POINTS: flatbuffers::VOffsetT = 4;
+  pub const VT_FILL_COLOR: flatbuffers::VOffsetT = 6;
+  pub const VT_STROKE_COLOR: flatbuffers::VOffsetT = 8;
+  pub const VT_STROKE_THICKNESS: flatbuffers::VOffsetT = 10;
+
+  #[inline]
+  pub unsafe fn init_from_table(table: flatbuffers::Table<'a>) -> Self {
+    Polygon { _tab: table }
+  }
+  #[allow(unused_mut)]
+  pub fn create<'bldr: 'args, 'args: 'mut_bldr, 'mut_bldr, A: flatbuffers::Allocator + 'bldr>(
+    _fbb: &'mut_bldr mut flatbuffers::FlatBufferBuilder<'bldr, A>,
+    args: &'args PolygonArgs<'args>
+  ) -> flatbuffers::WIPOffset<Polygon<'bldr>> {
+    let mut builder = PolygonBuilder::new(_fbb);
+    builder.add_stroke_thickness(args.stroke_thickness);
+    if let Some(x) = args.stroke_color { builder.add_stroke_color(x); }
+    if let Some(x) = args.fill_color { builder.add_fill_color(x); }
+    if let Some(x) = args.points { builder.add_points(x); }
+    builder.finish()
+  }
+
+
+  #[inline]
+  pub fn points(&self) -> Option<flatbuffers::Vector<'a, Point>> {
+    // Safety:
+    // Created from valid Table for this object
+    // which contains a valid value in this slot
+    unsafe { self._tab.get::<flatbuffers::ForwardsUOffset<flatbuffers::Vector<'a, Point>>>(Polygon::VT_POINTS, None)}
+  }
+
+  #[inline]
+  pub fn fill_color(&self) -> Option<&'a Color> {
+    // Safety:
+    // Created from valid Table for this object
+    // which contains a valid value in this slot
+    unsafe { self._tab.get::<Color>(Polygon::VT_FILL_COLOR, None)}
+  }
+
+  #[inline]
+  pub fn stroke_color(&self) -> Option<&'a Color> {
+    // Safety:
+    // Created from valid Table for this object
+    // which contains a valid value in this slot
+    unsafe { self._tab.get::<Color>(Polygon::VT_STROKE_COLOR, None)}
+  }
+
+  #[inline]
+  pub fn stroke_thickness(&self) -> f32 {
+    // Safety:
+    // Created from valid Table for this object
+    // which contains a valid value in this slot
+    unsafe { self._tab.get::<f32>(Polygon::VT_STROKE_THICKNESS, Some(0.0)).unwrap()}
+  }
+}
+
+impl flatbuffers::Verifiable for Polygon<'_> {
+  #[inline]
+  fn run_verifier(
+    v: &mut flatbuffers::Verifier, pos: usize
+  ) -> Result<(), flatbuffers::InvalidFlatbuffer> {
+    use self::flatbuffers::Verifiable;
+    v.visit_table(pos)?
+     .visit_field::<flatbuffers::ForwardsUOffset<flatbuffers::Vector<'_, Point>>>("points", Self::VT_POINTS, false)?
+     .visit_field::<Color>("fill_color", Self::VT_FILL_COLOR, false)?
+     .visit_field::<Color>("stroke_color", Self::VT_STROKE_COLOR, false)?
+     .visit_field::<f32>("stroke_thickness", Self::VT_STROKE_THICKNESS, false)?
+     .finish();
+    Ok(())
+  }
+}
+pub struct PolygonArgs<'a> {
+    pub points: Option<flatbuffers::WIPOffset<flatbuffers::Vector<'a, Point>>>,
+    pub fill_color: Option<&'a Color>,
+    pub stroke_color: Option<&'a Color>,
+    pub stroke_thickness: f32,
+}
+impl<'a> Default for PolygonArgs<'a> {
+  #[inline]
+  fn default() -> Self {
+    PolygonArgs {
+      points: None,
+      fill_color: None,
+      stroke_color: None,
+      stroke_thickness: 0.0,
+    }
+  }
+}
+
+pub struct PolygonBuilder<'a: 'b, 'b, A: flatbuffers::Allocator + 'a> {
+  fbb_: &'b mut flatbuffers::FlatBufferBuilder<'a, A>,
+  start_: flatbuffers::WIPOffset<flatbuffers::TableUnfinishedWIPOffset>,
+}
+impl<'a: 'b, 'b, A: flatbuffers::Allocator + 'a> PolygonBuilder<'a, 'b, A> {
+  #[inline]
+  pub fn add_points(&mut self, points: flatbuffers::WIPOffset<flatbuffers::Vector<'b , Point>>) {
+    self.fbb_.push_slot_always::<flatbuffers::WIPOffset<_>>(Polygon::VT_POINTS, points);
+  }
+  #[inline]
+  pub fn add_fill_color(&mut self, fill_color: &Color) {
+    self.fbb_.push_slot_always::<&Color>(Polygon::VT_FILL_COLOR, fill_color);
+  }
+  #[inline]
+  pub fn add_stroke_color(&mut self, stroke_color: &Color) {
+    self.fbb_.push_slot_always::<&Color>(Polygon::VT_STROKE_COLOR, stroke_color);
+  }
+  #[inline]
+  pub fn add_stroke_thickness(&mut self, stroke_thickness: f32) {
+    self.fbb_.push_slot::<f32>(Polygon::VT_STROKE_THICKNESS, stroke_thickness, 0.0);
+  }
+  #[inline]
+  pub fn new(_fbb: &'b mut flatbuffers::FlatBufferBuilder<'a, A>) -> PolygonBuilder<'a, 'b, A> {
+    let start = _fbb.start_table();
+    PolygonBuilder {
+      fbb_: _fbb,
+      start_: start,
+    }
+  }
+  #[inline]
+  pub fn finish(self) -> flatbuffers::WIPOffset<Polygon<'a>> {
+    let o = self.fbb_.end_table(self.start_);
+    flatbuffers::WIPOffset::new(o.value())
+  }
+}
+
+impl core::fmt::Debug for Polygon<'_> {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    let mut ds = f.debug_struct("Polygon");
+      ds.field("points", &self.points());
+      ds.field("fill_color", &self.fill_color());
+      ds.field("stroke_color", &self.stroke_color());
+      ds.field("stroke_thickness", &self.stroke_thickness());
+      ds.finish()
+  }
+}
+pub enum AnnotationOffset {}
+#[derive(Copy, Clone, PartialEq)]
+
+pub struct Annotation<'a> {
+  pub _tab: flatbuffers::Table<'a>,
+}
+
+impl<'a> flatbuffers::Follow<'a> for Annotation<'a> {
+  type Inner = Annotation<'a>;
+  #[inline]
+  unsafe fn follow(buf: &'a [u8], loc: usize) -> Self::Inner {
+    Self { _tab: unsafe { flatbuffers::Table::new(buf, loc) } }
+  }
+}
+
+impl<'a> Annotation<'a> {
+  pub const VT_POSITION: flatbuffers::VOffsetT = 4;
+  pub const VT_TEXT: flatbuffers::VOffsetT = 6;
+  pub const VT_FONT_SIZE: flatbuffers::VOffsetT = 8;
+  pub const VT_COLOR: flatbuffers::VOffsetT = 10;
+
+  #[inline]
+  pub unsafe fn init_from_table(table: flatbuffers::Table<'a>) -> Self {
+    Annotation { _tab: table }
+  }
+  #[allow(unused_mut)]
+  pub fn create<'bldr: 'args, 'args: 'mut_bldr, 'mut_bldr, A: flatbuffers::Allocator + 'bldr>(
+    _fbb: &'mut_bldr mut flatbuffers::FlatBufferBuilder<'bldr, A>,
+    args: &'args AnnotationArgs<'args>
+  ) -> flatbuffers::WIPOffset<Annotation<'bldr>> {
+    let mut builder = AnnotationBuilder::new(_fbb);
+    if let Some(x) = args.color { builder.add_color(x); }
+    builder.add_font_size(args.font_size);
+    if let Some(x) = args.text { builder.add_text(x); }
+    if let Some(x) = args.position { builder.add_position(x); }
+    builder.finish()
+  }
+
+
+  #[inline]
+  pub fn position(&self) -> Option<&'a Point> {
+    // Safety:
+    // Created from valid Table for this object
+    // which contains a valid value in this slot
+    unsafe { self._tab.get::<Point>(Annotation::VT_POSITION, None)}
+  }
+
+  #[inline]
+  pub fn text(&self) -> Option<&'a str> {
+    // Safety:
+    // Created from valid Table for this object
+    // which contains a valid value in this slot
+    unsafe { self._tab.get::<flatbuffers::ForwardsUOffset<&str>>(Annotation::VT_TEXT, None)}
+  }
+
+  #[inline]
+  pub fn font_size(&self) -> f32 {
+    // Safety:
+    // Created from valid Table for this object
+    // which contains a valid value in this slot
+    unsafe { self._tab.get::<f32>(Annotation::VT_FONT_SIZE, Some(0.0)).unwrap()}
+  }
+
+  #[inline]
+  pub fn color(&self) -> Option<&'a Color> {
+    // Safety:
+    // Created from valid Table for this object
+    // which contains a valid value in this slot
+    unsafe { self._tab.get::<Color>(Annotation::VT_COLOR, None)}
+  }
+}
+
+impl flatbuffers::Verifiable for Annotation<'_> {
+  #[inline]
+  fn run_verifier(
+    v: &mut flatbuffers::Verifier, pos: usize
+  ) -> Result<(), flatbuffers::InvalidFlatbuffer> {
+    use self::flatbuffers::Verifiable;
+    v.visit_table(pos)?
+     .visit_field::<Point>("position", Self::VT_POSITION, false)?
+     .visit_field::<flatbuffers::ForwardsUOffset<&str>>("text", Self::VT_TEXT, false)?
+     .visit_field::<f32>("font_size", Self::VT_FONT_SIZE, false)?
+     .visit_field::<Color>("color", Self::VT_COLOR, false)?
+     .finish();
+    Ok(())
+  }
+}
+pub struct AnnotationArgs<'a> {
+    pub position: Option<&'a Point>,
+    pub text: Option<flatbuffers::WIPOffset<&'a  str>>,
+    pub font_size: f32,
+    pub color: Option<&'a Color>,
+}
+impl<'a> Default for AnnotationArgs<'a> {
+  #[inline]
+  fn default() -> Self {
+    AnnotationArgs {
+      position: None,
+      text: None,
+      font_size: 0.0,
+      color: None,
+    }
+  }
+}
+
+pub struct AnnotationBuilder<'a: 'b, 'b, A: flatbuffers::Allocator + 'a> {
+  fbb_: &'b mut flatbuffers::FlatBufferBuilder<'a, A>,
+  start_: flatbuffers::WIPOffset<flatbuffers::TableUnfinishedWIPOffset>,
+}
+impl<'a: 'b, 'b, A: flatbuffers::Allocator + 'a> AnnotationBuilder<'a, 'b, A> {
+  #[inline]
+  pub fn add_position(&mut self, position: &Point) {
+    self.fbb_.push_slot_always::<&Point>(Annotation::VT_POSITION, position);
+  }
+  #[inline]
+  pub fn add_text(&mut self, text: flatbuffers::WIPOffset<&'b  str>) {
+    self.fbb_.push_slot_always::<flatbuffers::WIPOffset<_>>(Annotation::VT_TEXT, text);
+  }
+  #[inline]
+  pub fn add_font_size(&mut self, font_size: f32) {
+    self.fbb_.push_slot::<f32>(Annotation::VT_FONT_SIZE, font_size, 0.0);
+  }
+  #[inline]
+  pub fn add_color(&mut self, color: &Color) {
+    self.fbb_.push_slot_always::<&Color>(Annotation::VT_COLOR, color);
+  }
+  #[inline]
+  pub fn new(_fbb: &'b mut flatbuffers::FlatBufferBuilder<'a, A>) -> AnnotationBuilder<'a, 'b, A> {
+    let start = _fbb.start_table();
+    AnnotationBuilder {
+      fbb_: _fbb,
+      start_: start,
+    }
+  }
+  #[inline]
+  pub fn finish(self) -> flatbuffers::WIPOffset<Annotation<'a>> {
+    let o = self.fbb_.end_table(self.start_);
+    flatbuffers::WIPOffset::new(o.value())
+  }
+}
+
+impl core::fmt::Debug for Annotation<'_> {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    let mut ds = f.debug_struct("Annotation");
+      ds.field("position", &self.position());
+      ds.field("text", &self.text());
+      ds.field("font_size", &self.font_size());
+      ds.field("color", &self.color());
+      ds.finish()
+  }
+}
+pub enum LayerEntityOffset {}
+#[derive(Copy, Clone, PartialEq)]
+
+pub struct LayerEntity<'a> {
+  pub _tab: flatbuffers::Table<'a>,
+}
+
+impl<'a> flatbuffers::Follow<'a> for LayerEntity<'a> {
+  type Inner = LayerEntity<'a>;
+  #[inline]
+  unsafe fn follow(buf: &'a [u8], loc: usize) -> Self::Inner {
+    Self { _tab: unsafe { flatbuffers::Table::new(buf, loc) } }
+  }
+}
+
+impl<'a> LayerEntity<'a> {
+  pub const VT_ID: flatbuffers::VOffsetT = 4;
+  pub const VT_GEOMETRY_TYPE: flatbuffers::VOffsetT = 6;
+  pub const VT_GEOMETRY: flatbuffers::VOffsetT = 8;
+
+  #[inline]
+  pub unsafe fn init_from_table(table: flatbuffers::Table<'a>) -> Self {
+    LayerEntity { _tab: table }
+  }
+  #[allow(unused_mut)]
+  pub fn create<'bldr: 'args, 'args: 'mut_bldr, 'mut_bldr, A: flatbuffers::Allocator + 'bldr>(
+    _fbb: &'mut_bldr mut flatbuffers::FlatBufferBuilder<'bldr, A>,
+    args: &'args LayerEntityArgs
+  ) -> flatbuffers::WIPOffset<LayerEntity<'bldr>> {
+    let mut builder = LayerEntityBuilder::new(_fbb);
+    builder.add_id(args.id);
+    if let Some(x) = args.geometry { builder.add_geometry(x); }
+    builder.add_geometry_type(args.geometry_type);
+    builder.finish()
+  }
+
+
+  #[inline]
+  pub fn id(&self) -> u64 {
+    // Safety:
+    // Created from valid Table for this object
+    // which contains a valid value in this slot
+    unsafe { self._tab.get::<u64>(LayerEntity::VT_ID, Some(0)).unwrap()}
+  }
+
+  #[inline]
+  pub fn geometry_type(&self) -> Geometry {
+    // Safety:
+    // Created from valid Table for this object
+    // which contains a valid value in this slot
+    unsafe { self._tab.get::<Geometry>(LayerEntity::VT_GEOMETRY_TYPE, Some(Geometry::NONE)).unwrap()}
+  }
+
+  #[inline]
+  pub fn geometry(&self) -> Option<flatbuffers::Table<'a>> {
+    // Safety:
+    // Created from valid Table for this object
+    // which contains a valid value in this slot
+    unsafe { self._tab.get::<flatbuffers::ForwardsUOffset<flatbuffers::Table<'a>>>(LayerEntity::VT_GEOMETRY, None)}
+  }
+  #[inline]
+  #[allow(non_snake_case)]
+  pub fn geometry_as_polyline(&self) -> Option<Polyline<'a>> {
+    if self.geometry_type() == Geometry::Polyline {
+      self.geometry().map(|t| {
+       // Safety:
+       // Created from a valid Table for this object
+       // Which contains a valid union in this slot
+       unsafe { Polyline::init_from_table(t) }
+     })
+    } else {
+      None
+    }
+  }
+
+  #[inline]
+  #[allow(non_snake_case)]
+  pub fn geometry_as_polygon(&self) -> Option<Polygon<'a>> {
+    if self.geometry_type() == Geometry::Polygon {
+      self.geometry().map(|t| {
+       // Safety:
+       // Created from a valid Table for this object
+       // Which contains a valid union in this slot
+       unsafe { Polygon::init_from_table(t) }
+     })
+    } else {
+      None
+    }
+  }
+
+  #[inline]
+  #[allow(non_snake_case)]
+  pub fn geometry_as_annotation(&self) -> Option<Annotation<'a>> {
+    if self.geometry_type() == Geometry::Annotation {
+      self.geometry().map(|t| {
+       // Safety:
+       // Created from a valid Table for this object
+       // Which contains a valid union in this slot
+       unsafe { Annotation::init_from_table(t) }
+     })
+    } else {
+      None
+    }
+  }
+
+}
+
+impl flatbuffers::Verifiable for LayerEntity<'_> {
+  #[inline]
+  fn run_verifier(
+    v: &mut flatbuffers::Verifier, pos: usize
+  ) -> Result<(), flatbuffers::InvalidFlatbuffer> {
+    use self::flatbuffers::Verifiable;
+    v.visit_table(pos)?
+     .visit_field::<u64>("id", Self::VT_ID, false)?
+     .visit_union::<Geometry, _>("geometry_type", Self::VT_GEOMETRY_TYPE, "geometry", Self::VT_GEOMETRY, false, |key, v, pos| {
+        match key {
+          Geometry::Polyline => v.verify_union_variant::<flatbuffers::ForwardsUOffset<Polyline>>("Geometry::Polyline", pos),
+          Geometry::Polygon => v.verify_union_variant::<flatbuffers::ForwardsUOffset<Polygon>>("Geometry::Polygon", pos),
+          Geometry::Annotation => v.verify_union_variant::<flatbuffers::ForwardsUOffset<Annotation>>("Geometry::Annotation", pos),
+          _ => Ok(()),
+        }
+     })?
+     .finish();
+    Ok(())
+  }
+}
+pub struct LayerEntityArgs {
+    pub id: u64,
+    pub geometry_type: Geometry,
+    pub geometry: Option<flatbuffers::WIPOffset<flatbuffers::UnionWIPOffset>>,
+}
+impl<'a> Default for LayerEntityArgs {
+  #[inline]
+  fn default() -> Self {
+    LayerEntityArgs {
+      id: 0,
+      geometry_type: Geometry::NONE,
+      geometry: None,
+    }
+  }
+}
+
+pub struct LayerEntityBuilder<'a: 'b, 'b, A: flatbuffers::Allocator + 'a> {
+  fbb_: &'b mut flatbuffers::FlatBufferBuilder<'a, A>,
+  start_: flatbuffers::WIPOffset<flatbuffers::TableUnfinishedWIPOffset>,
+}
+impl<'a: 'b, 'b, A: flatbuffers::Allocator + 'a> LayerEntityBuilder<'a, 'b, A> {
+  #[inline]
+  pub fn add_id(&mut self, id: u64) {
+    self.fbb_.push_slot::<u64>(LayerEntity::VT_ID, id, 0);
+  }
+  #[inline]
+  pub fn add_geometry_type(&mut self, geometry_type: Geometry) {
+    self.fbb_.push_slot::<Geometry>(LayerEntity::VT_GEOMETRY_TYPE, geometry_type, Geometry::NONE);
+  }
+  #[inline]
+  pub fn add_geometry(&mut self, geometry: flatbuffers::WIPOffset<flatbuffers::UnionWIPOffset>) {
+    self.fbb_.push_slot_always::<flatbuffers::WIPOffset<_>>(LayerEntity::VT_GEOMETRY, geometry);
+  }
+  #[inline]
+  pub fn new(_fbb: &'b mut flatbuffers::FlatBufferBuilder<'a, A>) -> LayerEntityBuilder<'a, 'b, A> {
+    let start = _fbb.start_table();
+    LayerEntityBuilder {
+      fbb_: _fbb,
+      start_: start,
+    }
+  }
+  #[inline]
+  pub fn finish(self) -> flatbuffers::WIPOffset<LayerEntity<'a>> {
+    let o = self.fbb_.end_table(self.start_);
+    flatbuffers::WIPOffset::new(o.value())
+  }
+}
+
+impl core::fmt::Debug for LayerEntity<'_> {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    let mut ds = f.debug_struct("LayerEntity");
+      ds.field("id", &self.id());
+      ds.field("geometry_type", &self.geometry_type());
+      match self.geometry_type() {
+        Geometry::Polyline => {
+          if let Some(x) = self.geometry_as_polyline() {
+            ds.field("geometry", &x)
+          } else {
+            ds.field("geometry", &"InvalidFlatbuffer: Union discriminant does not match value.")
+          }
+        },
+        Geometry::Polygon => {
+          if let Some(x) = self.geometry_as_polygon() {
+            ds.field("geometry", &x)
+          } else {
+            ds.field("geometry", &"InvalidFlatbuffer: Union discriminant does not match value.")
+          }
+        },
+        Geometry::Annotation => {
+          if let Some(x) = self.geometry_as_annotation() {
+            ds.field("geometry", &x)
+          } else {
+            ds.field("geometry", &"InvalidFlatbuffer: Union discriminant does not match value.")
+          }
+        },
+        _ => {
+          let x: Option<()> = None;
+          ds.field("geometry", &x)
+        },
+      };
+      ds.finish()
+  }
+}
+pub enum ViewportResponseOffset {}
+#[derive(Copy, Clone, PartialEq)]
+
+pub struct ViewportResponse<'a> {
+  pub _tab: flatbuffers::Table<'a>,
+}
+
+impl<'a> flatbuffers::Follow<'a> for ViewportResponse<'a> {
+  type Inner = ViewportResponse<'a>;
+  #[inline]
+  unsafe fn follow(buf: &'a [u8], loc: usize) -> Self::Inner {
+    Self { _tab: unsafe { flatbuffers::Table::new(buf, loc) } }
+  }
+}
+
+impl<'a> ViewportResponse<'a> {
+  pub const VT_ENTITIES: flatbuffers::VOffsetT = 4;
+  pub const VT_PAGE: flatbuffers::VOffsetT = 6;
+  pub const VT_SEGMENTS: flatbuffers::VOffsetT = 8;
+  pub const VT_CURVES: flatbuffers::VOffsetT = 10;
+
+  #[inline]
+  pub unsafe fn init_from_table(table: flatbuffers::Table<'a>) -> Self {
+    ViewportResponse { _tab: table }
+  }
+  #[allow(unused_mut)]
+  pub fn create<'bldr: 'args, 'args: 'mut_bldr, 'mut_bldr, A: flatbuffers::Allocator + 'bldr>(
+    _fbb: &'mut_bldr mut flatbuffers::FlatBufferBuilder<'bldr, A>,
+    args: &'args ViewportResponseArgs<'args>
+  ) -> flatbuffers::WIPOffset<ViewportResponse<'bldr>> {
+    let mut builder = ViewportResponseBuilder::new(_fbb);
+    if let Some(x) = args.curves { builder.add_curves(x); }
+    if let Some(x) = args.segments { builder.add_segments(x); }
+    builder.add_page(args.page);
+    if let Some(x) = args.entities { builder.add_entities(x); }
+    builder.finish()
+  }
+
+
+  #[inline]
+  pub fn entities(&self) -> Option<flatbuffers::Vector<'a, flatbuffers::ForwardsUOffset<LayerEntity<'a>>>> {
+    // Safety:
+    // Created from valid Table for this object
+    // which contains a valid value in this slot
+    unsafe { self._tab.get::<flatbuffers::ForwardsUOffset<flatbuffers::Vector<'a, flatbuffers::ForwardsUOffset<LayerEntity>>>>(ViewportResponse::VT_ENTITIES, None)}
+  }
+
+  #[inline]
+  pub fn page(&self) -> i32 {
+    // Safety:
+    // Created from valid Table for this object
+    // which contains a valid value in this slot
+    unsafe { self._tab.get::<i32>(ViewportResponse::VT_PAGE, Some(0)).unwrap()}
+  }
+
+  #[inline]
+  pub fn segments(&self) -> Option<flatbuffers::Vector<'a, Segment>> {
+    // Safety:
+    // Created from valid Table for this object
+    // which contains a valid value in this slot
+    unsafe { self._tab.get::<flatbuffers::ForwardsUOffset<flatbuffers::Vector<'a, Segment>>>(ViewportResponse::VT_SEGMENTS, None)}
+  }
+
+  #[inline]
+  pub fn curves(&self) -> Option<flatbuffers::Vector<'a, Bezier>> {
+    // Safety:
+    // Created from valid Table for this object
+    // which contains a valid value in this slot
+    unsafe { self._tab.get::<flatbuffers::ForwardsUOffset<flatbuffers::Vector<'a, Bezier>>>(ViewportResponse::VT_CURVES, None)}
+  }
+}
+
+impl flatbuffers::Verifiable for ViewportResponse<'_> {
+  #[inline]
+  fn run_verifier(
+    v: &mut flatbuffers::Verifier, pos: usize
+  ) -> Result<(), flatbuffers::InvalidFlatbuffer> {
+    use self::flatbuffers::Verifiable;
+    v.visit_table(pos)?
+     .visit_field::<flatbuffers::ForwardsUOffset<flatbuffers::Vector<'_, flatbuffers::ForwardsUOffset<LayerEntity>>>>("entities", Self::VT_ENTITIES, false)?
+     .visit_field::<i32>("page", Self::VT_PAGE, false)?
+     .visit_field::<flatbuffers::ForwardsUOffset<flatbuffers::Vector<'_, Segment>>>("segments", Self::VT_SEGMENTS, false)?
+     .visit_field::<flatbuffers::ForwardsUOffset<flatbuffers::Vector<'_, Bezier>>>("curves", Self::VT_CURVES, false)?
+     .finish();
+    Ok(())
+  }
+}
+pub struct ViewportResponseArgs<'a> {
+    pub entities: Option<flatbuffers::WIPOffset<flatbuffers::Vector<'a, flatbuffers::ForwardsUOffset<LayerEntity<'a>>>>>,
+    pub page: i32,
+    pub segments: Option<flatbuffers::WIPOffset<flatbuffers::Vector<'a, Segment>>>,
+    pub curves: Option<flatbuffers::WIPOffset<flatbuffers::Vector<'a, Bezier>>>,
+}
+impl<'a> Default for ViewportResponseArgs<'a> {
+  #[inline]
+  fn default() -> Self {
+    ViewportResponseArgs {
+      entities: None,
+      page: 0,
+      segments: None,
+      curves: None,
+    }
+  }
+}
+
+pub struct ViewportResponseBuilder<'a: 'b, 'b, A: flatbuffers::Allocator + 'a> {
+  fbb_: &'b mut flatbuffers::FlatBufferBuilder<'a, A>,
+  start_: flatbuffers::WIPOffset<flatbuffers::TableUnfinishedWIPOffset>,
+}
+impl<'a: 'b, 'b, A: flatbuffers::Allocator + 'a> ViewportResponseBuilder<'a, 'b, A> {
+  #[inline]
+  pub fn add_entities(&mut self, entities: flatbuffers::WIPOffset<flatbuffers::Vector<'b , flatbuffers::ForwardsUOffset<LayerEntity<'b >>>>) {
+    self.fbb_.push_slot_always::<flatbuffers::WIPOffset<_>>(ViewportResponse::VT_ENTITIES, entities);
+  }
+  #[inline]
+  pub fn add_page(&mut self, page: i32) {
+    self.fbb_.push_slot::<i32>(ViewportResponse::VT_PAGE, page, 0);
+  }
+  #[inline]
+  pub fn add_segments(&mut self, segments: flatbuffers::WIPOffset<flatbuffers::Vector<'b , Segment>>) {
+    self.fbb_.push_slot_always::<flatbuffers::WIPOffset<_>>(ViewportResponse::VT_SEGMENTS, segments);
+  }
+  #[inline]
+  pub fn add_curves(&mut self, curves: flatbuffers::WIPOffset<flatbuffers::Vector<'b , Bezier>>) {
+    self.fbb_.push_slot_always::<flatbuffers::WIPOffset<_>>(ViewportResponse::VT_CURVES, curves);
+  }
+  #[inline]
+  pub fn new(_fbb: &'b mut flatbuffers::FlatBufferBuilder<'a, A>) -> ViewportResponseBuilder<'a, 'b, A> {
+    let start = _fbb.start_table();
+    ViewportResponseBuilder {
+      fbb_: _fbb,
+      start_: start,
+    }
+  }
+  #[inline]
+  pub fn finish(self) -> flatbuffers::WIPOffset<ViewportResponse<'a>> {
+    let o = self.fbb_.end_table(self.start_);
+    flatbuffers::WIPOffset::new(o.value())
+  }
+}
+
+impl core::fmt::Debug for ViewportResponse<'_> {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    let mut ds = f.debug_struct("ViewportResponse");
+      ds.field("entities", &self.entities());
+      ds.field("page", &self.page());
+      ds.field("segments", &self.segments());
+      ds.field("curves", &self.curves());
+      ds.finish()
+  }
+}
+#[inline]
+/// Verifies that a buffer of bytes contains a `ViewportResponse`
+/// and returns it.
+/// Note that verification is still experimental and may not
+/// catch every error, or be maximally performant. For the
+/// previous, unchecked, behavior use
+/// `root_as_viewport_response_unchecked`.
+pub fn root_as_viewport_response(buf: &[u8]) -> Result<ViewportResponse<'_>, flatbuffers::InvalidFlatbuffer> {
+  flatbuffers::root::<ViewportResponse>(buf)
+}
+#[inline]
+/// Verifies that a buffer of bytes contains a size prefixed
+/// `ViewportResponse` and returns it.
+/// Note that verification is still experimental and may not
+/// catch every error, or be maximally performant. For the
+/// previous, unchecked, behavior use
+/// `size_prefixed_root_as_viewport_response_unchecked`.
+pub fn size_prefixed_root_as_viewport_response(buf: &[u8]) -> Result<ViewportResponse<'_>, flatbuffers::InvalidFlatbuffer> {
+  flatbuffers::size_prefixed_root::<ViewportResponse>(buf)
+}
+#[inline]
+/// Verifies, with the given options, that a buffer of bytes
+/// contains a `ViewportResponse` and returns it.
+/// Note that verification is still experimental and may not
+/// catch every error, or be maximally performant. For the
+/// previous, unchecked, behavior use
+/// `root_as_viewport_response_unchecked`.
+pub fn root_as_viewport_response_with_opts<'b, 'o>(
+  opts: &'o flatbuffers::VerifierOptions,
+  buf: &'b [u8],
+) -> Result<ViewportResponse<'b>, flatbuffers::InvalidFlatbuffer> {
+  flatbuffers::root_with_opts::<ViewportResponse<'b>>(opts, buf)
+}
+#[inline]
+/// Verifies, with the given verifier options, that a buffer of
+/// bytes contains a size prefixed `ViewportResponse` and returns
+/// it. Note that verification is still experimental and may not
+/// catch every error, or be maximally performant. For the
+/// previous, unchecked, behavior use
+/// `root_as_viewport_response_unchecked`.
+pub fn size_prefixed_root_as_viewport_response_with_opts<'b, 'o>(
+  opts: &'o flatbuffers::VerifierOptions,
+  buf: &'b [u8],
+) -> Result<ViewportResponse<'b>, flatbuffers::InvalidFlatbuffer> {
+  flatbuffers::size_prefixed_root_with_opts::<ViewportResponse<'b>>(opts, buf)
+}
+#[inline]
+/// Assumes, without verification, that a buffer of bytes contains a ViewportResponse and returns it.
+/// # Safety
+/// Callers must trust the given bytes do indeed contain a valid `ViewportResponse`.
+pub unsafe fn root_as_viewport_response_unchecked(buf: &[u8]) -> ViewportResponse<'_> {
+  unsafe { flatbuffers::root_unchecked::<ViewportResponse>(buf) }
+}
+#[inline]
+/// Assumes, without verification, that a buffer of bytes contains a size prefixed ViewportResponse and returns it.
+/// # Safety
+/// Callers must trust the given bytes do indeed contain a valid size prefixed `ViewportResponse`.
+pub unsafe fn size_prefixed_root_as_viewport_response_unchecked(buf: &[u8]) -> ViewportResponse<'_> {
+  unsafe { flatbuffers::size_prefixed_root_unchecked::<ViewportResponse>(buf) }
+}
+#[inline]
+pub fn finish_viewport_response_buffer<'a, 'b, A: flatbuffers::Allocator + 'a>(
+    fbb: &'b mut flatbuffers::FlatBufferBuilder<'a, A>,
+    root: flatbuffers::WIPOffset<ViewportResponse<'a>>) {
+  fbb.finish(root, None);
+}
+
+#[inline]
+pub fn finish_size_prefixed_viewport_response_buffer<'a, 'b, A: flatbuffers::Allocator + 'a>(fbb: &'b mut flatbuffers::FlatBufferBuilder<'a, A>, root: flatbuffers::WIPOffset<ViewportResponse<'a>>) {
+  fbb.finish_size_prefixed(root, None);
+}
+}  // pub mod pro_takeoff
+